@@ -0,0 +1,2 @@
+-- Used to resume import tasks and to count imported books.
+CREATE INDEX IF NOT EXISTS import_books_library_calibre_book_idx ON import_books (library, calibre_book);
@@ -0,0 +1,2 @@
+-- Reason why a calibre book is not imported as a new book, like duplicated uuid.
+ALTER TABLE import_books ADD COLUMN IF NOT EXISTS skip_reason TEXT;
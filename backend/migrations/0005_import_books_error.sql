@@ -0,0 +1,3 @@
+-- Reason why a calibre book failed to import.
+ALTER TABLE import_books ADD COLUMN IF NOT EXISTS error_kind TEXT;
+ALTER TABLE import_books ADD COLUMN IF NOT EXISTS error_message TEXT;
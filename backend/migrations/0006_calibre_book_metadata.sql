@@ -0,0 +1,5 @@
+-- Keep title sort and series index of books imported from calibre.
+ALTER TABLE books ADD COLUMN IF NOT EXISTS title_sort TEXT NOT NULL DEFAULT '';
+UPDATE books SET title_sort = title WHERE title_sort = '';
+ALTER TABLE books_series_link ADD COLUMN IF NOT EXISTS series_index REAL NOT NULL DEFAULT 1.0;
+CREATE INDEX IF NOT EXISTS books_series_link_series_index_idx ON books_series_link (series, series_index);
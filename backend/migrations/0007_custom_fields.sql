@@ -0,0 +1,30 @@
+-- Definitions of custom fields, imported from calibre custom columns and plugin data.
+CREATE TABLE IF NOT EXISTS custom_fields
+(
+    id SERIAL PRIMARY KEY,
+    label TEXT NOT NULL,
+    name TEXT NOT NULL,
+    datatype TEXT NOT NULL,
+    is_multiple BOOLEAN NOT NULL DEFAULT FALSE,
+    display TEXT NOT NULL DEFAULT '{}',
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_modified TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (label)
+);
+
+-- Values of custom fields, only the column matching datatype of field is set.
+CREATE TABLE IF NOT EXISTS custom_field_values
+(
+    id SERIAL PRIMARY KEY,
+    book INTEGER NOT NULL,
+    field INTEGER NOT NULL,
+    text_value TEXT,
+    int_value BIGINT,
+    float_value DOUBLE PRECISION,
+    bool_value BOOLEAN,
+    date_value TIMESTAMP,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS custom_field_values_book_idx ON custom_field_values (book);
+CREATE INDEX IF NOT EXISTS custom_field_values_field_idx ON custom_field_values (field);
@@ -0,0 +1,4 @@
+-- Used by `sync-library` to detect changes made in calibre and in pengzu.
+ALTER TABLE import_books ADD COLUMN IF NOT EXISTS calibre_last_modified TIMESTAMP;
+ALTER TABLE import_books ADD COLUMN IF NOT EXISTS synced_metadata TEXT;
+ALTER TABLE import_libraries ADD COLUMN IF NOT EXISTS last_synced TIMESTAMP;
@@ -0,0 +1,15 @@
+-- Files ingested by `watch-folder`, one row per file.
+CREATE TABLE IF NOT EXISTS import_logs
+(
+    id SERIAL PRIMARY KEY,
+    path TEXT NOT NULL,
+    moved_to TEXT,
+    ok BOOLEAN NOT NULL,
+    book INTEGER,
+    skip_reason TEXT,
+    error_kind TEXT,
+    error_message TEXT,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS import_logs_created_idx ON import_logs (created);
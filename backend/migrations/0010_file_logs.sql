@@ -0,0 +1,15 @@
+-- Book files added, replaced or deleted from web api, one row per change.
+CREATE TABLE IF NOT EXISTS file_logs
+(
+    id SERIAL PRIMARY KEY,
+    book INTEGER NOT NULL,
+    file INTEGER NOT NULL,
+    format INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    old_size INTEGER,
+    new_size INTEGER,
+    user_id INTEGER NOT NULL,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS file_logs_book_idx ON file_logs (book);
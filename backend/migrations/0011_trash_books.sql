@@ -0,0 +1,19 @@
+-- Books deleted from web api, they can be restored until purged.
+CREATE TABLE IF NOT EXISTS trash_books
+(
+    id SERIAL PRIMARY KEY,
+    -- Id of book before it was deleted, it is reused on restore.
+    book INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    -- Directory of book files in trash, relative to library root.
+    path TEXT NOT NULL,
+    -- Row of book in `books` table, in json.
+    record TEXT NOT NULL,
+    -- Rows referring to book in other tables, in json.
+    links TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS trash_books_book_idx ON trash_books (book);
+CREATE INDEX IF NOT EXISTS trash_books_created_idx ON trash_books (created);
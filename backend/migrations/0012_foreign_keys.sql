@@ -0,0 +1,150 @@
+-- Remove rows referring to missing records, and then add foreign keys.
+--
+-- Rows of a book are removed with it. Links to authors, publishers, series,
+-- tags, categories, user tags and custom fields are removed with them.
+-- File formats, identifier types and languages still in use can not be removed.
+
+-- Rows referring to missing books.
+DELETE FROM books_authors_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_categories_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_languages_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_publishers_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_series_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_tags_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM books_user_tags_link WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM comments WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM custom_field_values WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM download_history WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM files WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM identifiers WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM ratings WHERE book NOT IN (SELECT id FROM books);
+DELETE FROM reading_history WHERE book NOT IN (SELECT id FROM books);
+UPDATE import_books SET book = NULL WHERE book NOT IN (SELECT id FROM books);
+UPDATE import_logs SET book = NULL WHERE book NOT IN (SELECT id FROM books);
+
+-- Rows referring to other missing records.
+DELETE FROM books_authors_link WHERE author NOT IN (SELECT id FROM authors);
+DELETE FROM books_categories_link WHERE category NOT IN (SELECT id FROM categories);
+DELETE FROM books_languages_link WHERE language NOT IN (SELECT id FROM languages);
+DELETE FROM books_publishers_link WHERE publisher NOT IN (SELECT id FROM publishers);
+DELETE FROM books_series_link WHERE series NOT IN (SELECT id FROM series);
+DELETE FROM books_tags_link WHERE tag NOT IN (SELECT id FROM tags);
+DELETE FROM books_user_tags_link WHERE tag NOT IN (SELECT id FROM user_tags);
+DELETE FROM books_user_tags_link WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM user_tags WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM custom_field_values WHERE field NOT IN (SELECT id FROM custom_fields);
+DELETE FROM download_history WHERE file NOT IN (SELECT id FROM files);
+DELETE FROM download_history WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM reading_history WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM files WHERE format NOT IN (SELECT id FROM file_formats);
+DELETE FROM identifiers WHERE scheme NOT IN (SELECT id FROM identifier_types);
+DELETE FROM import_books WHERE library NOT IN (SELECT id FROM import_libraries);
+
+-- Foreign keys to books.
+ALTER TABLE books_authors_link DROP CONSTRAINT IF EXISTS books_authors_link_book_fkey;
+ALTER TABLE books_authors_link ADD CONSTRAINT books_authors_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_categories_link DROP CONSTRAINT IF EXISTS books_categories_link_book_fkey;
+ALTER TABLE books_categories_link ADD CONSTRAINT books_categories_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_languages_link DROP CONSTRAINT IF EXISTS books_languages_link_book_fkey;
+ALTER TABLE books_languages_link ADD CONSTRAINT books_languages_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_publishers_link DROP CONSTRAINT IF EXISTS books_publishers_link_book_fkey;
+ALTER TABLE books_publishers_link ADD CONSTRAINT books_publishers_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_series_link DROP CONSTRAINT IF EXISTS books_series_link_book_fkey;
+ALTER TABLE books_series_link ADD CONSTRAINT books_series_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_tags_link DROP CONSTRAINT IF EXISTS books_tags_link_book_fkey;
+ALTER TABLE books_tags_link ADD CONSTRAINT books_tags_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE books_user_tags_link DROP CONSTRAINT IF EXISTS books_user_tags_link_book_fkey;
+ALTER TABLE books_user_tags_link ADD CONSTRAINT books_user_tags_link_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE comments DROP CONSTRAINT IF EXISTS comments_book_fkey;
+ALTER TABLE comments ADD CONSTRAINT comments_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE custom_field_values DROP CONSTRAINT IF EXISTS custom_field_values_book_fkey;
+ALTER TABLE custom_field_values ADD CONSTRAINT custom_field_values_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE download_history DROP CONSTRAINT IF EXISTS download_history_book_fkey;
+ALTER TABLE download_history ADD CONSTRAINT download_history_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE files DROP CONSTRAINT IF EXISTS files_book_fkey;
+ALTER TABLE files ADD CONSTRAINT files_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE identifiers DROP CONSTRAINT IF EXISTS identifiers_book_fkey;
+ALTER TABLE identifiers ADD CONSTRAINT identifiers_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE ratings DROP CONSTRAINT IF EXISTS ratings_book_fkey;
+ALTER TABLE ratings ADD CONSTRAINT ratings_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE reading_history DROP CONSTRAINT IF EXISTS reading_history_book_fkey;
+ALTER TABLE reading_history ADD CONSTRAINT reading_history_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE CASCADE;
+ALTER TABLE import_books DROP CONSTRAINT IF EXISTS import_books_book_fkey;
+ALTER TABLE import_books ADD CONSTRAINT import_books_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE SET NULL;
+ALTER TABLE import_logs DROP CONSTRAINT IF EXISTS import_logs_book_fkey;
+ALTER TABLE import_logs ADD CONSTRAINT import_logs_book_fkey
+    FOREIGN KEY (book) REFERENCES books (id) ON DELETE SET NULL;
+
+-- Foreign keys to other records.
+ALTER TABLE books_authors_link DROP CONSTRAINT IF EXISTS books_authors_link_author_fkey;
+ALTER TABLE books_authors_link ADD CONSTRAINT books_authors_link_author_fkey
+    FOREIGN KEY (author) REFERENCES authors (id) ON DELETE CASCADE;
+ALTER TABLE books_categories_link DROP CONSTRAINT IF EXISTS books_categories_link_category_fkey;
+ALTER TABLE books_categories_link ADD CONSTRAINT books_categories_link_category_fkey
+    FOREIGN KEY (category) REFERENCES categories (id) ON DELETE CASCADE;
+ALTER TABLE books_languages_link DROP CONSTRAINT IF EXISTS books_languages_link_language_fkey;
+ALTER TABLE books_languages_link ADD CONSTRAINT books_languages_link_language_fkey
+    FOREIGN KEY (language) REFERENCES languages (id) ON DELETE RESTRICT;
+ALTER TABLE books_publishers_link DROP CONSTRAINT IF EXISTS books_publishers_link_publisher_fkey;
+ALTER TABLE books_publishers_link ADD CONSTRAINT books_publishers_link_publisher_fkey
+    FOREIGN KEY (publisher) REFERENCES publishers (id) ON DELETE CASCADE;
+ALTER TABLE books_series_link DROP CONSTRAINT IF EXISTS books_series_link_series_fkey;
+ALTER TABLE books_series_link ADD CONSTRAINT books_series_link_series_fkey
+    FOREIGN KEY (series) REFERENCES series (id) ON DELETE CASCADE;
+ALTER TABLE books_tags_link DROP CONSTRAINT IF EXISTS books_tags_link_tag_fkey;
+ALTER TABLE books_tags_link ADD CONSTRAINT books_tags_link_tag_fkey
+    FOREIGN KEY (tag) REFERENCES tags (id) ON DELETE CASCADE;
+ALTER TABLE books_user_tags_link DROP CONSTRAINT IF EXISTS books_user_tags_link_tag_fkey;
+ALTER TABLE books_user_tags_link ADD CONSTRAINT books_user_tags_link_tag_fkey
+    FOREIGN KEY (tag) REFERENCES user_tags (id) ON DELETE CASCADE;
+ALTER TABLE books_user_tags_link DROP CONSTRAINT IF EXISTS books_user_tags_link_user_id_fkey;
+ALTER TABLE books_user_tags_link ADD CONSTRAINT books_user_tags_link_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE user_tags DROP CONSTRAINT IF EXISTS user_tags_user_id_fkey;
+ALTER TABLE user_tags ADD CONSTRAINT user_tags_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE custom_field_values DROP CONSTRAINT IF EXISTS custom_field_values_field_fkey;
+ALTER TABLE custom_field_values ADD CONSTRAINT custom_field_values_field_fkey
+    FOREIGN KEY (field) REFERENCES custom_fields (id) ON DELETE CASCADE;
+ALTER TABLE download_history DROP CONSTRAINT IF EXISTS download_history_file_fkey;
+ALTER TABLE download_history ADD CONSTRAINT download_history_file_fkey
+    FOREIGN KEY (file) REFERENCES files (id) ON DELETE CASCADE;
+ALTER TABLE download_history DROP CONSTRAINT IF EXISTS download_history_user_id_fkey;
+ALTER TABLE download_history ADD CONSTRAINT download_history_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE reading_history DROP CONSTRAINT IF EXISTS reading_history_user_id_fkey;
+ALTER TABLE reading_history ADD CONSTRAINT reading_history_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE files DROP CONSTRAINT IF EXISTS files_format_fkey;
+ALTER TABLE files ADD CONSTRAINT files_format_fkey
+    FOREIGN KEY (format) REFERENCES file_formats (id) ON DELETE RESTRICT;
+ALTER TABLE identifiers DROP CONSTRAINT IF EXISTS identifiers_scheme_fkey;
+ALTER TABLE identifiers ADD CONSTRAINT identifiers_scheme_fkey
+    FOREIGN KEY (scheme) REFERENCES identifier_types (id) ON DELETE RESTRICT;
+ALTER TABLE import_books DROP CONSTRAINT IF EXISTS import_books_library_fkey;
+ALTER TABLE import_books ADD CONSTRAINT import_books_library_fkey
+    FOREIGN KEY (library) REFERENCES import_libraries (id) ON DELETE CASCADE;
+
+-- Foreign keys are not indexed by postgres.
+CREATE INDEX IF NOT EXISTS books_user_tags_link_tag_idx ON books_user_tags_link (tag);
+CREATE INDEX IF NOT EXISTS download_history_book_idx ON download_history (book);
+CREATE INDEX IF NOT EXISTS download_history_file_idx ON download_history (file);
+CREATE INDEX IF NOT EXISTS identifiers_scheme_idx ON identifiers (scheme);
+CREATE INDEX IF NOT EXISTS reading_history_book_idx ON reading_history (book);
+CREATE INDEX IF NOT EXISTS import_books_book_idx ON import_books (book);
+CREATE INDEX IF NOT EXISTS import_logs_book_idx ON import_logs (book);
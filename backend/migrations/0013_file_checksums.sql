@@ -0,0 +1,5 @@
+-- Sha256 checksum of book files, in lowercase hex.
+--
+-- It is NULL for files imported before, until `backend checksum` is run.
+ALTER TABLE files ADD COLUMN IF NOT EXISTS sha256 TEXT;
+CREATE INDEX IF NOT EXISTS files_sha256_idx ON files (sha256);
@@ -0,0 +1,206 @@
+-- Full text search document of each book.
+--
+-- It contains title, authors, series, publishers, tags, comments and identifiers
+-- of book, and is kept up to date by triggers. Runs of Chinese and Japanese
+-- characters are split into overlapping bigrams, as there is no space between
+-- their words.
+CREATE TABLE IF NOT EXISTS book_search
+(
+    book INTEGER PRIMARY KEY REFERENCES books (id) ON DELETE CASCADE,
+    document TSVECTOR NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS book_search_document_idx ON book_search USING GIN (document);
+
+-- Lowercase text, and replace runs of CJK characters with their bigrams.
+CREATE OR REPLACE FUNCTION pengzu_cjk_bigrams(input TEXT) RETURNS TEXT AS
+$$
+DECLARE
+    words TEXT[] := '{}';
+    word TEXT;
+    i INTEGER;
+BEGIN
+    FOREACH word IN ARRAY regexp_split_to_array(
+            regexp_replace(lower(coalesce(input, '')),
+                           '([\u3040-\u30ff\u3400-\u4dbf\u4e00-\u9fff\uf900-\ufaff]+)', ' \1 ', 'g'),
+            '\s+')
+        LOOP
+            IF word ~ '^[\u3040-\u30ff\u3400-\u4dbf\u4e00-\u9fff\uf900-\ufaff]' AND char_length(word) > 1 THEN
+                FOR i IN 1 .. char_length(word) - 1
+                    LOOP
+                        words := words || substr(word, i, 2);
+                    END LOOP;
+            ELSIF word <> '' THEN
+                words := words || word;
+            END IF;
+        END LOOP;
+    RETURN array_to_string(words, ' ');
+END;
+$$ LANGUAGE plpgsql IMMUTABLE;
+
+-- Convert search keywords to a query, every word or bigram is matched as prefix.
+CREATE OR REPLACE FUNCTION pengzu_search_query(input TEXT) RETURNS TSQUERY AS
+$$
+SELECT to_tsquery('simple', coalesce(string_agg(
+        '''' || replace(replace(word, '\', '\\'), '''', '''''') || ''':*', ' & '), ''))
+FROM regexp_split_to_table(pengzu_cjk_bigrams(input), ' ') AS word
+WHERE word <> ''
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Title has the highest weight, then authors, then series, publishers and tags.
+CREATE OR REPLACE FUNCTION pengzu_book_search_document(book_id INTEGER) RETURNS TSVECTOR AS
+$$
+SELECT setweight(to_tsvector('simple', pengzu_cjk_bigrams(books.title)), 'A')
+           || setweight(to_tsvector('simple', pengzu_cjk_bigrams(
+        (SELECT string_agg(authors.name, ' ')
+         FROM books_authors_link
+                  JOIN authors ON authors.id = books_authors_link.author
+         WHERE books_authors_link.book = books.id))), 'B')
+           || setweight(to_tsvector('simple', pengzu_cjk_bigrams(concat_ws(' ',
+        (SELECT string_agg(series.name, ' ')
+         FROM books_series_link
+                  JOIN series ON series.id = books_series_link.series
+         WHERE books_series_link.book = books.id),
+        (SELECT string_agg(publishers.name, ' ')
+         FROM books_publishers_link
+                  JOIN publishers ON publishers.id = books_publishers_link.publisher
+         WHERE books_publishers_link.book = books.id),
+        (SELECT string_agg(tags.name, ' ')
+         FROM books_tags_link
+                  JOIN tags ON tags.id = books_tags_link.tag
+         WHERE books_tags_link.book = books.id)))), 'C')
+           || setweight(to_tsvector('simple', pengzu_cjk_bigrams(concat_ws(' ',
+        (SELECT string_agg(comments.text, ' ') FROM comments WHERE comments.book = books.id),
+        (SELECT string_agg(identifiers.value, ' ')
+         FROM identifiers
+         WHERE identifiers.book = books.id)))), 'D')
+FROM books
+WHERE books.id = book_id
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION pengzu_refresh_book_search(book_id INTEGER) RETURNS VOID AS
+$$
+INSERT INTO book_search (book, document)
+SELECT books.id, pengzu_book_search_document(books.id)
+FROM books
+WHERE books.id = book_id
+ON CONFLICT (book) DO UPDATE SET document = EXCLUDED.document;
+$$ LANGUAGE sql;
+
+-- Refresh search document when book or a row referring to it is changed.
+CREATE OR REPLACE FUNCTION pengzu_book_search_trigger() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_TABLE_NAME = 'books' THEN
+        PERFORM pengzu_refresh_book_search(NEW.id);
+        RETURN NULL;
+    END IF;
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM pengzu_refresh_book_search(OLD.book);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        PERFORM pengzu_refresh_book_search(NEW.book);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Refresh search documents of books when author, publisher, series or tag is renamed.
+CREATE OR REPLACE FUNCTION pengzu_book_search_name_trigger() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_TABLE_NAME = 'authors' THEN
+        PERFORM pengzu_refresh_book_search(book) FROM books_authors_link WHERE author = NEW.id;
+    ELSIF TG_TABLE_NAME = 'publishers' THEN
+        PERFORM pengzu_refresh_book_search(book) FROM books_publishers_link WHERE publisher = NEW.id;
+    ELSIF TG_TABLE_NAME = 'series' THEN
+        PERFORM pengzu_refresh_book_search(book) FROM books_series_link WHERE series = NEW.id;
+    ELSIF TG_TABLE_NAME = 'tags' THEN
+        PERFORM pengzu_refresh_book_search(book) FROM books_tags_link WHERE tag = NEW.id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS book_search_trigger ON books;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OF title
+    ON books
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON books_authors_link;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON books_authors_link
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON books_publishers_link;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON books_publishers_link
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON books_series_link;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON books_series_link
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON books_tags_link;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON books_tags_link
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON comments;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON comments
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_trigger ON identifiers;
+CREATE TRIGGER book_search_trigger
+    AFTER INSERT OR UPDATE OR DELETE
+    ON identifiers
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_trigger();
+
+DROP TRIGGER IF EXISTS book_search_name_trigger ON authors;
+CREATE TRIGGER book_search_name_trigger
+    AFTER UPDATE OF name
+    ON authors
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_name_trigger();
+
+DROP TRIGGER IF EXISTS book_search_name_trigger ON publishers;
+CREATE TRIGGER book_search_name_trigger
+    AFTER UPDATE OF name
+    ON publishers
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_name_trigger();
+
+DROP TRIGGER IF EXISTS book_search_name_trigger ON series;
+CREATE TRIGGER book_search_name_trigger
+    AFTER UPDATE OF name
+    ON series
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_name_trigger();
+
+DROP TRIGGER IF EXISTS book_search_name_trigger ON tags;
+CREATE TRIGGER book_search_name_trigger
+    AFTER UPDATE OF name
+    ON tags
+    FOR EACH ROW
+EXECUTE FUNCTION pengzu_book_search_name_trigger();
+
+-- Documents of existing books.
+INSERT INTO book_search (book, document)
+SELECT books.id, pengzu_book_search_document(books.id)
+FROM books
+ON CONFLICT (book) DO UPDATE SET document = EXCLUDED.document;
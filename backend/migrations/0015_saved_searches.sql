@@ -0,0 +1,12 @@
+-- Searches saved by users, they are evaluated again each time books are listed.
+CREATE TABLE IF NOT EXISTS saved_searches
+(
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- Advanced search conditions or query of search box, in json.
+    query TEXT NOT NULL,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_modified TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, name)
+);
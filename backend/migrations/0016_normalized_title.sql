@@ -0,0 +1,9 @@
+-- Lowercase title, replace runs of whitespaces and ascii punctuations with one space.
+--
+-- Used to find duplicated books, the same as `normalize_name()` in backend.
+CREATE OR REPLACE FUNCTION pengzu_normalize_title(title TEXT) RETURNS TEXT AS
+$$
+SELECT btrim(regexp_replace(lower(title), '[[:space:]!-/:-@[-`{-~]+', ' ', 'g'));
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+CREATE INDEX IF NOT EXISTS books_normalized_title_idx ON books (pengzu_normalize_title(title));
@@ -0,0 +1,10 @@
+-- Trigram indexes of names matched by search-as-you-type suggestions,
+-- so that `ILIKE` with leading wildcard does not scan the whole table.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS books_title_trgm_idx ON books USING GIN (title gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS authors_name_trgm_idx ON authors USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS publishers_name_trgm_idx ON publishers USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS series_name_trgm_idx ON series USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS tags_name_trgm_idx ON tags USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS categories_name_trgm_idx ON categories USING GIN (name gin_trgm_ops);
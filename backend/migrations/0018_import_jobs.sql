@@ -0,0 +1,23 @@
+-- Import jobs started from web api, kept after server restarts.
+CREATE TABLE IF NOT EXISTS import_jobs
+(
+    id SERIAL PRIMARY KEY,
+    -- 0: calibre library, 1: folder.
+    kind INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    -- Request of job in json, empty for calibre libraries imported from command line.
+    request TEXT NOT NULL,
+    -- Only set for calibre library.
+    library INTEGER REFERENCES import_libraries (id) ON DELETE SET NULL,
+    -- 0: pending, 1: running, 2: finished, 3: failed, 4: cancelled.
+    status INTEGER NOT NULL DEFAULT 0,
+    total BIGINT NOT NULL DEFAULT 0,
+    done BIGINT NOT NULL DEFAULT 0,
+    failed BIGINT NOT NULL DEFAULT 0,
+    message TEXT,
+    created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_modified TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS import_jobs_library_idx ON import_jobs (library);
+CREATE INDEX IF NOT EXISTS import_jobs_status_idx ON import_jobs (status);
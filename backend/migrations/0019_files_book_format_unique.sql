@@ -0,0 +1,7 @@
+-- Each book has at most one file in each format.
+-- Files of the same book share the same name, so that duplicated rows refer to
+-- the same file in library, and only the first one is kept.
+DELETE FROM files a USING files b
+WHERE a.book = b.book AND a.format = b.format AND a.id > b.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS files_book_format_idx ON files (book, format);
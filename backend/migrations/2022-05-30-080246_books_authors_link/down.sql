@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP TABLE books_authors_link;
@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP TABLE books_languages_link;
\ No newline at end of file
@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP TABLE douban_authors;
\ No newline at end of file
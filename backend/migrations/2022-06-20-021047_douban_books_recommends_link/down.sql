@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP TABLE douban_books_recommends_link;
\ No newline at end of file
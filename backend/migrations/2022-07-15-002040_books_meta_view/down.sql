@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP VIEW books_meta;
\ No newline at end of file
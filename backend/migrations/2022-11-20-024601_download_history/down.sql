@@ -1,3 +0,0 @@
--- This file should undo anything in `up.sql`
-
-DROP TABLE download_history;
\ No newline at end of file
@@ -0,0 +1,61 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::db::get_connection_pool;
+use crate::error::Error;
+use crate::import::checksums::{backfill_checksums, get_duplicate_files, verify_checksums};
+use crate::settings::get_library_path;
+
+pub const CMD_CHECKSUM: &str = "checksum";
+const OPT_VERIFY: &str = "verify";
+const OPT_DUPLICATES: &str = "duplicates";
+
+#[must_use]
+pub fn new_cmd() -> Command {
+    Command::new(CMD_CHECKSUM)
+        .about("Compute sha256 checksums of book files which have none")
+        .arg(
+            Arg::new(OPT_VERIFY)
+                .long(OPT_VERIFY)
+                .action(ArgAction::SetTrue)
+                .conflicts_with(OPT_DUPLICATES)
+                .help("Verify checksums of all files instead"),
+        )
+        .arg(
+            Arg::new(OPT_DUPLICATES)
+                .long(OPT_DUPLICATES)
+                .action(ArgAction::SetTrue)
+                .help("List files with the same checksum in different books instead"),
+        )
+}
+
+pub fn run_checksum(matches: &ArgMatches) -> Result<(), Error> {
+    let library_path = get_library_path()?;
+    let pg_pool = get_connection_pool()?;
+    let mut pg_conn = pg_pool.get()?;
+
+    if matches.get_flag(OPT_VERIFY) {
+        let broken_files = verify_checksums(&mut pg_conn, &library_path)?;
+        println!("Broken files: {}", broken_files.len());
+        for file in &broken_files {
+            println!("  book: {}, file: {}, {}", file.book, file.id, file.path);
+        }
+        return Ok(());
+    }
+
+    if matches.get_flag(OPT_DUPLICATES) {
+        let duplicates = get_duplicate_files(&mut pg_conn)?;
+        for item in &duplicates {
+            println!("{}", item.sha256);
+            for file in &item.files {
+                println!("  book: {}, file: {}, {}", file.book, file.id, file.path);
+            }
+        }
+        return Ok(());
+    }
+
+    backfill_checksums(&mut pg_conn, &library_path)
+}
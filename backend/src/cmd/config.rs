@@ -0,0 +1,115 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::settings::{get_settings, Settings};
+
+pub const CMD_CONFIG: &str = "config";
+const CMD_CHECK: &str = "check";
+
+const OPT_CONFIG: &str = "config";
+const OPT_LOG_LEVEL: &str = "log-level";
+const OPT_LISTEN: &str = "listen";
+const OPT_UNIX_SOCKET: &str = "unix-socket";
+const OPT_WORKERS: &str = "workers";
+const OPT_POOL_SIZE: &str = "pool-size";
+
+pub fn new_cmd() -> Command {
+    Command::new(CMD_CONFIG)
+        .about("Manage backend settings")
+        .subcommand(
+            Command::new(CMD_CHECK).about("Validate settings and print the effective values"),
+        )
+}
+
+/// Append global flags which override values in config file and environment.
+pub fn add_global_args(cmd: Command) -> Command {
+    cmd.arg(
+        Arg::new(OPT_CONFIG)
+            .long(OPT_CONFIG)
+            .global(true)
+            .action(ArgAction::Set)
+            .value_hint(ValueHint::FilePath)
+            .value_parser(value_parser!(PathBuf))
+            .help("Specify toml config file, default is $PENGZU_CONFIG"),
+    )
+    .arg(
+        Arg::new(OPT_LOG_LEVEL)
+            .long(OPT_LOG_LEVEL)
+            .global(true)
+            .action(ArgAction::Set)
+            .help("Specify log filter, like info or backend=debug"),
+    )
+    .arg(
+        Arg::new(OPT_LISTEN)
+            .long(OPT_LISTEN)
+            .global(true)
+            .action(ArgAction::Append)
+            .value_name("address")
+            .help("Specify tcp address to listen, like 0.0.0.0:3000, can be repeated"),
+    )
+    .arg(
+        Arg::new(OPT_UNIX_SOCKET)
+            .long(OPT_UNIX_SOCKET)
+            .global(true)
+            .action(ArgAction::Set)
+            .value_hint(ValueHint::FilePath)
+            .value_parser(value_parser!(PathBuf))
+            .help("Specify unix domain socket to listen"),
+    )
+    .arg(
+        Arg::new(OPT_WORKERS)
+            .long(OPT_WORKERS)
+            .global(true)
+            .action(ArgAction::Set)
+            .value_parser(value_parser!(usize))
+            .help("Specify number of http workers"),
+    )
+    .arg(
+        Arg::new(OPT_POOL_SIZE)
+            .long(OPT_POOL_SIZE)
+            .global(true)
+            .action(ArgAction::Set)
+            .value_parser(value_parser!(u32))
+            .help("Specify max connections in database pool"),
+    )
+}
+
+/// Load settings from config file and environment, then apply command line flags.
+pub fn load_settings(matches: &ArgMatches) -> Result<Settings, Error> {
+    let config_file = matches.get_one::<PathBuf>(OPT_CONFIG);
+    let mut settings = Settings::load(config_file.map(PathBuf::as_path))?;
+
+    if let Some(log_level) = matches.get_one::<String>(OPT_LOG_LEVEL) {
+        settings.log_level.clone_from(log_level);
+    }
+    if let Some(listen) = matches.get_many::<String>(OPT_LISTEN) {
+        settings.server.listen = listen.cloned().collect();
+    }
+    if let Some(unix_socket) = matches.get_one::<PathBuf>(OPT_UNIX_SOCKET) {
+        settings.server.unix_socket = Some(unix_socket.clone());
+    }
+    if let Some(workers) = matches.get_one::<usize>(OPT_WORKERS) {
+        settings.server.workers = *workers;
+    }
+    if let Some(pool_size) = matches.get_one::<u32>(OPT_POOL_SIZE) {
+        settings.server.pool_size = *pool_size;
+    }
+
+    Ok(settings)
+}
+
+pub fn run_config(matches: &ArgMatches) -> Result<(), Error> {
+    if matches.subcommand_matches(CMD_CHECK).is_some() {
+        let settings = get_settings();
+        settings.validate()?;
+        println!("{}", settings.to_toml()?);
+        return Ok(());
+    }
+
+    new_cmd().print_help().map_err(Into::into)
+}
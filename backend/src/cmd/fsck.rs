@@ -0,0 +1,44 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::db::get_connection_pool;
+use crate::error::Error;
+use crate::import::fsck::check_library;
+use crate::settings::get_library_path;
+
+pub const CMD_FSCK: &str = "fsck";
+const OPT_FIX: &str = "fix";
+
+#[must_use]
+pub fn new_cmd() -> Command {
+    Command::new(CMD_FSCK)
+        .about("Check that books and files in database match files in library")
+        .arg(
+            Arg::new(OPT_FIX)
+                .long(OPT_FIX)
+                .action(ArgAction::SetTrue)
+                .help("Repair size mismatches, cover mismatches and dangling links"),
+        )
+}
+
+pub fn run_fsck(matches: &ArgMatches) -> Result<(), Error> {
+    let fix = matches.get_flag(OPT_FIX);
+    let library_path = get_library_path()?;
+    let pg_pool = get_connection_pool()?;
+    let mut pg_conn = pg_pool.get()?;
+    let report = check_library(&mut pg_conn, &library_path, fix)?;
+
+    println!(
+        "Checked books: {}, files: {}, issues: {}",
+        report.books,
+        report.files,
+        report.issues.len()
+    );
+    for issue in &report.issues {
+        println!("  {issue}");
+    }
+    Ok(())
+}
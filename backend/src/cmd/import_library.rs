@@ -5,17 +5,24 @@
 use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
 
 use crate::error::Error;
+use crate::import::list_tasks::list_tasks;
 use crate::import::new_task::new_task;
 use crate::import::options::ImportBookFileAction;
+use crate::import::resume_task::{resume_task, retry_failed_task};
 use crate::settings::get_library_root_dir;
 
 pub const CMD_IMPORT_LIBRARY: &str = "import-library";
 
 const CALIBRE_LIBRARY: &str = "calibre-library";
 const OPT_MOVE_FILES: &str = "move-files";
+const OPT_ALLOW_DUPLICATION: &str = "allow-duplication";
 const OPT_UID: &str = "uid";
 const OPT_GID: &str = "gid";
 const OPT_CALIBRE_PATH: &str = "calibre_path";
+const OPT_RESUME: &str = "resume";
+const OPT_LIST: &str = "list";
+const OPT_RETRY_FAILED: &str = "retry-failed";
+const OPT_JOBS: &str = "jobs";
 
 // TODO(Shaohua): Replace with clap_derive.
 #[must_use]
@@ -28,6 +35,12 @@ pub fn new_cmd() -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Move files to new path, instead of copy"),
         )
+        .arg(
+            Arg::new(OPT_ALLOW_DUPLICATION)
+                .long(OPT_ALLOW_DUPLICATION)
+                .action(ArgAction::SetTrue)
+                .help("Import books even if they already exist in library"),
+        )
         .arg(
             Arg::new(OPT_UID)
                 .long(OPT_UID)
@@ -42,6 +55,41 @@ pub fn new_cmd() -> Command {
                 .value_parser(value_parser!(u32))
                 .help("Specify gid id book new book"),
         )
+        .arg(
+            Arg::new(OPT_JOBS)
+                .short('j')
+                .long(OPT_JOBS)
+                .action(ArgAction::Set)
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .default_value("1")
+                .help("Number of books imported in parallel"),
+        )
+        .arg(
+            Arg::new(OPT_RESUME)
+                .long(OPT_RESUME)
+                .action(ArgAction::Set)
+                .value_name("library-id")
+                .value_parser(value_parser!(i32))
+                .conflicts_with_all([CALIBRE_LIBRARY, OPT_LIST])
+                .help("Resume an interrupted import task"),
+        )
+        .arg(
+            Arg::new(OPT_RETRY_FAILED)
+                .long(OPT_RETRY_FAILED)
+                .action(ArgAction::Set)
+                .value_name("library-id")
+                .value_parser(value_parser!(i32))
+                .conflicts_with_all([CALIBRE_LIBRARY, OPT_LIST, OPT_RESUME])
+                .help("Import failed books of an import task again"),
+        )
+        .arg(
+            Arg::new(OPT_LIST)
+                .long(OPT_LIST)
+                .action(ArgAction::SetTrue)
+                .conflicts_with(CALIBRE_LIBRARY)
+                .help("List import tasks and their progress"),
+        )
         .arg(
             Arg::new(CALIBRE_LIBRARY)
                 .value_hint(ValueHint::DirPath)
@@ -52,6 +100,17 @@ pub fn new_cmd() -> Command {
 
 #[allow(clippy::similar_names)]
 pub fn run_daemon(matches: &ArgMatches) -> Result<(), Error> {
+    if matches.get_flag(OPT_LIST) {
+        return list_tasks();
+    }
+    let jobs = matches.get_one::<usize>(OPT_JOBS).copied().unwrap_or(1);
+    if let Some(library_id) = matches.get_one::<i32>(OPT_RESUME) {
+        return resume_task(*library_id, jobs);
+    }
+    if let Some(library_id) = matches.get_one::<i32>(OPT_RETRY_FAILED) {
+        return retry_failed_task(*library_id);
+    }
+
     let file_action = matches.get_one::<bool>(OPT_MOVE_FILES).map_or(
         ImportBookFileAction::DoNothing,
         |move_files| {
@@ -63,6 +122,7 @@ pub fn run_daemon(matches: &ArgMatches) -> Result<(), Error> {
         },
     );
 
+    let allow_duplication = matches.get_flag(OPT_ALLOW_DUPLICATION);
     let uid = matches.get_one::<u32>(OPT_UID).copied();
     let gid = matches.get_one::<u32>(OPT_GID).copied();
 
@@ -70,7 +130,15 @@ pub fn run_daemon(matches: &ArgMatches) -> Result<(), Error> {
     let library_path = library_path.into_os_string().into_string()?;
 
     if let Some(calibre_path) = matches.get_one::<String>(CALIBRE_LIBRARY) {
-        return new_task(calibre_path, &library_path, file_action, uid, gid);
+        return new_task(
+            calibre_path,
+            &library_path,
+            file_action,
+            allow_duplication,
+            uid,
+            gid,
+            jobs,
+        );
     }
 
     new_cmd().print_help().map_err(Into::into)
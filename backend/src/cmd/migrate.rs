@@ -2,17 +2,40 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use clap::Command;
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 
+use crate::db::get_connection_pool;
 use crate::error::Error;
+use crate::migrations;
 
 pub const CMD_MIGRATE: &str = "migrate";
+const OPT_DRY_RUN: &str = "dry-run";
+const OPT_TARGET: &str = "target";
 
 pub fn new_cmd() -> Command {
-    Command::new(CMD_MIGRATE).about("Migrate database to current version")
+    Command::new(CMD_MIGRATE)
+        .about("Migrate database to current version")
+        .arg(
+            Arg::new(OPT_DRY_RUN)
+                .long(OPT_DRY_RUN)
+                .action(ArgAction::SetTrue)
+                .help("Print pending migrations without applying them"),
+        )
+        .arg(
+            Arg::new(OPT_TARGET)
+                .long(OPT_TARGET)
+                .action(ArgAction::Set)
+                .value_name("version")
+                .value_parser(value_parser!(i32))
+                .help("Migrate to specific schema version, instead of the latest one"),
+        )
 }
 
-#[allow(clippy::unnecessary_wraps)]
-pub const fn do_migrate() -> Result<(), Error> {
-    Ok(())
+pub fn do_migrate(matches: &ArgMatches) -> Result<(), Error> {
+    let dry_run = matches.get_flag(OPT_DRY_RUN);
+    let target = matches.get_one::<i32>(OPT_TARGET).copied();
+
+    let db_pool = get_connection_pool()?;
+    let mut pg_conn = db_pool.get()?;
+    migrations::migrate(&mut pg_conn, target, dry_run)
 }
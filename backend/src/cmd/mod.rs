@@ -6,26 +6,46 @@ use clap::Command;
 use std::env;
 
 use crate::error::Error;
+use crate::settings::{get_settings, init_settings};
 
 mod add_user;
+mod checksum;
+mod config;
+mod fsck;
+mod import_folder;
 mod import_library;
 mod migrate;
+mod regenerate_covers;
 mod run_server;
+mod sync_library;
+mod watch_folder;
 
 pub fn run() -> Result<(), Error> {
     dotenvy::dotenv()?;
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
-    let mut cmd = Command::new("backend")
+    let cmd = Command::new("backend")
         .version(env!("VERGEN_GIT_DESCRIBE"))
         .author("Xu Shaohua <shaohua@biofan.org>")
         .about("Pengzu backend app")
         .subcommand(run_server::new_cmd())
         .subcommand(add_user::new_cmd())
         .subcommand(import_library::new_cmd())
-        .subcommand(migrate::new_cmd());
+        .subcommand(import_folder::new_cmd())
+        .subcommand(sync_library::new_cmd())
+        .subcommand(watch_folder::new_cmd())
+        .subcommand(regenerate_covers::new_cmd())
+        .subcommand(fsck::new_cmd())
+        .subcommand(checksum::new_cmd())
+        .subcommand(migrate::new_cmd())
+        .subcommand(config::new_cmd());
+    let mut cmd = config::add_global_args(cmd);
     let matches = cmd.clone().get_matches();
 
+    init_settings(config::load_settings(&matches)?)?;
+    env_logger::Builder::new()
+        .parse_filters(&get_settings().log_level)
+        .init();
+
     if let Some(matches) = matches.subcommand_matches(add_user::CMD_ADD_USER) {
         return add_user::add_user(matches);
     }
@@ -35,8 +55,29 @@ pub fn run() -> Result<(), Error> {
     if let Some(matches) = matches.subcommand_matches(import_library::CMD_IMPORT_LIBRARY) {
         return import_library::run_daemon(matches);
     }
-    if let Some(_matches) = matches.subcommand_matches(migrate::CMD_MIGRATE) {
-        return migrate::do_migrate();
+    if let Some(matches) = matches.subcommand_matches(import_folder::CMD_IMPORT_FOLDER) {
+        return import_folder::run_import(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(sync_library::CMD_SYNC_LIBRARY) {
+        return sync_library::run_sync(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(watch_folder::CMD_WATCH_FOLDER) {
+        return watch_folder::run_watch(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(regenerate_covers::CMD_REGENERATE_COVERS) {
+        return regenerate_covers::run_regenerate(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(fsck::CMD_FSCK) {
+        return fsck::run_fsck(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(checksum::CMD_CHECKSUM) {
+        return checksum::run_checksum(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(migrate::CMD_MIGRATE) {
+        return migrate::do_migrate(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches(config::CMD_CONFIG) {
+        return config::run_config(matches);
     }
 
     cmd.print_help().map_err(Into::into)
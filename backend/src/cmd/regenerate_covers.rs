@@ -0,0 +1,43 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+
+use crate::error::Error;
+use crate::import::covers::regenerate_covers;
+use crate::settings::get_library_path;
+
+pub const CMD_REGENERATE_COVERS: &str = "regenerate-covers";
+
+const OPT_UID: &str = "uid";
+const OPT_GID: &str = "gid";
+
+#[must_use]
+pub fn new_cmd() -> Command {
+    Command::new(CMD_REGENERATE_COVERS)
+        .about("Generate webp covers of all books again, after cover settings are changed")
+        .arg(
+            Arg::new(OPT_UID)
+                .long(OPT_UID)
+                .action(ArgAction::Set)
+                .value_parser(value_parser!(u32))
+                .help("Specify user id of cover images"),
+        )
+        .arg(
+            Arg::new(OPT_GID)
+                .long(OPT_GID)
+                .action(ArgAction::Set)
+                .value_parser(value_parser!(u32))
+                .help("Specify group id of cover images"),
+        )
+}
+
+pub fn run_regenerate(matches: &ArgMatches) -> Result<(), Error> {
+    let library_path = get_library_path()?;
+    regenerate_covers(
+        &library_path,
+        matches.get_one::<u32>(OPT_UID).copied(),
+        matches.get_one::<u32>(OPT_GID).copied(),
+    )
+}
@@ -6,6 +6,7 @@ use clap::Command;
 
 use crate::error::Error;
 use crate::routers;
+use crate::settings::get_settings;
 
 pub const CMD_RUN_SERVER: &str = "run";
 
@@ -14,6 +15,8 @@ pub fn new_cmd() -> Command {
 }
 
 pub fn run_server() -> Result<(), Error> {
+    get_settings().validate()?;
+
     let rt = actix_rt::Runtime::new()?;
     let handle = rt.spawn(async { routers::run().await });
     rt.block_on(handle).unwrap()
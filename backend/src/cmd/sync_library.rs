@@ -0,0 +1,43 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+
+use crate::error::Error;
+use crate::import::sync_library::sync_library;
+
+pub const CMD_SYNC_LIBRARY: &str = "sync-library";
+const OPT_LIBRARY_ID: &str = "library-id";
+const OPT_JOBS: &str = "jobs";
+
+pub fn new_cmd() -> Command {
+    Command::new(CMD_SYNC_LIBRARY)
+        .about("Apply changes in calibre library to imported books")
+        .arg(
+            Arg::new(OPT_JOBS)
+                .short('j')
+                .long(OPT_JOBS)
+                .action(ArgAction::Set)
+                .value_name("N")
+                .value_parser(value_parser!(usize))
+                .default_value("1")
+                .help("Number of new books imported in parallel"),
+        )
+        .arg(
+            Arg::new(OPT_LIBRARY_ID)
+                .required(true)
+                .value_name(OPT_LIBRARY_ID)
+                .value_parser(value_parser!(i32))
+                .help("Id of import library, listed by `import-library --list`"),
+        )
+}
+
+pub fn run_sync(matches: &ArgMatches) -> Result<(), Error> {
+    let jobs = matches.get_one::<usize>(OPT_JOBS).copied().unwrap_or(1);
+    let library_id = matches
+        .get_one::<i32>(OPT_LIBRARY_ID)
+        .copied()
+        .unwrap_or_default();
+    sync_library(library_id, jobs)
+}
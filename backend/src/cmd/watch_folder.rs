@@ -0,0 +1,66 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command, ValueHint};
+
+use crate::error::Error;
+use crate::import::options::{ImportBookFileAction, ImportBookOptions};
+use crate::import::watch_folder::watch_folder;
+use crate::settings::get_library_root_dir;
+
+pub const CMD_WATCH_FOLDER: &str = "watch-folder";
+
+const OPT_DIR: &str = "dir";
+const OPT_ALLOW_DUPLICATION: &str = "allow-duplication";
+const OPT_UID: &str = "uid";
+const OPT_GID: &str = "gid";
+
+#[must_use]
+pub fn new_cmd() -> Command {
+    Command::new(CMD_WATCH_FOLDER)
+        .about("Import ebook files dropped into folder")
+        .arg(
+            Arg::new(OPT_ALLOW_DUPLICATION)
+                .long(OPT_ALLOW_DUPLICATION)
+                .action(ArgAction::SetTrue)
+                .help("Import books even if book with same isbn exists in library"),
+        )
+        .arg(
+            Arg::new(OPT_UID)
+                .long(OPT_UID)
+                .action(ArgAction::Set)
+                .value_parser(value_parser!(u32))
+                .help("Specify user id of new book"),
+        )
+        .arg(
+            Arg::new(OPT_GID)
+                .long(OPT_GID)
+                .action(ArgAction::Set)
+                .value_parser(value_parser!(u32))
+                .help("Specify group id of new book"),
+        )
+        .arg(
+            Arg::new(OPT_DIR)
+                .value_hint(ValueHint::DirPath)
+                .help("Inbox folder to watch"),
+        )
+}
+
+pub fn run_watch(matches: &ArgMatches) -> Result<(), Error> {
+    let options = ImportBookOptions {
+        file_action: ImportBookFileAction::Copy,
+        allow_duplication: matches.get_flag(OPT_ALLOW_DUPLICATION),
+        uid: matches.get_one::<u32>(OPT_UID).copied(),
+        gid: matches.get_one::<u32>(OPT_GID).copied(),
+    };
+
+    let library_path = get_library_root_dir()?;
+    let library_path = library_path.into_os_string().into_string()?;
+
+    if let Some(dir) = matches.get_one::<String>(OPT_DIR) {
+        return watch_folder(dir, &library_path, &options);
+    }
+
+    new_cmd().print_help().map_err(Into::into)
+}
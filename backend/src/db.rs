@@ -2,33 +2,44 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use diesel::connection::SimpleConnection;
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
 
 use crate::error::{Error, ErrorKind};
+use crate::settings::get_settings;
 
 pub type DbPool = Pool<ConnectionManager<PgConnection>>;
 
-const TABLE_SCHEMAS: &str = include_str!("../data/create_tables.sql");
+/// First key of advisory locks taken when checking duplicated books to import.
+pub const DUPLICATION_LOCK: i32 = 1;
+
+/// First key of advisory locks held by worker of import library.
+pub const IMPORT_WORKER_LOCK: i32 = 2;
 
 /// Create postgres database connection pool.
 ///
 /// # Errors
 ///
 /// Returns error if:
-/// - No `DATABASE_URL` is set in current environment.
+/// - No `database_url` is set in settings.
 /// - Failed to connect to database.
 pub fn get_connection_pool() -> Result<DbPool, Error> {
-    let url = std::env::var("DATABASE_URL").map_err(|err| {
-        Error::from_string(
+    get_connection_pool_with_size(get_settings().server.pool_size)
+}
+
+/// Create postgres database connection pool with at most `max_size` connections.
+pub fn get_connection_pool_with_size(max_size: u32) -> Result<DbPool, Error> {
+    let url = &get_settings().database_url;
+    if url.is_empty() {
+        return Err(Error::new(
             ErrorKind::ConfigError,
-            format!("DATABASE_URL is not set in environment, err: {err:?}"),
-        )
-    })?;
-    let manager = ConnectionManager::<PgConnection>::new(&url);
+            "DATABASE_URL is not set in environment or config file",
+        ));
+    }
+    let manager = ConnectionManager::<PgConnection>::new(url);
 
     Pool::builder()
+        .max_size(max_size)
         .test_on_check_out(true)
         .build(manager)
         .map_err(|err| {
@@ -38,8 +49,3 @@ pub fn get_connection_pool() -> Result<DbPool, Error> {
             )
         })
 }
-
-pub fn create_table_schema(conn: &mut PgConnection) -> Result<(), Error> {
-    conn.batch_execute(TABLE_SCHEMAS)?;
-    Ok(())
-}
@@ -4,9 +4,12 @@
 
 use crate::models::auth::AuthError;
 use actix_http::error::HttpError;
+use actix_web::http::header::ContentType;
 use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
 use diesel::result::DatabaseErrorKind;
 use serde::Serialize;
+use shared::search_query::ParseError;
 use std::fmt;
 use std::io::{self, stderr, Write};
 
@@ -17,6 +20,7 @@ pub enum ErrorKind {
     InvalidToken,
 
     CalibreError,
+    ImportError,
 
     DbConnError,
     DbGeneralError,
@@ -33,6 +37,9 @@ pub enum ErrorKind {
 
     // Invalid form request.
     RequestFormError,
+
+    // Resource was modified by others.
+    ConflictError,
 }
 
 unsafe impl Send for ErrorKind {}
@@ -41,6 +48,10 @@ unsafe impl Send for ErrorKind {}
 pub struct Error {
     kind: ErrorKind,
     message: String,
+
+    /// Position and reason of invalid search query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    invalid_query: Option<ParseError>,
 }
 
 impl fmt::Display for Error {
@@ -57,12 +68,17 @@ impl Error {
         Self {
             kind,
             message: message.to_owned(),
+            invalid_query: None,
         }
     }
 
     #[must_use]
     pub const fn from_string(kind: ErrorKind, message: String) -> Self {
-        Self { kind, message }
+        Self {
+            kind,
+            message,
+            invalid_query: None,
+        }
     }
 
     #[must_use]
@@ -103,12 +119,17 @@ impl From<AuthError> for Error {
 impl From<diesel::result::Error> for Error {
     fn from(err: diesel::result::Error) -> Self {
         match &err {
-            diesel::result::Error::DatabaseError(kind, _info) => match kind {
+            diesel::result::Error::DatabaseError(kind, info) => match kind {
                 DatabaseErrorKind::UniqueViolation => {
                     Self::from_string(ErrorKind::DbUniqueViolationError, err.to_string())
                 }
                 DatabaseErrorKind::ForeignKeyViolation => {
-                    Self::from_string(ErrorKind::DbForeignKeyViolationError, err.to_string())
+                    // Details tells which record is missing or still referenced.
+                    let message = info.details().map_or_else(
+                        || info.message().to_owned(),
+                        |details| format!("{}, {details}", info.message()),
+                    );
+                    Self::from_string(ErrorKind::DbForeignKeyViolationError, message)
                 }
                 _ => Self::from_string(ErrorKind::DbGeneralError, err.to_string()),
             },
@@ -161,6 +182,17 @@ impl From<calibre::error::Error> for Error {
     }
 }
 
+impl From<cip::error::Error> for Error {
+    fn from(err: cip::error::Error) -> Self {
+        match err.kind() {
+            cip::error::ErrorKind::IoError => {
+                Self::from_string(ErrorKind::IoError, err.to_string())
+            }
+            _ => Self::from_string(ErrorKind::ImportError, err.to_string()),
+        }
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
         Self::from_string(ErrorKind::JsonError, err.to_string())
@@ -179,6 +211,15 @@ impl From<HttpError> for Error {
     }
 }
 
+impl From<actix_multipart::MultipartError> for Error {
+    fn from(err: actix_multipart::MultipartError) -> Self {
+        Self::from_string(
+            ErrorKind::RequestFormError,
+            format!("Invalid multipart form, err: {err}"),
+        )
+    }
+}
+
 impl From<ring::error::Unspecified> for Error {
     fn from(err: ring::error::Unspecified) -> Self {
         Self::from_string(ErrorKind::RingError, err.to_string())
@@ -191,26 +232,49 @@ impl From<data_encoding::DecodeError> for Error {
     }
 }
 
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self {
+            kind: ErrorKind::RequestFormError,
+            message: format!("Invalid search query: {err}"),
+            invalid_query: Some(err),
+        }
+    }
+}
+
 impl actix_web::error::ResponseError for Error {
     fn status_code(&self) -> StatusCode {
         match self.kind {
             ErrorKind::ConfigError
             | ErrorKind::CalibreError
+            | ErrorKind::ImportError
             | ErrorKind::DbConnError
             | ErrorKind::DbGeneralError
             | ErrorKind::JsonError
             | ErrorKind::ActixBlockingError
             | ErrorKind::HttpError
             | ErrorKind::RingError => StatusCode::INTERNAL_SERVER_ERROR,
-            ErrorKind::DbForeignKeyViolationError
-            | ErrorKind::DbUniqueViolationError
+            ErrorKind::DbUniqueViolationError
             | ErrorKind::InvalidToken
             | ErrorKind::RequestFormError
             | ErrorKind::IoError => StatusCode::BAD_REQUEST,
             ErrorKind::DbNotFoundError => StatusCode::NOT_FOUND,
+            ErrorKind::ConflictError | ErrorKind::DbForeignKeyViolationError => {
+                StatusCode::CONFLICT
+            }
             ErrorKind::JwtError | ErrorKind::AuthFailed => StatusCode::UNAUTHORIZED,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        // Send invalid part of search query as json, so that it can be highlighted.
+        if self.invalid_query.is_some() {
+            return HttpResponse::build(self.status_code()).json(self);
+        }
+        HttpResponse::build(self.status_code())
+            .content_type(ContentType::plaintext())
+            .body(self.to_string())
+    }
 }
 
 /// Dump an `err` and its source to stderr.
@@ -0,0 +1,207 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Add, replace and delete ebook files of existing books.
+//!
+//! Files in library and rows in `files` table are changed together, file
+//! operations are reverted if database transaction fails.
+
+use diesel::PgConnection;
+use shared::files::{File, FileWithPath};
+use std::path::Path;
+
+use crate::error::{Error, ErrorKind};
+use crate::import::file_util::{get_book_file_path, get_file_size, sha256sum};
+use crate::import::import_folder::{
+    copy_file, get_file_format, get_new_book_path, get_or_add_file_format, sanitize_path_component,
+};
+use crate::import::journal::run_with_journal;
+use crate::import::options::ImportBookOptions;
+use crate::models::books::{
+    get_book_record, get_book_record_for_update, touch_book, update_book_path,
+};
+use crate::models::file_formats::get_file_format_by_id;
+use crate::models::file_logs::{add_file_log, FileLogAction, NewFileLog};
+use crate::models::files::{
+    add_file, delete_file, get_book_files_and_formats, get_file, get_file_by_format,
+    update_file_content, NewFile,
+};
+
+fn get_file_with_path(pg_conn: &mut PgConnection, file: &File) -> Result<FileWithPath, Error> {
+    get_book_files_and_formats(pg_conn, file.book)?
+        .into_iter()
+        .find(|file_with_path| file_with_path.id == file.id)
+        .ok_or_else(|| {
+            Error::from_string(
+                ErrorKind::DbNotFoundError,
+                format!("File not found: {}", file.id),
+            )
+        })
+}
+
+/// Add `src_path` as a new format of book.
+///
+/// Returns error if book already has a file in the same format.
+pub fn add_book_file(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+    src_path: &Path,
+    user_id: i32,
+) -> Result<FileWithPath, Error> {
+    let format = get_file_format(src_path);
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        // Lock book so that files of the same format are not added concurrently.
+        let mut book = get_book_record_for_update(pg_conn, book_id)?;
+        let format_id = get_or_add_file_format(pg_conn, &format.to_ascii_uppercase())?;
+        match get_file_by_format(pg_conn, book_id, format_id) {
+            Ok(file) => {
+                return Err(Error::from_string(
+                    ErrorKind::ConflictError,
+                    format!("Book {book_id} already has {format} file: {}", file.id),
+                ));
+            }
+            Err(err) if err.kind() != ErrorKind::DbNotFoundError => return Err(err),
+            Err(_err) => (),
+        }
+
+        // Book added without any files has no directory yet.
+        if book.path.is_empty() {
+            book.path = get_new_book_path(&book.author_sort, &book.title, book.id);
+            update_book_path(pg_conn, book.id, &book.path, book.has_cover)?;
+        }
+        // Files of the same book share the same name.
+        let file_name = get_book_files_and_formats(pg_conn, book_id)?
+            .first()
+            .map_or_else(
+                || sanitize_path_component(&book.title),
+                |file| file.name.clone(),
+            );
+
+        let dest_path = get_book_file_path(library_path, &book.path, &file_name, &format);
+        if dest_path.exists() {
+            return Err(Error::from_string(
+                ErrorKind::IoError,
+                format!("Book file already exists: {}", dest_path.display()),
+            ));
+        }
+        copy_file(src_path, &dest_path, &ImportBookOptions::default(), journal)?;
+        let size = get_file_size(&dest_path)?;
+        let file = add_file(
+            pg_conn,
+            &NewFile {
+                book: book_id,
+                format: format_id,
+                size,
+                name: file_name,
+                sha256: Some(sha256sum(&dest_path)?),
+            },
+        )
+        .map_err(|err| {
+            // Files added by writers which do not lock book are rejected by unique index.
+            if err.kind() == ErrorKind::DbUniqueViolationError {
+                Error::from_string(
+                    ErrorKind::ConflictError,
+                    format!("Book {book_id} already has {format} file"),
+                )
+            } else {
+                err
+            }
+        })?;
+
+        add_file_log(
+            pg_conn,
+            &NewFileLog {
+                book: book_id,
+                file: file.id,
+                format: format_id,
+                action: FileLogAction::Add.as_str().to_owned(),
+                old_size: None,
+                new_size: Some(size),
+                user_id,
+            },
+        )?;
+        touch_book(pg_conn, book_id)?;
+        get_file_with_path(pg_conn, &file)
+    })
+}
+
+/// Replace content of book file with `src_path`, which shall be in the same format.
+pub fn replace_book_file(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+    file_id: i32,
+    src_path: &Path,
+    user_id: i32,
+) -> Result<FileWithPath, Error> {
+    let format = get_file_format(src_path);
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        let book = get_book_record(pg_conn, book_id)?;
+        let file = get_file(pg_conn, book_id, file_id)?;
+        let file_format = get_file_format_by_id(pg_conn, file.format)?;
+        if !file_format.name.eq_ignore_ascii_case(&format) {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!(
+                    "File {file_id} is in {} format, got {format}",
+                    file_format.name
+                ),
+            ));
+        }
+
+        let dest_path = get_book_file_path(library_path, &book.path, &file.name, &format);
+        journal.backup_file(&dest_path)?;
+        copy_file(src_path, &dest_path, &ImportBookOptions::default(), journal)?;
+        let size = get_file_size(&dest_path)?;
+        update_file_content(pg_conn, file_id, size, &sha256sum(&dest_path)?)?;
+
+        add_file_log(
+            pg_conn,
+            &NewFileLog {
+                book: book_id,
+                file: file_id,
+                format: file.format,
+                action: FileLogAction::Replace.as_str().to_owned(),
+                old_size: Some(file.size),
+                new_size: Some(size),
+                user_id,
+            },
+        )?;
+        touch_book(pg_conn, book_id)?;
+        get_file_with_path(pg_conn, &file)
+    })
+}
+
+/// Delete book file from both library and database.
+pub fn delete_book_file(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+    file_id: i32,
+    user_id: i32,
+) -> Result<(), Error> {
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        let book = get_book_record(pg_conn, book_id)?;
+        let file = get_file(pg_conn, book_id, file_id)?;
+        let file_format = get_file_format_by_id(pg_conn, file.format)?;
+        let dest_path = get_book_file_path(library_path, &book.path, &file.name, &file_format.name);
+        journal.backup_file(&dest_path)?;
+        delete_file(pg_conn, file_id)?;
+
+        add_file_log(
+            pg_conn,
+            &NewFileLog {
+                book: book_id,
+                file: file_id,
+                format: file.format,
+                action: FileLogAction::Delete.as_str().to_owned(),
+                old_size: Some(file.size),
+                new_size: None,
+                user_id,
+            },
+        )?;
+        touch_book(pg_conn, book_id)
+    })
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Compute and verify sha256 checksums of book files.
+//!
+//! Checksums are computed when files are imported or uploaded. Files imported
+//! before are hashed by `backfill_checksums()`, and all files are verified
+//! periodically to detect bit rot.
+
+use diesel::PgConnection;
+use shared::files::{DuplicateFiles, FileWithPath};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::import::file_util::sha256sum;
+use crate::import::progress::Progress;
+use crate::models::files::{
+    get_all_files_with_path, get_duplicate_checksums, update_file_checksum,
+};
+
+/// Compute checksums of files which have none.
+pub fn backfill_checksums(pg_conn: &mut PgConnection, library_path: &str) -> Result<(), Error> {
+    let mut files = get_all_files_with_path(pg_conn)?;
+    files.retain(|file| file.sha256.is_none());
+    log::info!("Compute checksums of {} files", files.len());
+
+    let progress = Progress::new(files.len() as u64);
+    let mut failed = 0;
+    for file in &files {
+        let ret = sha256sum(Path::new(library_path).join(&file.path))
+            .and_then(|sha256| update_file_checksum(pg_conn, file.id, &sha256));
+        if let Err(err) = &ret {
+            log::warn!("Failed to compute checksum of {}, err: {err:?}", file.path);
+            failed += 1;
+        }
+        progress.inc(ret.is_ok());
+    }
+
+    progress.finish();
+    log::info!(
+        "Computed checksums of {} files, failed: {failed}",
+        files.len() - failed
+    );
+    Ok(())
+}
+
+/// Hash all files again and compare with their recorded checksums.
+///
+/// Returns files which are missing or whose content has changed.
+pub fn verify_checksums(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+) -> Result<Vec<FileWithPath>, Error> {
+    let mut files = get_all_files_with_path(pg_conn)?;
+    files.retain(|file| file.sha256.is_some());
+    log::info!("Verify checksums of {} files", files.len());
+
+    let mut broken_files = Vec::new();
+    for file in files {
+        match sha256sum(Path::new(library_path).join(&file.path)) {
+            Ok(sha256) if file.sha256.as_ref() == Some(&sha256) => (),
+            Ok(sha256) => {
+                log::warn!(
+                    "Checksum of {} is {sha256}, expected {:?}",
+                    file.path,
+                    file.sha256
+                );
+                broken_files.push(file);
+            }
+            Err(err) => {
+                log::warn!("Failed to compute checksum of {}, err: {err:?}", file.path);
+                broken_files.push(file);
+            }
+        }
+    }
+
+    log::info!("Found {} broken files", broken_files.len());
+    Ok(broken_files)
+}
+
+/// Get files with the same content which are attached to different books.
+pub fn get_duplicate_files(pg_conn: &mut PgConnection) -> Result<Vec<DuplicateFiles>, Error> {
+    let checksums = get_duplicate_checksums(pg_conn)?;
+    if checksums.is_empty() {
+        return Ok(Vec::new());
+    }
+    let files = get_all_files_with_path(pg_conn)?;
+
+    Ok(checksums
+        .into_iter()
+        .map(|sha256| DuplicateFiles {
+            files: files
+                .iter()
+                .filter(|file| file.sha256.as_ref() == Some(&sha256))
+                .cloned()
+                .collect(),
+            sha256,
+        })
+        .collect())
+}
+
+/// Verify checksums of all files every `interval_days` days in background.
+pub fn start_auto_verify(pool: DbPool, library_path: String, interval_days: u32) {
+    if interval_days == 0 {
+        return;
+    }
+    let interval = Duration::from_secs(u64::from(interval_days) * 24 * 60 * 60);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let ret = pool
+            .get()
+            .map_err(Into::into)
+            .and_then(|mut pg_conn| verify_checksums(&mut pg_conn, &library_path));
+        if let Err(err) = ret {
+            log::warn!("Failed to verify checksums of files, err: {err:?}");
+        }
+    });
+}
@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::error::{Error, ErrorKind};
+use crate::settings::get_settings;
 
 const IMAGE_MAGIC_PROGRAM: &str = "convert";
 
@@ -90,8 +91,9 @@ pub fn convert_cover<P: AsRef<Path>>(path: P) -> Result<(PathBuf, PathBuf), Erro
         .as_ref()
         .with_file_name("small_cover")
         .with_extension("webp");
-    let width = 270;
-    let height = 400;
+    let cover = &get_settings().cover;
+    let width = cover.small_width;
+    let height = cover.small_height;
 
     if let Err(err) = convert_im(path.as_ref(), &webp_path) {
         log::warn!("ImageMagic convert failed: {err:?}");
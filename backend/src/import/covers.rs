@@ -0,0 +1,170 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Replace, extract and regenerate covers of books.
+//!
+//! `cover.jpg` in book directory is the original cover, `cover.webp` and
+//! `small_cover.webp` are generated from it.
+
+use cip::formats::metadata::read_metadata;
+use diesel::PgConnection;
+use image::{DynamicImage, ImageFormat};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::db::get_connection_pool;
+use crate::error::{Error, ErrorKind};
+use crate::import::convert::convert_cover;
+use crate::import::file_util::{self, get_book_metadata_path};
+use crate::import::import_folder::get_new_book_path;
+use crate::import::journal::{run_with_journal, FileJournal};
+use crate::import::progress::Progress;
+use crate::models::books::{
+    get_book_paths_with_cover, get_book_record_for_update, update_book_cover, update_book_path,
+};
+use crate::models::files::get_book_files_and_formats;
+
+const COVER_FILE: &str = "cover.jpg";
+
+/// Formats to look for embedded cover, in order of preference.
+const COVER_FORMATS: &[&str] = &["EPUB", "AZW3", "MOBI", "AZW", "PDF"];
+
+/// Save image as `cover.jpg` of book and generate webp covers.
+///
+/// Covers are generated in a temporary directory and then renamed into place,
+/// old covers are restored if it fails. Returns error if `image` is not
+/// a valid image.
+pub fn set_book_cover(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+    image: &[u8],
+) -> Result<(), Error> {
+    let img = image::load_from_memory(image)?;
+    // Keep it out of book directory, so that directory created for book can be
+    // removed on rollback.
+    let tmp_dir = Path::new(library_path).join(format!(".cover-{}", Uuid::new_v4()));
+    fs::create_dir_all(&tmp_dir)?;
+    let ret = run_with_journal(pg_conn, |pg_conn, journal| {
+        // Lock book so that its covers are not replaced concurrently.
+        let mut book = get_book_record_for_update(pg_conn, book_id)?;
+        // Book added without any files has no directory yet.
+        if book.path.is_empty() {
+            book.path = get_new_book_path(&book.author_sort, &book.title, book.id);
+            update_book_path(pg_conn, book.id, &book.path, book.has_cover)?;
+        }
+
+        let cover_path = get_book_metadata_path(library_path, &book.path, COVER_FILE);
+        let book_dir = cover_path
+            .parent()
+            .ok_or_else(|| Error::new(ErrorKind::IoError, "Invalid book path"))?;
+        journal.create_dir_all(book_dir, None, None)?;
+        save_covers(journal, &img, &tmp_dir, book_dir)?;
+
+        update_book_cover(pg_conn, book_id, true)
+    });
+    if let Err(err) = fs::remove_dir_all(&tmp_dir) {
+        log::warn!("Failed to remove {}, err: {err:?}", tmp_dir.display());
+    }
+    ret?;
+    log::info!("Cover of book {book_id} is updated");
+    Ok(())
+}
+
+/// Generate covers in `tmp_dir` and move them to `book_dir`, old covers are
+/// backed up in journal.
+fn save_covers(
+    journal: &mut FileJournal,
+    img: &DynamicImage,
+    tmp_dir: &Path,
+    book_dir: &Path,
+) -> Result<(), Error> {
+    let tmp_cover_path = tmp_dir.join(COVER_FILE);
+    img.to_rgb8()
+        .save_with_format(&tmp_cover_path, ImageFormat::Jpeg)?;
+    let (webp_path, small_webp_path) = convert_cover(&tmp_cover_path)?;
+    for path in [&tmp_cover_path, &webp_path, &small_webp_path] {
+        if let Some(file_name) = path.file_name() {
+            let dest_path = book_dir.join(file_name);
+            if dest_path.exists() {
+                journal.backup_file(&dest_path)?;
+            }
+            journal.rename(path.as_path(), dest_path.as_path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Extract cover embedded in ebook files of book, and use it as book cover.
+pub fn extract_book_cover(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+) -> Result<(), Error> {
+    let mut files = get_book_files_and_formats(pg_conn, book_id)?;
+    files.retain(|file| COVER_FORMATS.contains(&file.format_name.as_str()));
+    files.sort_by_key(|file| {
+        COVER_FORMATS
+            .iter()
+            .position(|format| *format == file.format_name)
+    });
+
+    for file in &files {
+        let path = Path::new(library_path).join(&file.path);
+        match read_metadata(&path) {
+            Ok(metadata) => {
+                if let Some(cover) = metadata.cover {
+                    return set_book_cover(pg_conn, library_path, book_id, &cover);
+                }
+                log::info!("No cover found in {}", path.display());
+            }
+            Err(err) => log::warn!(
+                "Failed to read metadata of {}, err: {err:?}",
+                path.display()
+            ),
+        }
+    }
+
+    Err(Error::from_string(
+        ErrorKind::RequestFormError,
+        format!("No cover found in files of book {book_id}"),
+    ))
+}
+
+/// Generate webp covers of all books again from their `cover.jpg`.
+///
+/// Used after size of small cover is changed in settings.
+pub fn regenerate_covers(
+    library_path: &str,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> Result<(), Error> {
+    let pg_pool = get_connection_pool()?;
+    let mut pg_conn = pg_pool.get()?;
+    let books = get_book_paths_with_cover(&mut pg_conn)?;
+    log::info!("Regenerate covers of {} books", books.len());
+
+    let progress = Progress::new(books.len() as u64);
+    let mut failed = 0;
+    for (book_id, book_path) in &books {
+        let cover_path = get_book_metadata_path(library_path, book_path, COVER_FILE);
+        let ret = convert_cover(&cover_path).and_then(|(webp_path, small_webp_path)| {
+            file_util::chown(webp_path, uid, gid)?;
+            file_util::chown(small_webp_path, uid, gid)
+        });
+        if let Err(err) = &ret {
+            log::warn!("Failed to regenerate cover of book {book_id}, err: {err:?}");
+            failed += 1;
+        }
+        progress.inc(ret.is_ok());
+    }
+
+    progress.finish();
+    log::info!(
+        "Regenerated covers of {} books, failed: {failed}",
+        books.len() - failed
+    );
+    Ok(())
+}
@@ -11,13 +11,14 @@ use crate::error::{Error, ErrorKind};
 
 pub type CalibreDbPool = Pool<ConnectionManager<SqliteConnection>>;
 
-pub fn get_calibre_db(calibre_path: &str) -> Result<CalibreDbPool, Error> {
+pub fn get_calibre_db(calibre_path: &str, max_size: u32) -> Result<CalibreDbPool, Error> {
     let calibre_dir = Path::new(calibre_path);
     let db_file = calibre_dir.join("metadata.db");
     let db_file = db_file.into_os_string();
     let db_file: String = db_file.into_string()?;
     let manager = ConnectionManager::<SqliteConnection>::new(&db_file);
     Pool::builder()
+        .max_size(max_size)
         .test_on_check_out(true)
         .build(manager)
         .map_err(|err| {
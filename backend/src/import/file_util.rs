@@ -4,7 +4,10 @@
 
 #![allow(clippy::similar_names)]
 
+use data_encoding::HEXLOWER;
+use ring::digest;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use crate::error::{Error, ErrorKind};
@@ -109,3 +112,32 @@ pub fn create_dir_all_and_chown<P: AsRef<Path>>(
 
     Ok(())
 }
+
+/// Get size of file, which is stored as `i32` in database.
+///
+/// Returns error if file is larger than 2GiB.
+pub fn get_file_size<P: AsRef<Path>>(path: P) -> Result<i32, Error> {
+    let path = path.as_ref();
+    let size = fs::metadata(path)?.len();
+    i32::try_from(size).map_err(|_err| {
+        Error::from_string(
+            ErrorKind::IoError,
+            format!("File is too large, {} bytes: {}", size, path.display()),
+        )
+    })
+}
+
+/// Get sha256 checksum of file content, in lowercase hex.
+pub fn sha256sum<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let mut file = fs::File::open(path)?;
+    let mut context = digest::Context::new(&digest::SHA256);
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.update(&buf[..n]);
+    }
+    Ok(HEXLOWER.encode(context.finish().as_ref()))
+}
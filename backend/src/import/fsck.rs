@@ -0,0 +1,268 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Check that database records match files in library.
+//!
+//! In fix mode, only safe cases are repaired:
+//!  - size of file record is updated to the real size
+//!  - `has_cover` of book is updated to match `cover.webp`, or webp covers
+//!    are generated again if `cover.jpg` still exists
+//!  - link rows referring to missing books are removed
+//!
+//! Missing files and orphan directories are only reported.
+
+use diesel::sql_types::Integer;
+use diesel::{sql_query, PgConnection, QueryableByName, RunQueryDsl};
+use shared::fsck::{FsckIssue, FsckIssueKind, FsckReport};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::import::convert::convert_cover;
+use crate::import::file_util::get_book_metadata_path;
+use crate::models::books::{get_book_paths, update_book_cover};
+use crate::models::files::{get_all_files_with_path, update_file_size};
+
+const COVER_FILE: &str = "cover.jpg";
+const WEBP_COVER_FILE: &str = "cover.webp";
+
+/// Tables with a `book` column.
+const LINK_TABLES: &[&str] = &[
+    "books_authors_link",
+    "books_categories_link",
+    "books_languages_link",
+    "books_publishers_link",
+    "books_series_link",
+    "books_tags_link",
+    "books_user_tags_link",
+    "comments",
+    "custom_field_values",
+    "download_history",
+    "files",
+    "identifiers",
+    "ratings",
+    "reading_history",
+];
+
+/// Scan database and library directory, and repair safe cases if `fix` is true.
+pub fn check_library(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    fix: bool,
+) -> Result<FsckReport, Error> {
+    let books = get_book_paths(pg_conn)?;
+    let mut report = FsckReport {
+        books: books.len(),
+        ..FsckReport::default()
+    };
+
+    check_files(pg_conn, library_path, fix, &mut report)?;
+    check_covers(pg_conn, library_path, &books, fix, &mut report)?;
+    check_orphan_dirs(library_path, &books, &mut report)?;
+    check_dangling_links(pg_conn, fix, &mut report)?;
+
+    log::info!(
+        "Checked {} books and {} files, found {} issues",
+        report.books,
+        report.files,
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+fn check_files(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    fix: bool,
+    report: &mut FsckReport,
+) -> Result<(), Error> {
+    let files = get_all_files_with_path(pg_conn)?;
+    report.files = files.len();
+
+    for file in &files {
+        let Ok(metadata) = fs::metadata(Path::new(library_path).join(&file.path)) else {
+            report.issues.push(FsckIssue {
+                kind: FsckIssueKind::MissingFile,
+                book: Some(file.book),
+                path: file.path.clone(),
+                message: format!("File {} does not exist", file.id),
+                fixed: false,
+            });
+            continue;
+        };
+
+        let size = i32::try_from(metadata.len()).unwrap_or(i32::MAX);
+        if size != file.size {
+            let fixed = fix && {
+                update_file_size(pg_conn, file.id, size)?;
+                true
+            };
+            report.issues.push(FsckIssue {
+                kind: FsckIssueKind::SizeMismatch,
+                book: Some(file.book),
+                path: file.path.clone(),
+                message: format!("Size of file {} is {size}, recorded {}", file.id, file.size),
+                fixed,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_covers(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    books: &[(i32, String, bool)],
+    fix: bool,
+    report: &mut FsckReport,
+) -> Result<(), Error> {
+    for (book_id, book_path, has_cover) in books {
+        let webp_path = get_book_metadata_path(library_path, book_path, WEBP_COVER_FILE);
+        let webp_exists = !book_path.is_empty() && webp_path.is_file();
+        if *has_cover == webp_exists {
+            continue;
+        }
+
+        let issue = if *has_cover {
+            let fixed = fix && {
+                let cover_path = get_book_metadata_path(library_path, book_path, COVER_FILE);
+                if !book_path.is_empty()
+                    && cover_path.is_file()
+                    && convert_cover(&cover_path).is_ok()
+                {
+                    log::info!("Webp covers of book {book_id} are generated again");
+                } else {
+                    update_book_cover(pg_conn, *book_id, false)?;
+                }
+                true
+            };
+            FsckIssue {
+                kind: FsckIssueKind::MissingCover,
+                book: Some(*book_id),
+                path: format!("{book_path}/{WEBP_COVER_FILE}"),
+                message: "Cover of book does not exist".to_owned(),
+                fixed,
+            }
+        } else {
+            let fixed = fix && {
+                update_book_cover(pg_conn, *book_id, true)?;
+                true
+            };
+            FsckIssue {
+                kind: FsckIssueKind::UnknownCover,
+                book: Some(*book_id),
+                path: format!("{book_path}/{WEBP_COVER_FILE}"),
+                message: "Cover exists but book is marked without cover".to_owned(),
+                fixed,
+            }
+        };
+        report.issues.push(issue);
+    }
+    Ok(())
+}
+
+/// Find directories in library which do not belong to any book.
+///
+/// Book directories are `{author}/{title} ({book-id})`, hidden directories
+/// like `.trash` are skipped.
+fn check_orphan_dirs(
+    library_path: &str,
+    books: &[(i32, String, bool)],
+    report: &mut FsckReport,
+) -> Result<(), Error> {
+    let book_dirs: HashSet<&str> = books
+        .iter()
+        .map(|(_book_id, book_path, _has_cover)| book_path.as_str())
+        .filter(|book_path| !book_path.is_empty())
+        .collect();
+    let author_dirs: HashSet<&str> = book_dirs
+        .iter()
+        .filter_map(|book_path| book_path.split('/').next())
+        .collect();
+
+    let mut orphan_dirs = Vec::new();
+    for author_dir in list_sub_dirs(Path::new(library_path))? {
+        if !author_dirs.contains(author_dir.as_str()) {
+            orphan_dirs.push(author_dir);
+            continue;
+        }
+        for book_dir in list_sub_dirs(&Path::new(library_path).join(&author_dir))? {
+            let book_path = format!("{author_dir}/{book_dir}");
+            if !book_dirs.contains(book_path.as_str()) {
+                orphan_dirs.push(book_path);
+            }
+        }
+    }
+
+    orphan_dirs.sort();
+    for path in orphan_dirs {
+        report.issues.push(FsckIssue {
+            kind: FsckIssueKind::OrphanDir,
+            book: None,
+            path,
+            message: "Directory does not belong to any book".to_owned(),
+            fixed: false,
+        });
+    }
+    Ok(())
+}
+
+/// Get names of non-hidden sub directories.
+fn list_sub_dirs(dir: &Path) -> Result<Vec<String>, Error> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with('.') && entry.file_type()?.is_dir() {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+#[derive(Debug, Clone, Copy, QueryableByName)]
+struct DanglingLink {
+    #[diesel(sql_type = Integer)]
+    id: i32,
+    #[diesel(sql_type = Integer)]
+    book: i32,
+}
+
+fn check_dangling_links(
+    pg_conn: &mut PgConnection,
+    fix: bool,
+    report: &mut FsckReport,
+) -> Result<(), Error> {
+    for table in LINK_TABLES {
+        let links = sql_query(format!(
+            "SELECT t.id, t.book FROM {table} t \
+             WHERE NOT EXISTS (SELECT 1 FROM books WHERE books.id = t.book) \
+             ORDER BY t.id"
+        ))
+        .load::<DanglingLink>(pg_conn)?;
+        if links.is_empty() {
+            continue;
+        }
+
+        let fixed = fix && {
+            sql_query(format!(
+                "DELETE FROM {table} t \
+                 WHERE NOT EXISTS (SELECT 1 FROM books WHERE books.id = t.book)"
+            ))
+            .execute(pg_conn)?;
+            true
+        };
+        for link in links {
+            report.issues.push(FsckIssue {
+                kind: FsckIssueKind::DanglingLink,
+                book: Some(link.book),
+                path: (*table).to_owned(),
+                message: format!("Row {} refers to missing book", link.id),
+                fixed,
+            });
+        }
+    }
+    Ok(())
+}
@@ -3,40 +3,42 @@
 // that can be found in the LICENSE file.
 
 mod db;
+mod duplication;
 mod files;
 
-use calibre::models::books::{get_next_book, CalibreBook};
+use calibre::models::books::{get_book, get_next_book, get_total_books, CalibreBook};
+use chrono::Datelike;
 use diesel::{PgConnection, SqliteConnection};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
 
-use crate::error::Error;
-use crate::import::models::books::{add_import_book, NewImportBook};
+use crate::db::DbPool;
+use crate::error::{Error, ErrorKind};
+use crate::import::db::CalibreDbPool;
+use crate::import::journal::{run_with_journal, FileJournal};
+use crate::import::models::books::{
+    add_import_book, delete_import_book, get_failed_import_books, NewImportBook,
+};
 use crate::import::models::libraries::{update_import_library, ImportLibrary};
 use crate::import::options::ImportBookOptions;
-use crate::models::books::{add_book, Book, NewBook};
+use crate::import::progress::Progress;
+use crate::import::synced_metadata::read_calibre_metadata;
+use crate::models::books::{add_book, title_sort_of, Book, NewBook};
 use db::import_book_detail;
+use duplication::{find_duplicated_book, lock_duplication_keys};
 use files::copy_book_files;
 
-/// Add book record to pgsql database.
-fn add_book_record(
+/// Get next book in calibre library.
+fn get_next_calibre_book(
     calibre_library_path: &str,
     sqlite_conn: &mut SqliteConnection,
-    pg_conn: &mut PgConnection,
     last_book_id: i32,
-) -> Result<Option<(CalibreBook, Book)>, Error> {
+) -> Result<Option<CalibreBook>, Error> {
     log::info!("import_book({}, {})", calibre_library_path, last_book_id);
     match get_next_book(sqlite_conn, last_book_id) {
-        Ok(calibre_book) => {
-            let calibre_book_clone: CalibreBook = calibre_book.clone();
-            let new_book = NewBook {
-                title: calibre_book.title.clone(),
-                path: calibre_book.path,
-                author_sort: calibre_book.author_sort.unwrap_or_default(),
-                uuid: calibre_book.uuid,
-                has_cover: calibre_book.has_cover,
-            };
-            let book = add_book(pg_conn, &new_book)?;
-            Ok(Some((calibre_book_clone, book)))
-        }
+        Ok(calibre_book) => Ok(Some(calibre_book)),
         Err(err) => match err.kind() {
             calibre::error::ErrorKind::DbNotFoundError => {
                 log::info!(
@@ -51,78 +53,351 @@ fn add_book_record(
     }
 }
 
+/// Label of custom field imported from calibre custom column.
+#[must_use]
+pub fn custom_column_label(label: &str) -> String {
+    format!("#{label}")
+}
+
+/// Label of custom field imported from calibre plugin data.
+#[must_use]
+pub fn plugin_data_label(name: &str) -> String {
+    format!("plugin:{name}")
+}
+
+/// Calibre uses `0101-01-01` as undefined date.
+const UNDEFINED_YEAR: i32 = 101;
+
+/// Convert calibre book to new book record.
+#[must_use]
+pub fn new_book_from_calibre(calibre_book: &CalibreBook) -> NewBook {
+    NewBook {
+        title: calibre_book.title.clone(),
+        path: calibre_book.path.clone(),
+        author_sort: calibre_book.author_sort.clone().unwrap_or_default(),
+        uuid: calibre_book.uuid.clone(),
+        has_cover: calibre_book.has_cover,
+        pubdate: calibre_book
+            .pubdate
+            .filter(|pubdate| pubdate.year() > UNDEFINED_YEAR),
+        created: calibre_book.timestamp,
+        last_modified: Some(calibre_book.last_modified),
+        title_sort: calibre_book
+            .sort
+            .clone()
+            .unwrap_or_else(|| title_sort_of(&calibre_book.title)),
+    }
+}
+
+/// Add book record to pgsql database.
+fn add_book_record(pg_conn: &mut PgConnection, calibre_book: &CalibreBook) -> Result<Book, Error> {
+    add_book(pg_conn, &new_book_from_calibre(calibre_book))
+}
+
+fn record_import_book(pg_conn: &mut PgConnection, new_book: &NewImportBook) {
+    if let Err(err) = add_import_book(pg_conn, new_book) {
+        log::error!("Failed to add import-book {:?}, err: {}", new_book, err);
+    }
+}
+
 fn do_import_book(
     calibre_library_path: &str,
     library_path: &str,
     sqlite_conn: &mut SqliteConnection,
     pg_conn: &mut PgConnection,
     calibre_book: &CalibreBook,
-    book: &Book,
     options: &ImportBookOptions,
-) -> Result<(), Error> {
+    journal: &mut FileJournal,
+) -> Result<Book, Error> {
+    let book = add_book_record(pg_conn, calibre_book)?;
     copy_book_files(
         calibre_library_path,
         library_path,
         sqlite_conn,
         pg_conn,
         calibre_book,
-        book,
+        &book,
         options,
+        journal,
     )?;
 
-    import_book_detail(sqlite_conn, pg_conn, calibre_book.id, book.id)
+    import_book_detail(sqlite_conn, pg_conn, calibre_book, book.id)?;
+    Ok(book)
 }
 
-pub fn import_books(
+/// Import one calibre book and record result in `import_books` table.
+///
+/// All database changes of this book are made in one transaction, and file
+/// operations are reverted if it fails.
+///
+/// Returns false if this book failed to import.
+fn import_book(
     sqlite_conn: &mut SqliteConnection,
     pg_conn: &mut PgConnection,
     import_library: &ImportLibrary,
     options: &ImportBookOptions,
-    mut last_book_id: i32,
-) -> Result<(), Error> {
-    log::info!("import_books({:?})", &import_library);
+    calibre_book: &CalibreBook,
+) -> bool {
+    let ret = run_with_journal(pg_conn, |pg_conn, journal| {
+        if !options.allow_duplication {
+            // Held until the book is recorded, so that the same book imported
+            // by other workers is found.
+            lock_duplication_keys(sqlite_conn, pg_conn, calibre_book)?;
+            if let Some(duplicated) = find_duplicated_book(sqlite_conn, pg_conn, calibre_book)? {
+                log::info!(
+                    "Skip calibre book {}, link to book {}, {}",
+                    calibre_book.id,
+                    duplicated.book,
+                    duplicated.reason
+                );
+                let new_book = NewImportBook {
+                    library: import_library.id,
+                    calibre_book: calibre_book.id,
+                    ok: true,
+                    book: Some(duplicated.book),
+                    skip_reason: Some(duplicated.reason),
+                    error_kind: None,
+                    error_message: None,
+                    calibre_last_modified: None,
+                    synced_metadata: None,
+                };
+                return add_import_book(pg_conn, &new_book);
+            }
+        }
 
-    loop {
-        match add_book_record(
+        let book = do_import_book(
             &import_library.calibre_library_path,
+            &import_library.library_path,
             sqlite_conn,
             pg_conn,
-            last_book_id,
-        ) {
-            Ok(Some((calibre_book, book))) => {
-                last_book_id = calibre_book.id;
-                log::info!("last book id updated: {}", last_book_id);
-
-                let ok = if let Err(err) = do_import_book(
-                    &import_library.calibre_library_path,
-                    &import_library.library_path,
-                    sqlite_conn,
-                    pg_conn,
-                    &calibre_book,
-                    &book,
-                    options,
-                ) {
-                    log::warn!("Failed to import book: {:?}, err: {:?}", &calibre_book, err);
-                    false
-                } else {
-                    true
-                };
+            calibre_book,
+            options,
+            journal,
+        )?;
+        let new_book = NewImportBook {
+            library: import_library.id,
+            calibre_book: calibre_book.id,
+            ok: true,
+            book: Some(book.id),
+            skip_reason: None,
+            error_kind: None,
+            error_message: None,
+            calibre_last_modified: Some(calibre_book.last_modified),
+            synced_metadata: Some(serde_json::to_string(&read_calibre_metadata(
+                sqlite_conn,
+                calibre_book,
+            )?)?),
+        };
+        add_import_book(pg_conn, &new_book)
+    });
 
-                let new_book = NewImportBook {
-                    library: import_library.id,
-                    calibre_book: calibre_book.id,
-                    ok,
-                    book: Some(book.id),
-                };
-                if let Err(err) = add_import_book(pg_conn, &new_book) {
-                    log::error!("Failed to add import-book {:?}, err: {}", new_book, err);
-                }
+    match ret {
+        Ok(()) => true,
+        Err(err) => {
+            log::warn!("Failed to import book: {calibre_book:?}, err: {err:?}");
+            let new_book = NewImportBook {
+                library: import_library.id,
+                calibre_book: calibre_book.id,
+                ok: false,
+                book: None,
+                skip_reason: None,
+                error_kind: Some(format!("{:?}", err.kind())),
+                error_message: Some(err.message().to_owned()),
+                calibre_last_modified: None,
+                synced_metadata: None,
+            };
+            record_import_book(pg_conn, &new_book);
+            false
+        }
+    }
+}
+
+/// Worker thread of import pipeline.
+///
+/// Receives calibre books from `receiver` until the channel is closed.
+/// Failed books are recorded and skipped. If database connections can not be
+/// taken, `stopped` is set and remaining books are drained.
+fn import_worker(
+    calibre_pool: &CalibreDbPool,
+    pg_pool: &DbPool,
+    import_library: &ImportLibrary,
+    options: &ImportBookOptions,
+    receiver: &Mutex<mpsc::Receiver<CalibreBook>>,
+    stopped: &AtomicBool,
+    progress: &Progress,
+) -> Result<(), Error> {
+    let conns = calibre_pool
+        .get()
+        .map_err(Error::from)
+        .and_then(|sqlite_conn| Ok((sqlite_conn, pg_pool.get()?)));
+    let (mut sqlite_conn, mut pg_conn) = match conns {
+        Ok(conns) => conns,
+        Err(err) => {
+            stopped.store(true, Ordering::Relaxed);
+            return Err(err);
+        }
+    };
+
+    loop {
+        let calibre_book = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_err) => break,
+        };
+        // Channel is closed.
+        let Ok(calibre_book) = calibre_book else {
+            break;
+        };
+
+        let ok = import_book(
+            &mut sqlite_conn,
+            &mut pg_conn,
+            import_library,
+            options,
+            &calibre_book,
+        );
+        progress.inc(ok);
+    }
+    Ok(())
+}
+
+/// Import calibre books, except books in `imported`.
+///
+/// Metadata is read in ascending order of calibre book id and dispatched to
+/// `jobs` worker threads, each of which holds its own database connections.
+///
+/// No more books are dispatched after `progress` is cancelled.
+#[allow(clippy::too_many_arguments)]
+pub fn import_books(
+    calibre_pool: &CalibreDbPool,
+    pg_conn: &mut PgConnection,
+    pg_pool: &DbPool,
+    import_library: &ImportLibrary,
+    options: &ImportBookOptions,
+    imported: &HashSet<i32>,
+    jobs: usize,
+    progress: &Progress,
+) -> Result<(), Error> {
+    log::info!("import_books({:?}), jobs: {jobs}", &import_library);
+    let jobs = jobs.max(1);
+    let mut sqlite_conn = calibre_pool.get()?;
+
+    #[allow(clippy::cast_sign_loss)]
+    let total = (get_total_books(&mut sqlite_conn)? as u64).saturating_sub(imported.len() as u64);
+    progress.set_total(total);
+    let stopped = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::sync_channel::<CalibreBook>(jobs * 2);
+    let receiver = Mutex::new(receiver);
+
+    let (producer_ret, worker_rets) = thread::scope(|scope| {
+        let handles: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    import_worker(
+                        calibre_pool,
+                        pg_pool,
+                        import_library,
+                        options,
+                        &receiver,
+                        &stopped,
+                        progress,
+                    )
+                })
+            })
+            .collect();
+
+        let mut last_book_id = 0;
+        let producer_ret = loop {
+            if stopped.load(Ordering::Relaxed) || progress.is_cancelled() {
+                break Ok(false);
             }
-            Ok(None) => {
-                log::info!("All books are imported: {:?}", import_library);
-                return update_import_library(pg_conn, import_library.id, true);
+            let calibre_book = match get_next_calibre_book(
+                &import_library.calibre_library_path,
+                &mut sqlite_conn,
+                last_book_id,
+            ) {
+                Ok(Some(calibre_book)) => calibre_book,
+                Ok(None) => break Ok(true),
+                Err(err) => break Err(err),
+            };
+            last_book_id = calibre_book.id;
+            if imported.contains(&calibre_book.id) {
+                continue;
             }
-            Err(err) => return Err(err),
+            if sender.send(calibre_book).is_err() {
+                // All of workers exited.
+                break Ok(false);
+            }
+        };
+        drop(sender);
+
+        let worker_rets: Vec<Result<(), Error>> = handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_err| {
+                    Err(Error::new(ErrorKind::ImportError, "Import worker panicked"))
+                })
+            })
+            .collect();
+        (producer_ret, worker_rets)
+    });
+    progress.finish();
+
+    for ret in worker_rets {
+        ret?;
+    }
+    if producer_ret? {
+        log::info!("All books are imported: {:?}", import_library);
+        update_import_library(pg_conn, import_library.id, true)
+    } else if progress.is_cancelled() {
+        Err(Error::from_string(
+            ErrorKind::ImportError,
+            format!("Import library {} is cancelled", import_library.id),
+        ))
+    } else {
+        Err(Error::new(
+            ErrorKind::ImportError,
+            "Import workers stopped unexpectedly",
+        ))
+    }
+}
+
+/// Import failed books in `import_library` again.
+pub fn retry_failed_books(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    import_library: &ImportLibrary,
+    options: &ImportBookOptions,
+) -> Result<(), Error> {
+    let failed_books = get_failed_import_books(pg_conn, import_library.id)?;
+    log::info!(
+        "Retry {} failed books in import library {}",
+        failed_books.len(),
+        import_library.id
+    );
+
+    for failed_book in failed_books {
+        if let Some(book_id) = failed_book.book {
+            // Retrying would match the partial book as a duplicate.
+            log::warn!(
+                "Skip calibre book {}, it was partially imported as book {}, remove it manually",
+                failed_book.calibre_book,
+                book_id
+            );
+            continue;
         }
+        let calibre_book = match get_book(sqlite_conn, failed_book.calibre_book) {
+            Ok(calibre_book) => calibre_book,
+            Err(err) => {
+                log::warn!(
+                    "Skip calibre book {}, failed to read it: {err:?}",
+                    failed_book.calibre_book
+                );
+                continue;
+            }
+        };
+        import_book(sqlite_conn, pg_conn, import_library, options, &calibre_book);
+        delete_import_book(pg_conn, failed_book.id)?;
     }
+
+    Ok(())
 }
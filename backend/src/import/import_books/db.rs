@@ -2,17 +2,23 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+use calibre::models::books::CalibreBook;
 use calibre::models::books_authors::get_book_authors;
 use calibre::models::books_languages::get_book_language;
+use calibre::models::books_plugin_data::get_book_plugin_data;
 use calibre::models::books_publishers::get_book_publisher;
 use calibre::models::books_ratings::get_book_rating;
 use calibre::models::books_series::get_book_series;
 use calibre::models::books_tags::get_book_tags;
 use calibre::models::comments::get_comment;
+use calibre::models::custom_columns::{get_custom_column_values, get_custom_columns};
 use calibre::models::identifiers::get_identifiers;
+use chrono::{DateTime, NaiveDateTime};
 use diesel::{PgConnection, SqliteConnection};
+use shared::custom_fields::CustomFieldType;
 
 use crate::error::Error;
+use crate::import::import_books::{custom_column_label, plugin_data_label};
 use crate::models::authors::get_author_by_name;
 use crate::models::books_authors::{add_book_author, NewBookAuthor};
 use crate::models::books_languages::{add_book_language, NewBookLanguage};
@@ -20,6 +26,9 @@ use crate::models::books_publishers::{add_book_publisher, NewBookPublisher};
 use crate::models::books_series::{add_book_series, NewBookSeries};
 use crate::models::books_tags::{add_book_tag, NewBookTag};
 use crate::models::comments::{add_comment, NewComment};
+use crate::models::custom_fields::{
+    add_custom_field_value, get_custom_field_by_label, NewCustomFieldValue,
+};
 use crate::models::identifier_types::get_identifier_type_by_name;
 use crate::models::identifiers::{add_identifier, NewIdentifier};
 use crate::models::languages::get_language_by_name;
@@ -152,6 +161,7 @@ fn import_series(
     sqlite_conn: &mut SqliteConnection,
     pg_conn: &mut PgConnection,
     calibre_book_id: i32,
+    series_index: f32,
     book_id: i32,
 ) -> Result<(), Error> {
     log::info!("import_series({}, {})", calibre_book_id, book_id);
@@ -162,6 +172,7 @@ fn import_series(
             let new_series = NewBookSeries {
                 book: book_id,
                 series: series.id,
+                series_index,
             };
             add_book_series(pg_conn, &new_series)
         }
@@ -217,22 +228,125 @@ fn import_tags(
     Ok(())
 }
 
+/// Parse datetime value of calibre custom column, like `2023-01-02 03:04:05+00:00`.
+fn parse_custom_datetime(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%:z")
+        .map(|datetime| datetime.naive_utc())
+        .or_else(|_err| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+}
+
+/// Convert text value read from calibre to typed value.
+///
+/// Value is kept as text if it does not match datatype of field.
+fn new_custom_field_value(
+    book_id: i32,
+    field_id: i32,
+    datatype: CustomFieldType,
+    value: String,
+) -> NewCustomFieldValue {
+    let mut new_value = NewCustomFieldValue {
+        book: book_id,
+        field: field_id,
+        ..NewCustomFieldValue::default()
+    };
+    match datatype {
+        CustomFieldType::Int | CustomFieldType::Rating => {
+            new_value.int_value = value.parse().ok();
+        }
+        CustomFieldType::Float => new_value.float_value = value.parse().ok(),
+        CustomFieldType::Bool => {
+            new_value.bool_value = match value.as_str() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            };
+        }
+        CustomFieldType::Datetime => new_value.date_value = parse_custom_datetime(&value),
+        CustomFieldType::Text
+        | CustomFieldType::Comments
+        | CustomFieldType::Series
+        | CustomFieldType::Enumeration
+        | CustomFieldType::Json => {}
+    }
+
+    if new_value.int_value.is_none()
+        && new_value.float_value.is_none()
+        && new_value.bool_value.is_none()
+        && new_value.date_value.is_none()
+    {
+        new_value.text_value = Some(value);
+    }
+    new_value
+}
+
+fn import_custom_columns(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book_id: i32,
+    book_id: i32,
+) -> Result<(), Error> {
+    log::info!("import_custom_columns({calibre_book_id}, {book_id})");
+    for column in get_custom_columns(sqlite_conn)? {
+        let Some(datatype) = CustomFieldType::parse(&column.datatype) else {
+            continue;
+        };
+        let values = get_custom_column_values(sqlite_conn, &column, calibre_book_id)?;
+        if values.is_empty() {
+            continue;
+        }
+        let field = get_custom_field_by_label(pg_conn, &custom_column_label(&column.label))?;
+        for value in values {
+            let new_value = new_custom_field_value(book_id, field.id, datatype, value);
+            add_custom_field_value(pg_conn, &new_value)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn import_plugin_data(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book_id: i32,
+    book_id: i32,
+) -> Result<(), Error> {
+    log::info!("import_plugin_data({calibre_book_id}, {book_id})");
+    for plugin_data in get_book_plugin_data(sqlite_conn, calibre_book_id)? {
+        let field = get_custom_field_by_label(pg_conn, &plugin_data_label(&plugin_data.name))?;
+        let new_value =
+            new_custom_field_value(book_id, field.id, CustomFieldType::Json, plugin_data.val);
+        add_custom_field_value(pg_conn, &new_value)?;
+    }
+
+    Ok(())
+}
+
 /// Import book details to pgsql database.
 ///
 /// Copy book files and metadata (including cover image) if required.
 pub fn import_book_detail(
     sqlite_conn: &mut SqliteConnection,
     pg_conn: &mut PgConnection,
-    calibre_book_id: i32,
+    calibre_book: &CalibreBook,
     book_id: i32,
 ) -> Result<(), Error> {
+    let calibre_book_id = calibre_book.id;
     import_authors(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     import_comment(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     import_identifiers(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     import_language(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     import_publisher(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
-    import_series(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
+    import_series(
+        sqlite_conn,
+        pg_conn,
+        calibre_book_id,
+        calibre_book.series_index,
+        book_id,
+    )?;
     import_rating(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     import_tags(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
+    import_custom_columns(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
+    import_plugin_data(sqlite_conn, pg_conn, calibre_book_id, book_id)?;
     Ok(())
 }
@@ -0,0 +1,190 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use calibre::models::books::CalibreBook;
+use calibre::models::books_authors::get_book_authors;
+use calibre::models::identifiers::get_identifiers;
+use diesel::sql_types::{Array, Integer, Text};
+use diesel::{PgConnection, RunQueryDsl, SqliteConnection};
+use std::collections::BTreeSet;
+
+use crate::db::DUPLICATION_LOCK;
+use crate::error::{Error, ErrorKind};
+use crate::models::authors::get_authors_by_book_id;
+use crate::models::books::{get_book_id_by_uuid, get_books_by_title};
+use crate::models::identifier_types::get_identifier_type_by_name;
+use crate::models::identifiers::get_books_by_identifier;
+
+const ISBN_TYPE: &str = "isbn";
+
+/// An existing book which matches the calibre book.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatedBook {
+    pub book: i32,
+    pub reason: String,
+}
+
+/// Remove dashes and spaces in isbn.
+fn normalize_isbn(isbn: &str) -> String {
+    isbn.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Lowercase, remove punctuations and merge whitespaces.
+///
+/// Titles are normalized in database by `pengzu_normalize_title()` in the same way.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn find_by_uuid(
+    pg_conn: &mut PgConnection,
+    calibre_book: &CalibreBook,
+) -> Result<Option<DuplicatedBook>, Error> {
+    match get_book_id_by_uuid(pg_conn, &calibre_book.uuid) {
+        Ok(book_id) => Ok(Some(DuplicatedBook {
+            book: book_id,
+            reason: format!("duplicated uuid: {}", calibre_book.uuid),
+        })),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => Ok(None),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Get isbn of calibre book, both raw and normalized values.
+fn get_isbn_values(
+    sqlite_conn: &mut SqliteConnection,
+    calibre_book: &CalibreBook,
+) -> Result<Vec<String>, Error> {
+    let mut isbn_list: Vec<String> = get_identifiers(sqlite_conn, calibre_book.id)?
+        .into_iter()
+        .filter(|identifier| identifier.type_.eq_ignore_ascii_case(ISBN_TYPE))
+        .map(|identifier| identifier.val)
+        .collect();
+    if let Some(isbn) = &calibre_book.isbn {
+        isbn_list.push(isbn.clone());
+    }
+    let mut values = Vec::new();
+    for isbn in isbn_list {
+        let normalized = normalize_isbn(&isbn);
+        if normalized.is_empty() {
+            continue;
+        }
+        if normalized != isbn {
+            values.push(normalized);
+        }
+        values.push(isbn);
+    }
+    Ok(values)
+}
+
+fn find_by_isbn(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book: &CalibreBook,
+) -> Result<Option<DuplicatedBook>, Error> {
+    let values = get_isbn_values(sqlite_conn, calibre_book)?;
+    if values.is_empty() {
+        return Ok(None);
+    }
+
+    let isbn_type = match get_identifier_type_by_name(pg_conn, ISBN_TYPE) {
+        Ok(isbn_type) => isbn_type,
+        Err(err) => {
+            return match err.kind() {
+                ErrorKind::DbNotFoundError => Ok(None),
+                _ => Err(err),
+            }
+        }
+    };
+    let book_ids = get_books_by_identifier(pg_conn, isbn_type.id, &values)?;
+    Ok(book_ids.first().map(|book_id| DuplicatedBook {
+        book: *book_id,
+        reason: format!("duplicated isbn: {}", values.join(", ")),
+    }))
+}
+
+fn find_by_title_and_authors(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book: &CalibreBook,
+) -> Result<Option<DuplicatedBook>, Error> {
+    let book_list = get_books_by_title(pg_conn, &calibre_book.title)?;
+    if book_list.is_empty() {
+        return Ok(None);
+    }
+
+    let calibre_authors: BTreeSet<String> = get_book_authors(sqlite_conn, calibre_book.id)?
+        .iter()
+        .map(|author| normalize_name(&author.name))
+        .collect();
+    let author_list = get_authors_by_book_id(pg_conn, &book_list)?;
+    for book in &book_list {
+        let authors: BTreeSet<String> = author_list
+            .iter()
+            .filter(|author| author.book == book.id)
+            .map(|author| normalize_name(&author.name))
+            .collect();
+        if authors == calibre_authors {
+            return Ok(Some(DuplicatedBook {
+                book: book.id,
+                reason: format!("duplicated title and authors: {}", calibre_book.title),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Lock uuid, isbn and normalized title of calibre book until current transaction ends.
+///
+/// Books sharing any of these keys are checked and imported one by one, so that
+/// parallel workers do not import the same book twice. Keys are locked in
+/// ascending order of their hash to avoid deadlock.
+pub fn lock_duplication_keys(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book: &CalibreBook,
+) -> Result<(), Error> {
+    let mut keys = vec![format!("uuid:{}", calibre_book.uuid)];
+    for isbn in get_isbn_values(sqlite_conn, calibre_book)? {
+        keys.push(format!("isbn:{}", normalize_isbn(&isbn)));
+    }
+    diesel::sql_query(
+        "SELECT pg_advisory_xact_lock($1, key) FROM ( \
+            SELECT DISTINCT hashtext(k) AS key \
+            FROM unnest($2 || ('title:' || pengzu_normalize_title($3))) AS k \
+            ORDER BY key) AS keys",
+    )
+    .bind::<Integer, _>(DUPLICATION_LOCK)
+    .bind::<Array<Text>, _>(&keys)
+    .bind::<Text, _>(&calibre_book.title)
+    .execute(pg_conn)?;
+    Ok(())
+}
+
+/// Find an existing book which matches calibre book.
+///
+/// Books are matched by uuid, isbn, and then normalized title plus author set.
+pub fn find_duplicated_book(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    calibre_book: &CalibreBook,
+) -> Result<Option<DuplicatedBook>, Error> {
+    if let Some(book) = find_by_uuid(pg_conn, calibre_book)? {
+        return Ok(Some(book));
+    }
+    if let Some(book) = find_by_isbn(sqlite_conn, pg_conn, calibre_book)? {
+        return Ok(Some(book));
+    }
+    find_by_title_and_authors(sqlite_conn, pg_conn, calibre_book)
+}
@@ -5,17 +5,18 @@
 use calibre::models::books::CalibreBook;
 use calibre::models::data::get_book_data;
 use diesel::{PgConnection, SqliteConnection};
-use std::fs;
 
 use crate::error::{Error, ErrorKind};
 use crate::import::convert::convert_cover;
 use crate::import::file_util;
-use crate::import::file_util::{get_book_file_path, get_book_metadata_path};
+use crate::import::file_util::{get_book_file_path, get_book_metadata_path, sha256sum};
+use crate::import::journal::FileJournal;
 use crate::import::options::{ImportBookFileAction, ImportBookOptions};
 use crate::models::books::Book;
 use crate::models::file_formats::get_file_format_by_name;
 use crate::models::files::{add_file, NewFile};
 
+#[allow(clippy::too_many_arguments)]
 fn copy_book_file(
     calibre_library_path: &str,
     library_path: &str,
@@ -24,6 +25,7 @@ fn copy_book_file(
     file_name: &str,
     format: &str,
     options: &ImportBookOptions,
+    journal: &mut FileJournal,
 ) -> Result<(), Error> {
     log::info!("copy_book_file({}/{}.{})", book_path, file_name, format);
 
@@ -41,11 +43,11 @@ fn copy_book_file(
             format!("Failed to get parent dir: {:?}", &dest_path),
         )
     })?;
-    file_util::create_dir_all_and_chown(parent_dir, options.uid, options.gid)?;
+    journal.create_dir_all(parent_dir, options.uid, options.gid)?;
     if move_files {
-        fs::rename(&src_path, &dest_path)?;
+        journal.rename(&src_path, &dest_path)?;
     } else {
-        fs::copy(&src_path, &dest_path).map(drop)?;
+        journal.copy(&src_path, &dest_path)?;
     }
     file_util::chown(&dest_path, options.uid, options.gid)
 }
@@ -56,6 +58,7 @@ fn copy_book_metadata_opf(
     calibre_book_path: &str,
     book_path: &str,
     options: &ImportBookOptions,
+    journal: &mut FileJournal,
 ) -> Result<(), Error> {
     let file_name = "metadata.opf";
     log::info!("copy_book_metadata_opf({}/{})", book_path, file_name);
@@ -73,11 +76,11 @@ fn copy_book_metadata_opf(
             format!("Failed to get parent dir: {:?}", &dest_path),
         )
     })?;
-    file_util::create_dir_all_and_chown(parent_dir, options.uid, options.gid)?;
+    journal.create_dir_all(parent_dir, options.uid, options.gid)?;
     if move_files {
-        fs::rename(&src_path, &dest_path)?;
+        journal.rename(&src_path, &dest_path)?;
     } else {
-        fs::copy(&src_path, &dest_path).map(drop)?;
+        journal.copy(&src_path, &dest_path)?;
     }
     file_util::chown(&dest_path, options.uid, options.gid)
 }
@@ -88,6 +91,7 @@ fn copy_book_cover(
     calibre_book_path: &str,
     book_path: &str,
     options: &ImportBookOptions,
+    journal: &mut FileJournal,
 ) -> Result<(), Error> {
     log::info!("copy_book_cover({})", book_path);
 
@@ -106,19 +110,22 @@ fn copy_book_cover(
             format!("Failed to get parent dir: {:?}", &dest_path),
         )
     })?;
-    file_util::create_dir_all_and_chown(parent_dir, options.uid, options.gid)?;
+    journal.create_dir_all(parent_dir, options.uid, options.gid)?;
     if move_files {
-        fs::rename(&src_path, &dest_path).map(drop)?;
+        journal.rename(&src_path, &dest_path)?;
     } else {
-        fs::copy(&src_path, &dest_path).map(drop)?;
+        journal.copy(&src_path, &dest_path)?;
     }
     file_util::chown(&dest_path, options.uid, options.gid)?;
 
     let (webp_path, small_webp_path) = convert_cover(&dest_path)?;
+    journal.add_file(&webp_path);
+    journal.add_file(&small_webp_path);
     file_util::chown(webp_path, options.uid, options.gid)?;
     file_util::chown(small_webp_path, options.uid, options.gid)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn copy_book_files(
     calibre_library_path: &str,
     library_path: &str,
@@ -128,6 +135,7 @@ pub fn copy_book_files(
     calibre_book: &CalibreBook,
     book: &Book,
     options: &ImportBookOptions,
+    journal: &mut FileJournal,
 ) -> Result<(), Error> {
     let calibre_book_id: i32 = calibre_book.id;
     let calibre_book_path: &str = &calibre_book.path;
@@ -144,6 +152,7 @@ pub fn copy_book_files(
         calibre_book_path,
         book_path,
         options,
+        journal,
     ) {
         log::warn!("Failed to copy book cover: {:?}", err);
     }
@@ -154,6 +163,7 @@ pub fn copy_book_files(
         calibre_book_path,
         book_path,
         options,
+        journal,
     ) {
         log::warn!("Failed to copy book metadata.opf: {:?}", err);
     }
@@ -169,13 +179,41 @@ pub fn copy_book_files(
             &calibre_file.name,
             &calibre_file.format,
             options,
+            journal,
         )?;
 
+        // Files are kept in calibre library if they are not copied.
+        // Checksum is left empty if neither of them can be read, and is computed
+        // later by `backend checksum`.
+        let sha256 = sha256sum(get_book_file_path(
+            library_path,
+            book_path,
+            &calibre_file.name,
+            &calibre_file.format,
+        ))
+        .or_else(|_| {
+            sha256sum(get_book_file_path(
+                calibre_library_path,
+                calibre_book_path,
+                &calibre_file.name,
+                &calibre_file.format,
+            ))
+        })
+        .map_err(|err| {
+            log::warn!(
+                "Failed to compute checksum of {}.{}, err: {err:?}",
+                calibre_file.name,
+                calibre_file.format
+            );
+        })
+        .ok();
+
         let new_file = NewFile {
             book: book_id,
             format: file_format.id,
             size: calibre_file.uncompressed_size,
             name: calibre_file.name,
+            sha256,
         };
         add_file(pg_conn, &new_file)?;
     }
@@ -0,0 +1,511 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Import ebook files in a folder, with metadata embedded in each file.
+
+use cip::formats::metadata::{read_metadata, FileMetadata};
+use diesel::PgConnection;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::get_connection_pool;
+use crate::error::{Error, ErrorKind};
+use crate::import::convert::convert_cover;
+use crate::import::file_util;
+use crate::import::file_util::{
+    get_book_file_path, get_book_metadata_path, get_file_size, sha256sum,
+};
+use crate::import::journal::{run_with_journal, FileJournal};
+use crate::import::options::{ImportBookFileAction, ImportBookOptions};
+use crate::import::progress::Progress;
+use crate::models::authors::get_or_add_author;
+use crate::models::books::{add_book, title_sort_of, update_book_path, Book, NewBook};
+use crate::models::books_authors::{add_book_author, NewBookAuthor};
+use crate::models::books_languages::{add_book_language, NewBookLanguage};
+use crate::models::books_publishers::{add_book_publisher, NewBookPublisher};
+use crate::models::file_formats::{add_file_format, get_file_format_by_name, NewFileFormat};
+use crate::models::files::{add_file, NewFile};
+use crate::models::identifier_types::{
+    add_identifier_type, get_identifier_type_by_name, NewIdentifierType,
+};
+use crate::models::identifiers::{add_identifier, get_books_by_identifier, NewIdentifier};
+use crate::models::languages::get_or_add_language;
+use crate::models::publishers::get_or_add_publisher;
+
+/// File extensions which can be imported.
+pub const SUPPORTED_FORMATS: &[&str] = &["epub", "pdf", "mobi", "azw", "azw3"];
+
+const UNKNOWN_AUTHOR: &str = "Unknown";
+const ISBN_SCHEME: &str = "isbn";
+
+/// Max number of chars in each path component.
+const MAX_PATH_COMPONENT: usize = 64;
+
+/// Result of importing one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFileResult {
+    Imported(i32),
+
+    /// Book with the same ISBN exists.
+    Duplicated(i32),
+}
+
+#[must_use]
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            SUPPORTED_FORMATS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
+
+/// Get all of ebook files in `dir` recursively, sorted by path.
+///
+/// Symbolic links are skipped, so that files out of `dir` are not imported
+/// and loops of links are not followed.
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_symlink() {
+            log::info!("Skip symbolic link: {}", path.display());
+        } else if file_type.is_dir() {
+            walk_dir(&path, files)?;
+        } else if is_supported_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Replace chars which are invalid in file name, and limit its length to `max_len` chars.
+fn sanitize_chars(name: &str, max_len: usize) -> String {
+    name.trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(max_len)
+        .collect()
+}
+
+/// Replace chars which are invalid in file name, and limit its length.
+#[must_use]
+pub fn sanitize_path_component(name: &str) -> String {
+    let name = sanitize_chars(name, MAX_PATH_COMPONENT);
+    let name = name.trim().trim_start_matches('.').to_owned();
+    if name.is_empty() {
+        "_".to_owned()
+    } else {
+        name
+    }
+}
+
+/// Sanitize file name like `sanitize_path_component()`, and keep its extension.
+///
+/// Only stem of file name is truncated if it is too long.
+#[must_use]
+pub fn sanitize_file_name(name: &str) -> String {
+    let name = name.trim();
+    let Some((stem, extension)) = name.rsplit_once('.') else {
+        return sanitize_path_component(name);
+    };
+    let extension = sanitize_chars(extension, MAX_PATH_COMPONENT / 2);
+    if extension.is_empty() {
+        return sanitize_path_component(stem);
+    }
+    let max_len = MAX_PATH_COMPONENT - extension.chars().count() - 1;
+    let stem = sanitize_chars(stem, max_len);
+    let stem = stem.trim().trim_start_matches('.');
+    if stem.is_empty() {
+        format!("_.{extension}")
+    } else {
+        format!("{stem}.{extension}")
+    }
+}
+
+/// Convert language tag like `en-US` to ISO 639-2 code used in calibre.
+fn to_lang_code(language: &str) -> String {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let code = match primary.as_str() {
+        "ar" => "ara",
+        "de" => "deu",
+        "en" => "eng",
+        "es" => "spa",
+        "fr" => "fra",
+        "it" => "ita",
+        "ja" => "jpn",
+        "ko" => "kor",
+        "nl" => "nld",
+        "pt" => "por",
+        "ru" => "rus",
+        "zh" => "zho",
+        _ => return primary,
+    };
+    code.to_owned()
+}
+
+fn get_or_add_isbn_scheme(pg_conn: &mut PgConnection) -> Result<i32, Error> {
+    match get_identifier_type_by_name(pg_conn, ISBN_SCHEME) {
+        Ok(identifier_type) => Ok(identifier_type.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_type = NewIdentifierType {
+                    name: ISBN_SCHEME.to_owned(),
+                };
+                add_identifier_type(pg_conn, &new_type)?;
+                get_identifier_type_by_name(pg_conn, ISBN_SCHEME)
+                    .map(|identifier_type| identifier_type.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
+
+pub fn get_or_add_file_format(pg_conn: &mut PgConnection, format: &str) -> Result<i32, Error> {
+    match get_file_format_by_name(pg_conn, format) {
+        Ok(file_format) => Ok(file_format.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_format = NewFileFormat {
+                    name: format.to_owned(),
+                };
+                add_file_format(pg_conn, &new_format)?;
+                get_file_format_by_name(pg_conn, format).map(|file_format| file_format.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
+
+/// Copy or move ebook file into book directory.
+pub fn copy_file(
+    src_path: &Path,
+    dest_path: &Path,
+    options: &ImportBookOptions,
+    journal: &mut FileJournal,
+) -> Result<(), Error> {
+    let move_files = match options.file_action {
+        ImportBookFileAction::Copy => false,
+        ImportBookFileAction::Move => true,
+        ImportBookFileAction::DoNothing => return Ok(()),
+    };
+
+    let parent_dir = dest_path.parent().ok_or_else(|| {
+        Error::from_string(
+            ErrorKind::IoError,
+            format!("Failed to get parent dir: {}", dest_path.display()),
+        )
+    })?;
+    journal.create_dir_all(parent_dir, options.uid, options.gid)?;
+    if move_files {
+        journal.rename(src_path, dest_path)?;
+    } else {
+        journal.copy(src_path, dest_path)?;
+    }
+    file_util::chown(dest_path, options.uid, options.gid)
+}
+
+/// Save embedded cover image as `cover.jpg` and generate webp covers.
+fn save_cover(
+    library_path: &str,
+    book_path: &str,
+    cover: &[u8],
+    options: &ImportBookOptions,
+    journal: &mut FileJournal,
+) -> Result<(), Error> {
+    let cover_path = get_book_metadata_path(library_path, book_path, "cover.jpg");
+    if let Some(parent_dir) = cover_path.parent() {
+        journal.create_dir_all(parent_dir, options.uid, options.gid)?;
+    }
+    let img = image::load_from_memory(cover)?;
+    img.to_rgb8().save(&cover_path)?;
+    journal.add_file(&cover_path);
+    file_util::chown(&cover_path, options.uid, options.gid)?;
+
+    let (webp_path, small_webp_path) = convert_cover(&cover_path)?;
+    journal.add_file(&webp_path);
+    journal.add_file(&small_webp_path);
+    file_util::chown(webp_path, options.uid, options.gid)?;
+    file_util::chown(small_webp_path, options.uid, options.gid)
+}
+
+/// Add authors, publisher, isbn and language of book.
+fn add_book_detail(
+    pg_conn: &mut PgConnection,
+    book_id: i32,
+    metadata: &FileMetadata,
+    isbn_scheme: i32,
+) -> Result<(), Error> {
+    for name in &metadata.authors {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let author = get_or_add_author(pg_conn, name)?;
+        add_book_author(
+            pg_conn,
+            &NewBookAuthor {
+                book: book_id,
+                author,
+            },
+        )?;
+    }
+
+    if let Some(name) = &metadata.publisher {
+        let publisher = get_or_add_publisher(pg_conn, name.trim())?;
+        add_book_publisher(
+            pg_conn,
+            &NewBookPublisher {
+                book: book_id,
+                publisher,
+            },
+        )?;
+    }
+
+    if let Some(isbn) = &metadata.isbn {
+        let new_identifier = NewIdentifier {
+            book: book_id,
+            scheme: isbn_scheme,
+            value: isbn.clone(),
+            url: None,
+        };
+        add_identifier(pg_conn, &new_identifier)?;
+    }
+
+    if let Some(language) = &metadata.language {
+        let language = get_or_add_language(pg_conn, &to_lang_code(language))?;
+        add_book_language(
+            pg_conn,
+            &NewBookLanguage {
+                book: book_id,
+                language,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get lowercase extension of file.
+#[must_use]
+pub fn get_file_format(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Get relative path of book directory, in the same layout as calibre library.
+#[must_use]
+pub fn get_new_book_path(author: &str, title: &str, book_id: i32) -> String {
+    format!(
+        "{}/{} ({book_id})",
+        sanitize_path_component(author),
+        sanitize_path_component(title),
+    )
+}
+
+/// Copy ebook file into book directory and add it to `files` table.
+fn add_book_file(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book: &Book,
+    file_name: &str,
+    path: &Path,
+    options: &ImportBookOptions,
+    journal: &mut FileJournal,
+) -> Result<(), Error> {
+    let format = get_file_format(path);
+    let dest_path = get_book_file_path(library_path, &book.path, file_name, &format);
+    let size = get_file_size(path)?;
+    copy_file(path, &dest_path, options, journal)?;
+    let sha256 = sha256sum(path).or_else(|_| sha256sum(&dest_path))?;
+    let file_format = get_or_add_file_format(pg_conn, &format.to_ascii_uppercase())?;
+    add_file(
+        pg_conn,
+        &NewFile {
+            book: book.id,
+            format: file_format,
+            size,
+            name: file_name.to_owned(),
+            sha256: Some(sha256),
+        },
+    )?;
+    Ok(())
+}
+
+/// Add a new book with `paths` as its files.
+///
+/// Title is read from name of the first file if it is not set in metadata.
+fn do_import_files(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    paths: &[PathBuf],
+    metadata: &FileMetadata,
+    options: &ImportBookOptions,
+    journal: &mut FileJournal,
+) -> Result<Book, Error> {
+    let file_stem = paths
+        .first()
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = metadata.title.clone().unwrap_or(file_stem);
+    let author = metadata
+        .authors
+        .first()
+        .cloned()
+        .unwrap_or_else(|| UNKNOWN_AUTHOR.to_owned());
+
+    let new_book = NewBook {
+        title: title.clone(),
+        path: String::new(),
+        author_sort: author.clone(),
+        uuid: uuid::Uuid::new_v4().to_string(),
+        has_cover: false,
+        pubdate: None,
+        created: None,
+        last_modified: None,
+        title_sort: title_sort_of(&title),
+    };
+    let mut book = add_book(pg_conn, &new_book)?;
+
+    let file_name = sanitize_path_component(&title);
+    book.path = get_new_book_path(&author, &title, book.id);
+
+    for path in paths {
+        add_book_file(
+            pg_conn,
+            library_path,
+            &book,
+            &file_name,
+            path,
+            options,
+            journal,
+        )?;
+    }
+
+    if let Some(cover) = &metadata.cover {
+        if options.file_action == ImportBookFileAction::DoNothing {
+            log::info!("Skip cover of book {}", book.id);
+        } else if let Err(err) = save_cover(library_path, &book.path, cover, options, journal) {
+            log::warn!("Failed to save cover of book {}, err: {err:?}", book.id);
+        } else {
+            book.has_cover = true;
+        }
+    }
+    update_book_path(pg_conn, book.id, &book.path, book.has_cover)?;
+
+    let isbn_scheme = get_or_add_isbn_scheme(pg_conn)?;
+    add_book_detail(pg_conn, book.id, metadata, isbn_scheme)?;
+    Ok(book)
+}
+
+/// Import one ebook file.
+///
+/// All database changes are made in one transaction, and file operations are
+/// reverted if it fails.
+pub fn import_file(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    path: &Path,
+    options: &ImportBookOptions,
+) -> Result<ImportFileResult, Error> {
+    let metadata = read_metadata(path)?;
+
+    if !options.allow_duplication {
+        if let Some(isbn) = &metadata.isbn {
+            let isbn_scheme = get_or_add_isbn_scheme(pg_conn)?;
+            let book_ids =
+                get_books_by_identifier(pg_conn, isbn_scheme, std::slice::from_ref(isbn))?;
+            if let Some(book_id) = book_ids.first() {
+                return Ok(ImportFileResult::Duplicated(*book_id));
+            }
+        }
+    }
+
+    let book = import_files(
+        pg_conn,
+        library_path,
+        &[path.to_path_buf()],
+        &metadata,
+        options,
+    )?;
+    Ok(ImportFileResult::Imported(book.id))
+}
+
+/// Import ebook files as formats of one book, with `metadata` given.
+///
+/// Files shall have different formats. All database changes are made in one
+/// transaction, and file operations are reverted if it fails.
+pub fn import_files(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    paths: &[PathBuf],
+    metadata: &FileMetadata,
+    options: &ImportBookOptions,
+) -> Result<Book, Error> {
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        do_import_files(pg_conn, library_path, paths, metadata, options, journal)
+    })
+}
+
+/// Import all ebook files in `dir` recursively.
+///
+/// Stops importing remaining files after `progress` is cancelled.
+pub fn import_folder(
+    dir: &str,
+    library_path: &str,
+    options: &ImportBookOptions,
+    progress: &Progress,
+) -> Result<(), Error> {
+    let mut files = Vec::new();
+    walk_dir(Path::new(dir), &mut files)?;
+    files.sort();
+    log::info!("Found {} ebook files in {dir}", files.len());
+    progress.set_total(files.len() as u64);
+
+    let pg_pool = get_connection_pool()?;
+    let mut pg_conn = pg_pool.get()?;
+    let mut imported = 0;
+    let mut duplicated = 0;
+    let mut failed = 0;
+    for path in &files {
+        if progress.is_cancelled() {
+            return Err(Error::from_string(
+                ErrorKind::ImportError,
+                format!("Import folder {dir} is cancelled"),
+            ));
+        }
+        let ret = import_file(&mut pg_conn, library_path, path, options);
+        progress.inc(ret.is_ok());
+        match ret {
+            Ok(ImportFileResult::Imported(book_id)) => {
+                log::info!("Imported {} as book {book_id}", path.display());
+                imported += 1;
+            }
+            Ok(ImportFileResult::Duplicated(book_id)) => {
+                log::info!("Skip {}, same isbn as book {book_id}", path.display());
+                duplicated += 1;
+            }
+            Err(err) => {
+                log::warn!("Failed to import {}, err: {err:?}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    progress.finish();
+    log::info!(
+        "Import folder {dir}: imported: {imported}, duplicated: {duplicated}, failed: {failed}"
+    );
+    Ok(())
+}
@@ -0,0 +1,402 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Import jobs started from web api.
+//!
+//! Jobs are queued and run one by one in a background thread of web server.
+//! They are saved in `import_jobs` table, only pending and running jobs are
+//! kept in memory. Pending jobs are queued again after server restarts.
+//!
+//! Calibre libraries imported from command line are listed as jobs too.
+
+use chrono::NaiveDateTime;
+use diesel::PgConnection;
+use shared::import::{ImportJob, ImportJobKind, ImportJobStatus, NewImportJobReq};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use crate::db::DbPool;
+use crate::error::{Error, ErrorKind};
+use crate::import::import_folder::import_folder;
+use crate::import::models::books::get_import_book_count;
+use crate::import::models::jobs::{
+    add_import_job, add_missing_import_jobs, get_import_job, get_import_jobs,
+    get_import_jobs_by_status, set_import_job_library, update_import_job, NewImportJob,
+    UpdateImportJob,
+};
+use crate::import::models::libraries::{
+    get_import_library, try_lock_import_library, unlock_import_library,
+};
+use crate::import::new_task::ImportTask;
+use crate::import::options::{ImportBookFileAction, ImportBookOptions};
+use crate::import::progress::Progress;
+
+/// Max number of worker threads of a calibre import job.
+const MAX_JOBS: usize = 16;
+
+#[derive(Debug, Default)]
+struct JobState {
+    status: ImportJobStatus,
+    library: Option<i32>,
+    message: Option<String>,
+}
+
+#[derive(Debug)]
+struct JobEntry {
+    id: i32,
+    req: NewImportJobReq,
+    created: NaiveDateTime,
+    progress: Progress,
+    state: Mutex<JobState>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl JobEntry {
+    fn to_job(&self) -> ImportJob {
+        let state = lock(&self.state);
+        ImportJob {
+            id: self.id,
+            kind: self.req.kind,
+            path: self.req.path.clone(),
+            library: state.library,
+            status: state.status,
+            total: self.progress.total(),
+            done: self.progress.done(),
+            failed: self.progress.failed(),
+            message: state.message.clone(),
+            created: self.created,
+        }
+    }
+
+    fn set_status(&self, status: ImportJobStatus, message: Option<String>) {
+        let mut state = lock(&self.state);
+        state.status = status;
+        state.message = message;
+    }
+
+    fn set_library(&self, library_id: i32) {
+        lock(&self.state).library = Some(library_id);
+    }
+
+    /// Write status and progress of job to database.
+    #[allow(clippy::cast_possible_wrap)]
+    fn save(&self, pg_conn: &mut PgConnection) -> Result<(), Error> {
+        let job = self.to_job();
+        let update = UpdateImportJob {
+            status: job.status.into(),
+            total: job.total as i64,
+            done: job.done as i64,
+            failed: job.failed as i64,
+            message: job.message,
+        };
+        update_import_job(pg_conn, self.id, &update)
+    }
+}
+
+/// Queue of import jobs, shared by web workers.
+pub struct ImportJobs {
+    pool: DbPool,
+
+    /// Pending and running jobs.
+    jobs: Arc<Mutex<Vec<Arc<JobEntry>>>>,
+    sender: Mutex<mpsc::Sender<Arc<JobEntry>>>,
+}
+
+impl ImportJobs {
+    /// Create job queue and start its executor thread.
+    ///
+    /// Books are imported into `library_path`. Pending jobs saved in database
+    /// are queued again.
+    pub fn new(pool: DbPool, library_path: String) -> Result<Self, Error> {
+        let (sender, receiver) = mpsc::channel::<Arc<JobEntry>>();
+        let jobs = Arc::new(Mutex::new(Vec::new()));
+        {
+            let pool = pool.clone();
+            let jobs = jobs.clone();
+            thread::spawn(move || run_executor(&pool, &library_path, &jobs, &receiver));
+        }
+        let import_jobs = Self {
+            pool,
+            jobs,
+            sender: Mutex::new(sender),
+        };
+        import_jobs.restore_jobs()?;
+        Ok(import_jobs)
+    }
+
+    /// Queue pending jobs, and mark jobs interrupted by server restart as failed.
+    ///
+    /// Interrupted calibre library can be resumed from command line, its status
+    /// is read from import library.
+    fn restore_jobs(&self) -> Result<(), Error> {
+        let mut pg_conn = self.pool.get()?;
+        for job in get_import_jobs_by_status(&mut pg_conn, ImportJobStatus::Running.into())? {
+            if job.library.is_none() {
+                log::warn!("Import job {} is interrupted", job.id);
+                let update = UpdateImportJob {
+                    status: ImportJobStatus::Failed.into(),
+                    total: job.total,
+                    done: job.done,
+                    failed: job.failed,
+                    message: Some("Interrupted by server restart".to_owned()),
+                };
+                update_import_job(&mut pg_conn, job.id, &update)?;
+            }
+        }
+
+        for job in get_import_jobs_by_status(&mut pg_conn, ImportJobStatus::Pending.into())? {
+            let req: NewImportJobReq = serde_json::from_str(&job.request)?;
+            let entry = Arc::new(JobEntry {
+                id: job.id,
+                req,
+                created: job.created,
+                progress: Progress::default(),
+                state: Mutex::new(JobState::default()),
+            });
+            log::info!("Restore import job {}: {:?}", entry.id, entry.req);
+            self.push_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    fn push_entry(&self, entry: Arc<JobEntry>) -> Result<(), Error> {
+        lock(&self.jobs).push(entry.clone());
+        lock(&self.sender)
+            .send(entry)
+            .map_err(|_err| Error::new(ErrorKind::ImportError, "Import job executor is stopped"))
+    }
+
+    /// Validate request and append a new job to queue.
+    pub fn add_job(
+        &self,
+        pg_conn: &mut PgConnection,
+        req: NewImportJobReq,
+    ) -> Result<ImportJob, Error> {
+        let path = Path::new(&req.path);
+        if !path.is_dir() {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Invalid folder: {}", req.path),
+            ));
+        }
+        if req.kind == ImportJobKind::Calibre && !path.join("metadata.db").is_file() {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("metadata.db not found in {}", req.path),
+            ));
+        }
+
+        let req = NewImportJobReq {
+            jobs: req.jobs.clamp(1, MAX_JOBS),
+            ..req
+        };
+        let new_job = NewImportJob {
+            kind: req.kind.into(),
+            path: req.path.clone(),
+            request: serde_json::to_string(&req)?,
+        };
+        let job = add_import_job(pg_conn, &new_job)?;
+        let entry = Arc::new(JobEntry {
+            id: job.id,
+            req,
+            created: job.created,
+            progress: Progress::default(),
+            state: Mutex::new(JobState::default()),
+        });
+        log::info!("Add import job {}: {:?}", entry.id, entry.req);
+        self.push_entry(entry.clone())?;
+        Ok(entry.to_job())
+    }
+
+    fn get_entry(&self, job_id: i32) -> Option<Arc<JobEntry>> {
+        lock(&self.jobs)
+            .iter()
+            .find(|entry| entry.id == job_id)
+            .cloned()
+    }
+
+    /// Get live progress of pending or running job.
+    #[must_use]
+    pub fn get_active_job(&self, job_id: i32) -> Option<ImportJob> {
+        self.get_entry(job_id).map(|entry| entry.to_job())
+    }
+
+    /// Get progress of job, stopped jobs are read from database.
+    pub fn get_job(&self, pg_conn: &mut PgConnection, job_id: i32) -> Result<ImportJob, Error> {
+        if let Some(job) = self.get_active_job(job_id) {
+            return Ok(job);
+        }
+        let mut job = get_import_job(pg_conn, job_id)?.to_job();
+        read_library_progress(pg_conn, &mut job, false)?;
+        Ok(job)
+    }
+
+    /// Get all jobs, newest first.
+    ///
+    /// Progress of calibre jobs is read from `import_books` table.
+    pub fn get_jobs(&self, pg_conn: &mut PgConnection) -> Result<Vec<ImportJob>, Error> {
+        add_missing_import_jobs(pg_conn)?;
+        let records = get_import_jobs(pg_conn)?;
+        let mut list = Vec::with_capacity(records.len());
+        for record in records {
+            let entry = self.get_entry(record.id);
+            let mut job = entry
+                .as_ref()
+                .map_or_else(|| record.to_job(), |entry| entry.to_job());
+            read_library_progress(pg_conn, &mut job, entry.is_some())?;
+            list.push(job);
+        }
+        Ok(list)
+    }
+
+    /// Stop a pending or running job.
+    ///
+    /// Running job stops after books being imported are finished.
+    pub fn cancel_job(&self, pg_conn: &mut PgConnection, job_id: i32) -> Result<ImportJob, Error> {
+        let Some(entry) = self.get_entry(job_id) else {
+            let job = self.get_job(pg_conn, job_id)?;
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!(
+                    "Import job {job_id} is {}, it is not run by web server",
+                    job.status
+                ),
+            ));
+        };
+        {
+            let mut state = lock(&entry.state);
+            if state.status.is_done() {
+                return Err(Error::from_string(
+                    ErrorKind::RequestFormError,
+                    format!("Import job {job_id} is already {}", state.status),
+                ));
+            }
+            entry.progress.cancel();
+            if state.status == ImportJobStatus::Pending {
+                state.status = ImportJobStatus::Cancelled;
+            }
+        }
+        entry.save(pg_conn)?;
+        log::info!("Cancel import job {job_id}");
+        Ok(entry.to_job())
+    }
+}
+
+/// Read progress of calibre library from `import_books` table.
+///
+/// Status of jobs not running in this server is read from import library,
+/// which may be resumed from command line.
+fn read_library_progress(
+    pg_conn: &mut PgConnection,
+    job: &mut ImportJob,
+    is_active: bool,
+) -> Result<(), Error> {
+    let Some(library_id) = job.library else {
+        return Ok(());
+    };
+    let import_library = get_import_library(pg_conn, library_id)?;
+    let (imported, failed) = get_import_book_count(pg_conn, library_id)?;
+    #[allow(clippy::cast_sign_loss)]
+    {
+        job.total = import_library.total as u64;
+        job.done = (imported + failed) as u64;
+        job.failed = failed as u64;
+    }
+    if is_active {
+        return Ok(());
+    }
+
+    if import_library.finished {
+        job.status = ImportJobStatus::Finished;
+        job.message = None;
+    } else if !try_lock_import_library(pg_conn, library_id)? {
+        // Held by a worker process.
+        job.status = ImportJobStatus::Running;
+        job.message = None;
+    } else {
+        unlock_import_library(pg_conn, library_id)?;
+        if !job.status.is_done() {
+            job.status = ImportJobStatus::Failed;
+            job.message = Some(format!(
+                "Interrupted, resume with `backend import-library --resume {library_id}`"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn run_job(pool: &DbPool, library_path: &str, entry: &JobEntry) -> Result<(), Error> {
+    let req = &entry.req;
+    let options = ImportBookOptions {
+        file_action: if req.move_files {
+            ImportBookFileAction::Move
+        } else {
+            ImportBookFileAction::Copy
+        },
+        allow_duplication: req.allow_duplication,
+        uid: req.uid,
+        gid: req.gid,
+    };
+
+    match req.kind {
+        ImportJobKind::Calibre => {
+            let task = ImportTask::create(&req.path, library_path, options, req.jobs)?;
+            entry.set_library(task.library_id());
+            {
+                let mut pg_conn = pool.get()?;
+                set_import_job_library(&mut pg_conn, entry.id, task.library_id())?;
+            }
+            task.run(&entry.progress)
+        }
+        ImportJobKind::Folder => import_folder(&req.path, library_path, &options, &entry.progress),
+    }
+}
+
+fn save_entry(pool: &DbPool, entry: &JobEntry) {
+    let ret = pool
+        .get()
+        .map_err(Error::from)
+        .and_then(|mut pg_conn| entry.save(&mut pg_conn));
+    if let Err(err) = ret {
+        log::warn!("Failed to save import job {}, err: {err:?}", entry.id);
+    }
+}
+
+/// Run jobs in queue one by one, until all senders are dropped.
+///
+/// Stopped jobs are saved to database and removed from `jobs`.
+fn run_executor(
+    pool: &DbPool,
+    library_path: &str,
+    jobs: &Mutex<Vec<Arc<JobEntry>>>,
+    receiver: &mpsc::Receiver<Arc<JobEntry>>,
+) {
+    for entry in receiver {
+        if entry.progress.is_cancelled() {
+            entry.set_status(ImportJobStatus::Cancelled, None);
+        } else {
+            log::info!("Start import job {}", entry.id);
+            entry.set_status(ImportJobStatus::Running, None);
+            save_entry(pool, &entry);
+            let (status, message) = match run_job(pool, library_path, &entry) {
+                Ok(()) => (ImportJobStatus::Finished, None),
+                Err(err) if entry.progress.is_cancelled() => {
+                    (ImportJobStatus::Cancelled, Some(err.to_string()))
+                }
+                Err(err) => {
+                    log::warn!("Import job {} failed, err: {err:?}", entry.id);
+                    (ImportJobStatus::Failed, Some(err.to_string()))
+                }
+            };
+            log::info!("Import job {} is {status}", entry.id);
+            entry.set_status(status, message);
+        }
+        save_entry(pool, &entry);
+        lock(jobs).retain(|item| item.id != entry.id);
+    }
+}
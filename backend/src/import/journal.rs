@@ -0,0 +1,136 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use diesel::{Connection, PgConnection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::import::file_util;
+
+#[derive(Debug)]
+enum FileOperation {
+    CreateDir(PathBuf),
+    CreateFile(PathBuf),
+    MoveFile { src: PathBuf, dest: PathBuf },
+}
+
+/// Records file operations of importing a book, so that they can be rolled back
+/// if database transaction fails.
+#[derive(Debug, Default)]
+pub struct FileJournal {
+    operations: Vec<FileOperation>,
+
+    /// Files moved aside by `backup_file()`, removed on commit.
+    backup_files: Vec<PathBuf>,
+}
+
+impl FileJournal {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create directory and all of its missing parents.
+    pub fn create_dir_all<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> Result<(), Error> {
+        let mut missing_dirs = vec![];
+        let mut dir = path.as_ref().to_path_buf();
+        while !dir.as_os_str().is_empty() && !dir.exists() {
+            missing_dirs.push(dir.clone());
+            if !dir.pop() {
+                break;
+            }
+        }
+
+        file_util::create_dir_all_and_chown(path, uid, gid)?;
+        for dir in missing_dirs.into_iter().rev() {
+            self.operations.push(FileOperation::CreateDir(dir));
+        }
+        Ok(())
+    }
+
+    pub fn copy<P: AsRef<Path>>(&mut self, src: P, dest: P) -> Result<(), Error> {
+        fs::copy(src, dest.as_ref())?;
+        self.operations
+            .push(FileOperation::CreateFile(dest.as_ref().to_path_buf()));
+        Ok(())
+    }
+
+    pub fn rename<P: AsRef<Path>>(&mut self, src: P, dest: P) -> Result<(), Error> {
+        fs::rename(src.as_ref(), dest.as_ref())?;
+        self.operations.push(FileOperation::MoveFile {
+            src: src.as_ref().to_path_buf(),
+            dest: dest.as_ref().to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Record a file generated by external program.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) {
+        self.operations
+            .push(FileOperation::CreateFile(path.as_ref().to_path_buf()));
+    }
+
+    /// Move file at `path` aside, so that it can be restored on rollback.
+    pub fn backup_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        if !path.exists() {
+            log::warn!("File not found: {}", path.display());
+            return Ok(());
+        }
+        let mut backup_path = path.as_os_str().to_owned();
+        backup_path.push(".bak");
+        let backup_path = PathBuf::from(backup_path);
+        self.rename(path, backup_path.as_path())?;
+        self.backup_files.push(backup_path);
+        Ok(())
+    }
+
+    /// Keep all changes, and remove backup files.
+    pub fn commit(&mut self) {
+        self.operations.clear();
+        for path in self.backup_files.drain(..) {
+            if let Err(err) = fs::remove_file(&path) {
+                log::warn!("Failed to remove {}, err: {err:?}", path.display());
+            }
+        }
+    }
+
+    /// Revert file operations in reverse order.
+    ///
+    /// Errors are logged and ignored.
+    pub fn rollback(&mut self) {
+        self.backup_files.clear();
+        while let Some(operation) = self.operations.pop() {
+            let ret = match &operation {
+                FileOperation::CreateDir(dir) => fs::remove_dir(dir),
+                FileOperation::CreateFile(path) => fs::remove_file(path),
+                FileOperation::MoveFile { src, dest } => fs::rename(dest, src),
+            };
+            if let Err(err) = ret {
+                log::warn!("Failed to rollback {operation:?}, err: {err:?}");
+            }
+        }
+    }
+}
+
+/// Run `func` in a database transaction, and revert its file operations if it fails.
+pub fn run_with_journal<T, F>(pg_conn: &mut PgConnection, func: F) -> Result<T, Error>
+where
+    F: FnOnce(&mut PgConnection, &mut FileJournal) -> Result<T, Error>,
+{
+    let mut journal = FileJournal::new();
+    let ret = pg_conn.transaction::<T, Error, _>(|pg_conn| func(pg_conn, &mut journal));
+    if ret.is_ok() {
+        journal.commit();
+    } else {
+        journal.rollback();
+    }
+    ret
+}
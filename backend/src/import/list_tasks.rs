@@ -0,0 +1,43 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use crate::db::get_connection_pool;
+use crate::error::Error;
+use crate::import::models::books::get_import_book_count;
+use crate::import::models::libraries::get_import_libraries;
+
+/// Print progress of all import tasks.
+pub fn list_tasks() -> Result<(), Error> {
+    let pg_pool = get_connection_pool()?;
+    let mut pg_conn = pg_pool.get()?;
+    let library_list = get_import_libraries(&mut pg_conn)?;
+
+    println!(
+        "{:>6} {:>8} {:>8} {:>8} {:>8} {:>8} {:>19}  calibre library",
+        "id", "total", "imported", "failed", "finished", "worker", "last synced"
+    );
+    for library in library_list {
+        let (imported, failed) = get_import_book_count(&mut pg_conn, library.id)?;
+        let worker = library
+            .worker_pid
+            .map_or_else(|| "-".to_owned(), |pid| pid.to_string());
+        let last_synced = library.last_synced.map_or_else(
+            || "-".to_owned(),
+            |last_synced| last_synced.format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+        println!(
+            "{:>6} {:>8} {:>8} {:>8} {:>8} {:>8} {:>19}  {}",
+            library.id,
+            library.total,
+            imported,
+            failed,
+            library.finished,
+            worker,
+            last_synced,
+            library.calibre_library_path
+        );
+    }
+
+    Ok(())
+}
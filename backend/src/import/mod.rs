@@ -2,10 +2,25 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+pub mod book_files;
+pub mod checksums;
 mod convert;
+pub mod covers;
 mod db;
 mod file_util;
+pub mod fsck;
 pub mod import_books;
+pub mod import_folder;
+pub mod jobs;
+mod journal;
+pub mod list_tasks;
 mod models;
 pub mod new_task;
 pub mod options;
+pub mod progress;
+pub mod resume_task;
+pub mod sync_library;
+mod synced_metadata;
+pub mod trash;
+pub mod watch_folder;
+mod worker;
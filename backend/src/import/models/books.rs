@@ -3,7 +3,7 @@
 // that can be found in the LICENSE file.
 
 use chrono::NaiveDateTime;
-use diesel::{Insertable, PgConnection, Queryable, RunQueryDsl};
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
 use serde::Serialize;
 
 use crate::error::Error;
@@ -16,6 +16,13 @@ pub struct NewImportBook {
     pub calibre_book: i32,
     pub ok: bool,
     pub book: Option<i32>,
+    pub skip_reason: Option<String>,
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+    pub calibre_last_modified: Option<NaiveDateTime>,
+
+    /// Metadata of book in json, used to detect changes in next sync.
+    pub synced_metadata: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -27,6 +34,11 @@ pub struct ImportBook {
     pub ok: bool,
     pub book: Option<i32>,
     pub created: NaiveDateTime,
+    pub skip_reason: Option<String>,
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+    pub calibre_last_modified: Option<NaiveDateTime>,
+    pub synced_metadata: Option<String>,
 }
 
 pub fn add_import_book(conn: &mut PgConnection, new_book: &NewImportBook) -> Result<(), Error> {
@@ -37,3 +49,88 @@ pub fn add_import_book(conn: &mut PgConnection, new_book: &NewImportBook) -> Res
         .map(drop)
         .map_err(Into::into)
 }
+
+/// Get ids of calibre books recorded in import library.
+pub fn get_imported_calibre_books(
+    conn: &mut PgConnection,
+    library_id: i32,
+) -> Result<Vec<i32>, Error> {
+    use crate::schema::import_books::dsl::{calibre_book, import_books, library};
+    import_books
+        .filter(library.eq(library_id))
+        .select(calibre_book)
+        .load::<i32>(conn)
+        .map_err(Into::into)
+}
+
+/// Get count of imported books and failed books in import library.
+pub fn get_import_book_count(
+    conn: &mut PgConnection,
+    library_id: i32,
+) -> Result<(i64, i64), Error> {
+    use crate::schema::import_books::dsl::{import_books, library, ok};
+    let imported = import_books
+        .filter(library.eq(library_id))
+        .filter(ok.eq(true))
+        .count()
+        .first::<i64>(conn)?;
+    let failed = import_books
+        .filter(library.eq(library_id))
+        .filter(ok.eq(false))
+        .count()
+        .first::<i64>(conn)?;
+    Ok((imported, failed))
+}
+
+/// Get failed books in import library, ordered by calibre book id.
+pub fn get_failed_import_books(
+    conn: &mut PgConnection,
+    library_id: i32,
+) -> Result<Vec<ImportBook>, Error> {
+    use crate::schema::import_books::dsl::{calibre_book, import_books, library, ok};
+    import_books
+        .filter(library.eq(library_id))
+        .filter(ok.eq(false))
+        .order_by(calibre_book.asc())
+        .load::<ImportBook>(conn)
+        .map_err(Into::into)
+}
+
+/// Get all books recorded in import library, ordered by calibre book id.
+pub fn get_import_books(
+    conn: &mut PgConnection,
+    library_id: i32,
+) -> Result<Vec<ImportBook>, Error> {
+    use crate::schema::import_books::dsl::{calibre_book, import_books, library};
+    import_books
+        .filter(library.eq(library_id))
+        .order_by(calibre_book.asc())
+        .load::<ImportBook>(conn)
+        .map_err(Into::into)
+}
+
+/// Update sync state of import book after changes in calibre are applied.
+pub fn update_import_book_synced(
+    conn: &mut PgConnection,
+    id_val: i32,
+    calibre_last_modified_val: NaiveDateTime,
+    synced_metadata_val: &str,
+) -> Result<(), Error> {
+    use crate::schema::import_books::dsl::{calibre_last_modified, import_books, synced_metadata};
+    diesel::update(import_books.find(id_val))
+        .set((
+            calibre_last_modified.eq(calibre_last_modified_val),
+            synced_metadata.eq(synced_metadata_val),
+        ))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+pub fn delete_import_book(conn: &mut PgConnection, id_val: i32) -> Result<(), Error> {
+    use crate::schema::import_books::dsl::import_books;
+    diesel::delete(import_books.find(id_val))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
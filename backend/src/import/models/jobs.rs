@@ -0,0 +1,162 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+use diesel::sql_types::Integer;
+use diesel::{
+    AsChangeset, Connection, ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable,
+    RunQueryDsl,
+};
+use shared::import;
+
+use crate::error::Error;
+use crate::schema::import_jobs;
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = import_jobs)]
+pub struct NewImportJob {
+    pub kind: i32,
+    pub path: String,
+    pub request: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Queryable)]
+pub struct ImportJob {
+    pub id: i32,
+    pub kind: i32,
+    pub path: String,
+    pub request: String,
+    pub library: Option<i32>,
+    pub status: i32,
+    pub total: i64,
+    pub done: i64,
+    pub failed: i64,
+    pub message: Option<String>,
+    pub created: NaiveDateTime,
+    pub last_modified: NaiveDateTime,
+}
+
+impl ImportJob {
+    #[must_use]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn to_job(&self) -> import::ImportJob {
+        import::ImportJob {
+            id: self.id,
+            kind: self.kind.into(),
+            path: self.path.clone(),
+            library: self.library,
+            status: self.status.into(),
+            total: self.total as u64,
+            done: self.done as u64,
+            failed: self.failed as u64,
+            message: self.message.clone(),
+            created: self.created,
+        }
+    }
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = import_jobs, treat_none_as_null = true)]
+pub struct UpdateImportJob {
+    pub status: i32,
+    pub total: i64,
+    pub done: i64,
+    pub failed: i64,
+    pub message: Option<String>,
+}
+
+pub fn add_import_job(conn: &mut PgConnection, new_job: &NewImportJob) -> Result<ImportJob, Error> {
+    use crate::schema::import_jobs::dsl::import_jobs;
+    diesel::insert_into(import_jobs)
+        .values(new_job)
+        .get_result::<ImportJob>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_import_job(conn: &mut PgConnection, id_val: i32) -> Result<ImportJob, Error> {
+    use crate::schema::import_jobs::dsl::import_jobs;
+    import_jobs
+        .find(id_val)
+        .first::<ImportJob>(conn)
+        .map_err(Into::into)
+}
+
+/// Get all jobs, newest first.
+pub fn get_import_jobs(conn: &mut PgConnection) -> Result<Vec<ImportJob>, Error> {
+    use crate::schema::import_jobs::dsl::{id, import_jobs};
+    import_jobs
+        .order_by(id.desc())
+        .load::<ImportJob>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_import_jobs_by_status(
+    conn: &mut PgConnection,
+    status_val: i32,
+) -> Result<Vec<ImportJob>, Error> {
+    use crate::schema::import_jobs::dsl::{id, import_jobs, status};
+    import_jobs
+        .filter(status.eq(status_val))
+        .order_by(id.asc())
+        .load::<ImportJob>(conn)
+        .map_err(Into::into)
+}
+
+pub fn update_import_job(
+    conn: &mut PgConnection,
+    id_val: i32,
+    job: &UpdateImportJob,
+) -> Result<(), Error> {
+    use crate::schema::import_jobs::dsl::{import_jobs, last_modified};
+    diesel::update(import_jobs.find(id_val))
+        .set((job, last_modified.eq(diesel::dsl::now)))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Link job to the import library it created.
+///
+/// Record of this library listed by `add_missing_import_jobs()` is replaced.
+pub fn set_import_job_library(
+    conn: &mut PgConnection,
+    id_val: i32,
+    library_id: i32,
+) -> Result<(), Error> {
+    use crate::schema::import_jobs::dsl::{id, import_jobs, last_modified, library};
+    conn.transaction::<(), Error, _>(|conn| {
+        diesel::delete(
+            import_jobs
+                .filter(library.eq(library_id))
+                .filter(id.ne(id_val)),
+        )
+        .execute(conn)?;
+        diesel::update(import_jobs.find(id_val))
+            .set((library.eq(library_id), last_modified.eq(diesel::dsl::now)))
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+/// Add records of calibre libraries imported from command line, so that they
+/// are listed with import jobs.
+pub fn add_missing_import_jobs(conn: &mut PgConnection) -> Result<(), Error> {
+    let running: i32 = import::ImportJobStatus::Running.into();
+    let finished: i32 = import::ImportJobStatus::Finished.into();
+    diesel::sql_query(
+        "INSERT INTO import_jobs (kind, path, request, library, status, total, created) \
+         SELECT $1, calibre_library_path, '', id, \
+           CASE WHEN finished THEN $2 ELSE $3 END, total, created \
+         FROM import_libraries \
+         WHERE id NOT IN (SELECT library FROM import_jobs WHERE library IS NOT NULL) \
+         ON CONFLICT (library) DO NOTHING",
+    )
+    .bind::<Integer, _>(i32::from(import::ImportJobKind::Calibre))
+    .bind::<Integer, _>(finished)
+    .bind::<Integer, _>(running)
+    .execute(conn)
+    .map(drop)
+    .map_err(Into::into)
+}
@@ -3,9 +3,13 @@
 // that can be found in the LICENSE file.
 
 use chrono::NaiveDateTime;
-use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
+use diesel::sql_types::{Bool, Integer};
+use diesel::{
+    ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, QueryableByName, RunQueryDsl,
+};
 use serde::Serialize;
 
+use crate::db::IMPORT_WORKER_LOCK;
 use crate::error::Error;
 use crate::schema::import_libraries;
 
@@ -31,6 +35,7 @@ pub struct ImportLibrary {
     pub worker_pid: Option<i32>,
     pub created: NaiveDateTime,
     pub last_modified: NaiveDateTime,
+    pub last_synced: Option<NaiveDateTime>,
 }
 
 pub fn add_import_library(
@@ -56,3 +61,86 @@ pub fn update_import_library(
         .map(drop)
         .map_err(Into::into)
 }
+
+/// Record time of the last successful sync.
+pub fn update_import_library_synced(conn: &mut PgConnection, id_val: i32) -> Result<(), Error> {
+    use crate::schema::import_libraries::dsl::{import_libraries, last_synced};
+    diesel::update(import_libraries.find(id_val))
+        .set(last_synced.eq(diesel::dsl::now))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+pub fn get_import_library(conn: &mut PgConnection, id_val: i32) -> Result<ImportLibrary, Error> {
+    use crate::schema::import_libraries::dsl::import_libraries;
+    import_libraries
+        .find(id_val)
+        .first::<ImportLibrary>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_import_libraries(conn: &mut PgConnection) -> Result<Vec<ImportLibrary>, Error> {
+    use crate::schema::import_libraries::dsl::{id, import_libraries};
+    import_libraries
+        .order_by(id.asc())
+        .load::<ImportLibrary>(conn)
+        .map_err(Into::into)
+}
+
+#[derive(Debug, QueryableByName)]
+struct LockResult {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+/// Try to take advisory lock of import library in current database session.
+///
+/// Lock is released when the session ends, even if worker process is killed.
+/// Returns false if this library is held by another worker.
+pub fn try_lock_import_library(conn: &mut PgConnection, id_val: i32) -> Result<bool, Error> {
+    let result = diesel::sql_query("SELECT pg_try_advisory_lock($1, $2) AS locked")
+        .bind::<Integer, _>(IMPORT_WORKER_LOCK)
+        .bind::<Integer, _>(id_val)
+        .get_result::<LockResult>(conn)?;
+    Ok(result.locked)
+}
+
+/// Release advisory lock of import library taken by `try_lock_import_library()`.
+pub fn unlock_import_library(conn: &mut PgConnection, id_val: i32) -> Result<(), Error> {
+    diesel::sql_query("SELECT pg_advisory_unlock($1, $2) AS locked")
+        .bind::<Integer, _>(IMPORT_WORKER_LOCK)
+        .bind::<Integer, _>(id_val)
+        .get_result::<LockResult>(conn)?;
+    Ok(())
+}
+
+/// Set `worker_pid` of import library, which is shown in task list.
+///
+/// Worker shall hold lock of this library.
+pub fn acquire_import_library(conn: &mut PgConnection, id_val: i32, pid: i32) -> Result<(), Error> {
+    use crate::schema::import_libraries::dsl::{id, import_libraries, last_modified, worker_pid};
+
+    diesel::update(import_libraries.filter(id.eq(id_val)))
+        .set((worker_pid.eq(pid), last_modified.eq(diesel::dsl::now)))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Clear `worker_pid` of import library.
+pub fn release_import_library(conn: &mut PgConnection, id_val: i32, pid: i32) -> Result<(), Error> {
+    use crate::schema::import_libraries::dsl::{id, import_libraries, last_modified, worker_pid};
+    diesel::update(
+        import_libraries
+            .filter(id.eq(id_val))
+            .filter(worker_pid.eq(pid)),
+    )
+    .set((
+        worker_pid.eq(None::<i32>),
+        last_modified.eq(diesel::dsl::now),
+    ))
+    .execute(conn)
+    .map(drop)
+    .map_err(Into::into)
+}
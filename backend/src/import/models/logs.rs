@@ -0,0 +1,34 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use diesel::{Insertable, PgConnection, RunQueryDsl};
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::schema::import_logs;
+
+#[derive(Debug, Serialize, Insertable)]
+#[diesel(table_name = import_logs)]
+pub struct NewImportLog {
+    /// Path of file in watched folder.
+    pub path: String,
+
+    /// New path of file after it is moved to `done/` or `failed/`.
+    pub moved_to: Option<String>,
+
+    pub ok: bool,
+    pub book: Option<i32>,
+    pub skip_reason: Option<String>,
+    pub error_kind: Option<String>,
+    pub error_message: Option<String>,
+}
+
+pub fn add_import_log(conn: &mut PgConnection, new_log: &NewImportLog) -> Result<(), Error> {
+    use crate::schema::import_logs::dsl::import_logs;
+    diesel::insert_into(import_logs)
+        .values(new_log)
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
@@ -3,4 +3,6 @@
 // that can be found in the LICENSE file.
 
 pub mod books;
+pub mod jobs;
 pub mod libraries;
+pub mod logs;
@@ -6,6 +6,8 @@
 
 use calibre::models::authors::get_authors;
 use calibre::models::books::get_total_books;
+use calibre::models::books_plugin_data::get_plugin_names;
+use calibre::models::custom_columns::get_custom_columns;
 use calibre::models::file_formats::get_file_formats;
 use calibre::models::identifier_types::get_identifier_types;
 use calibre::models::languages::get_languages;
@@ -13,14 +15,20 @@ use calibre::models::publishers::get_publishers;
 use calibre::models::series::get_series;
 use calibre::models::tags::get_tags;
 use diesel::{PgConnection, SqliteConnection};
+use shared::custom_fields::CustomFieldType;
 
-use crate::db::get_connection_pool;
+use std::collections::HashSet;
+
+use crate::db::{get_connection_pool_with_size, DbPool};
 use crate::error::{Error, ErrorKind};
-use crate::import::db::get_calibre_db;
-use crate::import::import_books::import_books;
-use crate::import::models::libraries::{add_import_library, NewImportLibrary};
+use crate::import::db::{get_calibre_db, CalibreDbPool};
+use crate::import::import_books::{custom_column_label, import_books, plugin_data_label};
+use crate::import::models::libraries::{add_import_library, ImportLibrary, NewImportLibrary};
 use crate::import::options::{ImportBookFileAction, ImportBookOptions};
+use crate::import::progress::Progress;
+use crate::import::worker::run_as_worker;
 use crate::models::authors::{add_author, NewAuthor};
+use crate::models::custom_fields::{add_custom_field, NewCustomField};
 use crate::models::file_formats::{add_file_format, NewFileFormat};
 use crate::models::identifier_types::{add_identifier_type, NewIdentifierType};
 use crate::models::languages::{add_language, NewLanguage};
@@ -269,52 +277,153 @@ fn import_identifier_types(
     Ok(())
 }
 
+/// Import definitions of calibre custom columns and plugin data as custom fields.
+fn import_custom_fields(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+) -> Result<(), Error> {
+    let mut new_fields = Vec::new();
+    for column in get_custom_columns(sqlite_conn)? {
+        if CustomFieldType::parse(&column.datatype).is_none() {
+            log::info!(
+                "Ignore custom column: {}, type: {}",
+                column.label,
+                column.datatype
+            );
+            continue;
+        }
+        new_fields.push(NewCustomField {
+            label: custom_column_label(&column.label),
+            name: column.name,
+            datatype: column.datatype,
+            is_multiple: column.is_multiple,
+            display: column.display,
+        });
+    }
+    for name in get_plugin_names(sqlite_conn)? {
+        new_fields.push(NewCustomField {
+            label: plugin_data_label(&name),
+            name,
+            datatype: CustomFieldType::Json.as_str().to_owned(),
+            is_multiple: true,
+            display: "{}".to_owned(),
+        });
+    }
+
+    for new_field in new_fields {
+        if let Err(err) = add_custom_field(pg_conn, &new_field) {
+            match err.kind() {
+                ErrorKind::DbUniqueViolationError => {
+                    log::info!("custom field exists: {new_field:?}");
+                }
+                _ => return Err(err),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get size of connection pool for `jobs` workers and the main thread.
+#[allow(clippy::cast_possible_truncation)]
+pub(super) fn pool_size_for_jobs(jobs: usize) -> u32 {
+    jobs.max(1) as u32 + 1
+}
+
+/// Import library recorded in database, with connection pools of its workers.
+pub struct ImportTask {
+    calibre_pool: CalibreDbPool,
+    pg_pool: DbPool,
+    import_library: ImportLibrary,
+    options: ImportBookOptions,
+    jobs: usize,
+}
+
+impl ImportTask {
+    /// Import metadata shared by books, like authors and tags, and add a new
+    /// import library.
+    pub fn create(
+        calibre_library_path: &str,
+        library_path: &str,
+        options: ImportBookOptions,
+        jobs: usize,
+    ) -> Result<Self, Error> {
+        let pool_size = pool_size_for_jobs(jobs);
+        let calibre_pool = get_calibre_db(calibre_library_path, pool_size)?;
+        let pg_pool = get_connection_pool_with_size(pool_size)?;
+        let mut sqlite_conn = calibre_pool.get()?;
+        let mut pg_conn = pg_pool.get()?;
+        import_authors(&mut sqlite_conn, &mut pg_conn)?;
+        import_languages(&mut sqlite_conn, &mut pg_conn)?;
+        import_publishers(&mut sqlite_conn, &mut pg_conn)?;
+        import_series(&mut sqlite_conn, &mut pg_conn)?;
+        import_tags(&mut sqlite_conn, &mut pg_conn)?;
+        import_file_formats(&mut sqlite_conn, &mut pg_conn)?;
+        import_identifier_types(&mut sqlite_conn, &mut pg_conn)?;
+        import_custom_fields(&mut sqlite_conn, &mut pg_conn)?;
+
+        let options_str = serde_json::to_string(&options)?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let total_books = get_total_books(&mut sqlite_conn)? as i32;
+        let new_library = NewImportLibrary {
+            calibre_library_path: calibre_library_path.to_string(),
+            library_path: library_path.to_owned(),
+            total: total_books,
+            finished: false,
+            options: options_str,
+        };
+        let import_library = add_import_library(&mut pg_conn, &new_library)?;
+
+        Ok(Self {
+            calibre_pool,
+            pg_pool,
+            import_library,
+            options,
+            jobs,
+        })
+    }
+
+    #[must_use]
+    pub const fn library_id(&self) -> i32 {
+        self.import_library.id
+    }
+
+    /// Import all books in calibre library.
+    pub fn run(&self, progress: &Progress) -> Result<(), Error> {
+        let mut pg_conn = self.pg_pool.get()?;
+        run_as_worker(&mut pg_conn, &self.import_library, |pg_conn| {
+            import_books(
+                &self.calibre_pool,
+                pg_conn,
+                &self.pg_pool,
+                &self.import_library,
+                &self.options,
+                &HashSet::new(),
+                self.jobs,
+                progress,
+            )
+        })
+    }
+}
+
 #[allow(clippy::similar_names)]
+#[allow(clippy::too_many_arguments)]
 pub fn new_task(
     calibre_library_path: &str,
     library_path: &str,
     file_action: ImportBookFileAction,
+    allow_duplication: bool,
     uid: Option<u32>,
     gid: Option<u32>,
+    jobs: usize,
 ) -> Result<(), Error> {
-    let calibre_pool = get_calibre_db(calibre_library_path)?;
-    let pg_pool = get_connection_pool()?;
-    let mut sqlite_conn = calibre_pool.get()?;
-    let mut pg_conn = pg_pool.get()?;
-    import_authors(&mut sqlite_conn, &mut pg_conn)?;
-    import_languages(&mut sqlite_conn, &mut pg_conn)?;
-    import_publishers(&mut sqlite_conn, &mut pg_conn)?;
-    import_series(&mut sqlite_conn, &mut pg_conn)?;
-    import_tags(&mut sqlite_conn, &mut pg_conn)?;
-    import_file_formats(&mut sqlite_conn, &mut pg_conn)?;
-    import_identifier_types(&mut sqlite_conn, &mut pg_conn)?;
-
     let options = ImportBookOptions {
         file_action,
-        allow_duplication: true,
+        allow_duplication,
         uid,
         gid,
     };
-    let options_str = serde_json::to_string(&options)?;
-
-    #[allow(clippy::cast_possible_truncation)]
-    let total_books = get_total_books(&mut sqlite_conn)? as i32;
-    let new_library = NewImportLibrary {
-        calibre_library_path: calibre_library_path.to_string(),
-        library_path: library_path.to_owned(),
-        total: total_books,
-        finished: false,
-        options: options_str,
-    };
-    let import_library = add_import_library(&mut pg_conn, &new_library)?;
-    let last_book_id = 0;
-    import_books(
-        &mut sqlite_conn,
-        &mut pg_conn,
-        &import_library,
-        &options,
-        last_book_id,
-    )?;
-
-    Ok(())
+    let task = ImportTask::create(calibre_library_path, library_path, options, jobs)?;
+    task.run(&Progress::default())
 }
@@ -0,0 +1,122 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Interval to print progress log, in milliseconds.
+const REPORT_INTERVAL: u64 = 30_000;
+
+/// Tracks import throughput shared by workers.
+///
+/// It is also used to observe and cancel import jobs started in web server.
+#[derive(Debug)]
+pub struct Progress {
+    total: AtomicU64,
+    done: AtomicU64,
+    failed: AtomicU64,
+    cancelled: AtomicBool,
+    start: Instant,
+
+    /// Milliseconds since `start` when last report was printed.
+    last_report: AtomicU64,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Progress {
+    #[must_use]
+    pub fn new(total: u64) -> Self {
+        Self {
+            total: AtomicU64::new(total),
+            done: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            cancelled: AtomicBool::new(false),
+            start: Instant::now(),
+            last_report: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Number of finished books, including failed ones.
+    #[must_use]
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Ask workers to stop importing new books.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Increase finished books by one, and print progress periodically.
+    ///
+    /// `ok` is false if this book failed to import.
+    pub fn inc(&self, ok: bool) {
+        if !ok {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let elapsed = self.start.elapsed().as_millis() as u64;
+        let last_report = self.last_report.load(Ordering::Relaxed);
+        if elapsed < last_report + REPORT_INTERVAL {
+            return;
+        }
+        // Only one worker prints this report.
+        if self
+            .last_report
+            .compare_exchange(last_report, elapsed, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            self.report(done, elapsed);
+        }
+    }
+
+    /// Print progress of all books.
+    pub fn finish(&self) {
+        #[allow(clippy::cast_possible_truncation)]
+        let elapsed = self.start.elapsed().as_millis() as u64;
+        self.report(self.done(), elapsed);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn report(&self, done: u64, elapsed_ms: u64) {
+        let total = self.total();
+        let elapsed_secs = (elapsed_ms as f64 / 1000.0).max(0.001);
+        let speed = done as f64 / elapsed_secs;
+        let remaining = total.saturating_sub(done);
+        let eta = if speed > 0.0 {
+            format!("{:.0}s", remaining as f64 / speed)
+        } else {
+            "unknown".to_owned()
+        };
+        log::info!(
+            "Import progress: {done}/{total}, {speed:.2} books/s, elapsed: {elapsed_secs:.0}s, eta: {eta}"
+        );
+    }
+}
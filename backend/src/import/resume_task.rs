@@ -0,0 +1,69 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use std::collections::HashSet;
+
+use crate::db::get_connection_pool_with_size;
+use crate::error::Error;
+use crate::import::db::get_calibre_db;
+use crate::import::import_books::{import_books, retry_failed_books};
+use crate::import::models::books::get_imported_calibre_books;
+use crate::import::models::libraries::get_import_library;
+use crate::import::new_task::pool_size_for_jobs;
+use crate::import::options::ImportBookOptions;
+use crate::import::progress::Progress;
+use crate::import::worker::run_as_worker;
+
+/// Continue an interrupted import task.
+///
+/// Calibre books already recorded in `import_books` are skipped. As books are
+/// imported in parallel, the recorded ones may not be contiguous.
+pub fn resume_task(library_id: i32, jobs: usize) -> Result<(), Error> {
+    let pool_size = pool_size_for_jobs(jobs);
+    let pg_pool = get_connection_pool_with_size(pool_size)?;
+    let mut pg_conn = pg_pool.get()?;
+    let import_library = get_import_library(&mut pg_conn, library_id)?;
+    if import_library.finished {
+        log::info!("Import library {library_id} is already finished");
+        return Ok(());
+    }
+
+    let options: ImportBookOptions = serde_json::from_str(&import_library.options)?;
+    let calibre_pool = get_calibre_db(&import_library.calibre_library_path, pool_size)?;
+
+    run_as_worker(&mut pg_conn, &import_library, |pg_conn| {
+        let imported: HashSet<i32> = get_imported_calibre_books(pg_conn, library_id)?
+            .into_iter()
+            .collect();
+        log::info!(
+            "Resume import library {library_id}, {} books recorded",
+            imported.len()
+        );
+        import_books(
+            &calibre_pool,
+            pg_conn,
+            &pg_pool,
+            &import_library,
+            &options,
+            &imported,
+            jobs,
+            &Progress::default(),
+        )
+    })
+}
+
+/// Import failed books of an import task again.
+pub fn retry_failed_task(library_id: i32) -> Result<(), Error> {
+    let pool_size = pool_size_for_jobs(1);
+    let pg_pool = get_connection_pool_with_size(pool_size)?;
+    let mut pg_conn = pg_pool.get()?;
+    let import_library = get_import_library(&mut pg_conn, library_id)?;
+    let options: ImportBookOptions = serde_json::from_str(&import_library.options)?;
+    let calibre_pool = get_calibre_db(&import_library.calibre_library_path, pool_size)?;
+    let mut sqlite_conn = calibre_pool.get()?;
+
+    run_as_worker(&mut pg_conn, &import_library, |pg_conn| {
+        retry_failed_books(&mut sqlite_conn, pg_conn, &import_library, &options)
+    })
+}
@@ -0,0 +1,475 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Apply changes made in calibre library to books imported from it.
+//!
+//! Metadata of each book is recorded in `import_books.synced_metadata` when it
+//! is imported or synced. It is the common base to compare calibre values with
+//! pengzu values, so that a field changed on both sides is reported as conflict
+//! instead of being overwritten.
+
+use calibre::models::books::{get_book, get_book_modified_times};
+use chrono::NaiveDateTime;
+use diesel::{Connection, PgConnection, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::db::get_connection_pool_with_size;
+use crate::error::{Error, ErrorKind};
+use crate::import::db::get_calibre_db;
+use crate::import::import_books::import_books;
+use crate::import::models::books::{
+    delete_import_book, get_import_books, update_import_book_synced, ImportBook,
+};
+use crate::import::models::libraries::{
+    get_import_library, update_import_library_synced, ImportLibrary,
+};
+use crate::import::new_task::pool_size_for_jobs;
+use crate::import::options::ImportBookOptions;
+use crate::import::progress::Progress;
+use crate::import::synced_metadata::{
+    links_update_req, read_book_metadata, read_calibre_metadata, SyncedLinks, SyncedMetadata,
+};
+use crate::import::trash::trash_book;
+use crate::import::worker::run_as_worker;
+use crate::models::books::{get_book_record, update_book_fields, Book};
+use crate::models::books_meta::apply_book_metadata;
+
+/// Recorded in `trash_books.user_id` for books removed in calibre.
+const SYNC_USER_ID: i32 = 0;
+
+/// A field changed both in calibre and in pengzu.
+#[derive(Debug)]
+pub struct SyncConflict {
+    pub calibre_book: i32,
+    pub book: i32,
+    pub field: &'static str,
+    pub calibre_value: String,
+    pub pengzu_value: String,
+}
+
+impl fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "calibre book {} -> book {}, {}: calibre: {:?}, pengzu: {:?}",
+            self.calibre_book, self.book, self.field, self.calibre_value, self.pengzu_value
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct SyncReport {
+    added: usize,
+    updated: usize,
+    deleted: usize,
+    conflicts: Vec<SyncConflict>,
+}
+
+/// Three-way merge of one field.
+///
+/// Calibre value is applied only if pengzu value is not changed since last sync.
+fn merge_field<T: Clone + PartialEq + fmt::Debug>(
+    field: &'static str,
+    base: Option<&T>,
+    calibre_value: &T,
+    merged_value: &mut T,
+    conflicts: &mut Vec<SyncConflict>,
+    ids: (i32, i32),
+) {
+    if calibre_value == merged_value || base == Some(calibre_value) {
+        return;
+    }
+    if base == Some(merged_value) {
+        *merged_value = calibre_value.clone();
+        return;
+    }
+
+    conflicts.push(SyncConflict {
+        calibre_book: ids.0,
+        book: ids.1,
+        field,
+        calibre_value: format!("{calibre_value:?}"),
+        pengzu_value: format!("{merged_value:?}"),
+    });
+}
+
+/// Three-way merge of links of book.
+///
+/// Files and custom fields are not synced, their changes in calibre are reported
+/// as conflicts, so that the book is not marked as synced.
+fn merge_links(
+    base: Option<&SyncedLinks>,
+    calibre_links: &SyncedLinks,
+    merged: &mut SyncedLinks,
+    conflicts: &mut Vec<SyncConflict>,
+    ids: (i32, i32),
+) {
+    merge_field(
+        "authors",
+        base.map(|base| &base.authors),
+        &calibre_links.authors,
+        &mut merged.authors,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "tags",
+        base.map(|base| &base.tags),
+        &calibre_links.tags,
+        &mut merged.tags,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "series",
+        base.map(|base| &base.series),
+        &calibre_links.series,
+        &mut merged.series,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "publisher",
+        base.map(|base| &base.publisher),
+        &calibre_links.publisher,
+        &mut merged.publisher,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "lang",
+        base.map(|base| &base.lang),
+        &calibre_links.lang,
+        &mut merged.lang,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "rating",
+        base.map(|base| &base.rating),
+        &calibre_links.rating,
+        &mut merged.rating,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "comment",
+        base.map(|base| &base.comment),
+        &calibre_links.comment,
+        &mut merged.comment,
+        conflicts,
+        ids,
+    );
+    merge_field(
+        "identifiers",
+        base.map(|base| &base.identifiers),
+        &calibre_links.identifiers,
+        &mut merged.identifiers,
+        conflicts,
+        ids,
+    );
+
+    // Books imported by old versions have no record of files and custom fields.
+    let Some(base) = base else {
+        return;
+    };
+    if base.files != calibre_links.files {
+        conflicts.push(SyncConflict {
+            calibre_book: ids.0,
+            book: ids.1,
+            field: "files",
+            calibre_value: format!("{:?}", calibre_links.files),
+            pengzu_value: "not synced, import this book again".to_owned(),
+        });
+    }
+    if base.custom_fields != calibre_links.custom_fields {
+        conflicts.push(SyncConflict {
+            calibre_book: ids.0,
+            book: ids.1,
+            field: "custom_fields",
+            calibre_value: format!("{:?}", calibre_links.custom_fields),
+            pengzu_value: "not synced, import this book again".to_owned(),
+        });
+    }
+}
+
+/// Apply changes of a calibre book to pengzu book.
+///
+/// Returns true if book is updated.
+fn sync_book(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    import_book: &ImportBook,
+    book_id: i32,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Result<bool, Error> {
+    let calibre_book = get_book(sqlite_conn, import_book.calibre_book)?;
+    let calibre_metadata = read_calibre_metadata(sqlite_conn, &calibre_book)?;
+    let calibre_fields = &calibre_metadata.fields;
+    let calibre_links = calibre_metadata.links.clone().unwrap_or_default();
+    let base: Option<SyncedMetadata> = match &import_book.synced_metadata {
+        Some(metadata) => Some(serde_json::from_str(metadata)?),
+        None => None,
+    };
+
+    pg_conn.transaction::<bool, Error, _>(|pg_conn| {
+        let book = get_book_record(pg_conn, book_id)?;
+        let pengzu_metadata = read_book_metadata(pg_conn, &book)?;
+        let pengzu_links = pengzu_metadata.links.unwrap_or_default();
+        let mut merged = pengzu_metadata.fields.clone();
+        let mut merged_links = pengzu_links.clone();
+        let num_conflicts = conflicts.len();
+        let ids = (calibre_book.id, book_id);
+        merge_field(
+            "title",
+            base.as_ref().map(|base| &base.fields.title),
+            &calibre_fields.title,
+            &mut merged.title,
+            conflicts,
+            ids,
+        );
+        merge_field(
+            "title_sort",
+            base.as_ref().map(|base| &base.fields.title_sort),
+            &calibre_fields.title_sort,
+            &mut merged.title_sort,
+            conflicts,
+            ids,
+        );
+        merge_field(
+            "author_sort",
+            base.as_ref().map(|base| &base.fields.author_sort),
+            &calibre_fields.author_sort,
+            &mut merged.author_sort,
+            conflicts,
+            ids,
+        );
+        merge_field(
+            "pubdate",
+            base.as_ref().map(|base| &base.fields.pubdate),
+            &calibre_fields.pubdate,
+            &mut merged.pubdate,
+            conflicts,
+            ids,
+        );
+        merge_links(
+            base.as_ref().and_then(|base| base.links.as_ref()),
+            &calibre_links,
+            &mut merged_links,
+            conflicts,
+            ids,
+        );
+
+        let links_updated = merged_links != pengzu_links;
+        if links_updated {
+            let req = links_update_req(pg_conn, &book, &pengzu_links, &merged_links)?;
+            apply_book_metadata(pg_conn, book_id, &req)?;
+        }
+        // Changing authors resets author_sort, so fields are written after links.
+        let updated = links_updated || merged != pengzu_metadata.fields;
+        if updated {
+            update_book_fields(pg_conn, book_id, &merged)?;
+        }
+
+        // Keep previous sync state if there are conflicts, so that they are
+        // reported again until resolved.
+        if conflicts.len() == num_conflicts {
+            update_import_book_synced(
+                pg_conn,
+                import_book.id,
+                calibre_book.last_modified,
+                &serde_json::to_string(&calibre_metadata)?,
+            )?;
+        }
+        Ok(updated)
+    })
+}
+
+/// Check whether book is modified in pengzu since last sync.
+///
+/// Files and custom fields are not compared, as they are not synced.
+fn is_book_modified(
+    pg_conn: &mut PgConnection,
+    import_book: &ImportBook,
+    book: &Book,
+) -> Result<bool, Error> {
+    let Some(metadata) = &import_book.synced_metadata else {
+        return Ok(true);
+    };
+    let base: SyncedMetadata = serde_json::from_str(metadata)?;
+    let pengzu_metadata = read_book_metadata(pg_conn, book)?;
+    if base.fields != pengzu_metadata.fields {
+        return Ok(true);
+    }
+    let (Some(base_links), Some(pengzu_links)) = (base.links, pengzu_metadata.links) else {
+        return Ok(false);
+    };
+    Ok(SyncedLinks {
+        files: Vec::new(),
+        custom_fields: Vec::new(),
+        ..base_links
+    } != SyncedLinks {
+        files: Vec::new(),
+        ..pengzu_links
+    })
+}
+
+/// Move pengzu book whose calibre book was removed to trash.
+///
+/// Book is kept and reported as conflict if it was modified in pengzu.
+fn remove_book(
+    pg_conn: &mut PgConnection,
+    import_library: &ImportLibrary,
+    import_book: &ImportBook,
+    book_id: i32,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Result<bool, Error> {
+    let book = get_book_record(pg_conn, book_id)?;
+    if is_book_modified(pg_conn, import_book, &book)? {
+        conflicts.push(SyncConflict {
+            calibre_book: import_book.calibre_book,
+            book: book_id,
+            field: "book",
+            calibre_value: "deleted".to_owned(),
+            pengzu_value: "modified".to_owned(),
+        });
+        return Ok(false);
+    }
+
+    trash_book(pg_conn, &import_library.library_path, book_id, SYNC_USER_ID)?;
+    delete_import_book(pg_conn, import_book.id)?;
+    Ok(true)
+}
+
+/// Apply changes and deletions of calibre books already imported.
+fn sync_imported_books(
+    sqlite_conn: &mut SqliteConnection,
+    pg_conn: &mut PgConnection,
+    import_library: &ImportLibrary,
+    calibre_books: &HashMap<i32, NaiveDateTime>,
+    report: &mut SyncReport,
+) -> Result<(), Error> {
+    for import_book in get_import_books(pg_conn, import_library.id)? {
+        let Some(calibre_last_modified) = calibre_books.get(&import_book.calibre_book) else {
+            // Calibre book is removed.
+            match import_book.book {
+                Some(book_id) if import_book.ok && import_book.skip_reason.is_none() => {
+                    if remove_book(
+                        pg_conn,
+                        import_library,
+                        &import_book,
+                        book_id,
+                        &mut report.conflicts,
+                    )? {
+                        report.deleted += 1;
+                    }
+                }
+                _ => delete_import_book(pg_conn, import_book.id)?,
+            }
+            continue;
+        };
+
+        // Failed books are handled by `--retry-failed`, and skipped books are
+        // linked to books not owned by this library.
+        let Some(book_id) = import_book.book else {
+            continue;
+        };
+        if !import_book.ok || import_book.skip_reason.is_some() {
+            continue;
+        }
+        if import_book
+            .calibre_last_modified
+            .is_some_and(|last_modified| last_modified >= *calibre_last_modified)
+        {
+            continue;
+        }
+
+        if sync_book(
+            sqlite_conn,
+            pg_conn,
+            &import_book,
+            book_id,
+            &mut report.conflicts,
+        )? {
+            report.updated += 1;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_report(library_id: i32, report: &SyncReport) {
+    println!(
+        "Sync import library {library_id}: added: {}, updated: {}, deleted: {}, conflicts: {}",
+        report.added,
+        report.updated,
+        report.deleted,
+        report.conflicts.len()
+    );
+    for conflict in &report.conflicts {
+        println!("  conflict: {conflict}");
+    }
+}
+
+/// Apply books added, changed and deleted in calibre library since last import or sync.
+pub fn sync_library(library_id: i32, jobs: usize) -> Result<(), Error> {
+    let pool_size = pool_size_for_jobs(jobs);
+    let pg_pool = get_connection_pool_with_size(pool_size)?;
+    let mut pg_conn = pg_pool.get()?;
+    let import_library = get_import_library(&mut pg_conn, library_id)?;
+    if !import_library.finished {
+        return Err(Error::from_string(
+            ErrorKind::ImportError,
+            format!("Import library {library_id} is not finished, resume it first"),
+        ));
+    }
+
+    let options: ImportBookOptions = serde_json::from_str(&import_library.options)?;
+    let calibre_pool = get_calibre_db(&import_library.calibre_library_path, pool_size)?;
+
+    run_as_worker(&mut pg_conn, &import_library, |pg_conn| {
+        let mut sqlite_conn = calibre_pool.get()?;
+        let calibre_books: HashMap<i32, NaiveDateTime> = get_book_modified_times(&mut sqlite_conn)?
+            .into_iter()
+            .collect();
+        let mut report = SyncReport::default();
+        sync_imported_books(
+            &mut sqlite_conn,
+            pg_conn,
+            &import_library,
+            &calibre_books,
+            &mut report,
+        )?;
+        // Connections are taken again by import workers.
+        drop(sqlite_conn);
+
+        let recorded: HashSet<i32> = get_import_books(pg_conn, library_id)?
+            .iter()
+            .map(|import_book| import_book.calibre_book)
+            .collect();
+        report.added = calibre_books
+            .keys()
+            .filter(|calibre_book| !recorded.contains(calibre_book))
+            .count();
+        if report.added > 0 {
+            import_books(
+                &calibre_pool,
+                pg_conn,
+                &pg_pool,
+                &import_library,
+                &options,
+                &recorded,
+                jobs,
+                &Progress::default(),
+            )?;
+        }
+
+        for conflict in &report.conflicts {
+            log::warn!("Sync conflict: {conflict}");
+        }
+        print_report(library_id, &report);
+        update_import_library_synced(pg_conn, library_id)
+    })
+}
@@ -0,0 +1,253 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Snapshot of book metadata, compared between calibre and pengzu when syncing.
+
+use calibre::models::books::CalibreBook;
+use calibre::models::books_authors::get_book_authors;
+use calibre::models::books_languages::get_book_language;
+use calibre::models::books_plugin_data::get_book_plugin_data;
+use calibre::models::books_publishers::get_book_publisher;
+use calibre::models::books_ratings::get_book_rating;
+use calibre::models::books_series::get_book_series;
+use calibre::models::books_tags::get_book_tags;
+use calibre::models::comments::get_comment;
+use calibre::models::custom_columns::{get_custom_column_values, get_custom_columns};
+use calibre::models::data::get_book_data;
+use calibre::models::identifiers::get_identifiers;
+use diesel::{PgConnection, SqliteConnection};
+use serde::{Deserialize, Serialize};
+use shared::books_meta::{BookIdentifierReq, BookMetadataUpdateReq, BookSeriesReq};
+
+use crate::error::{Error, ErrorKind};
+use crate::import::import_books::{custom_column_label, new_book_from_calibre, plugin_data_label};
+use crate::models::books::{Book, BookFields};
+use crate::models::books_authors::get_authors_by_book;
+use crate::models::books_languages::get_language_by_book;
+use crate::models::books_publishers::get_publisher_by_book;
+use crate::models::books_series::{get_series_by_book, get_series_index_by_book};
+use crate::models::books_tags::get_tags_by_book;
+use crate::models::comments;
+use crate::models::files::get_book_files_and_formats;
+use crate::models::identifier_types::get_or_add_identifier_type;
+use crate::models::identifiers::get_identifiers_with_type;
+use crate::models::ratings::get_rating;
+use crate::models::tags::get_or_add_tag;
+
+/// Metadata recorded in `import_books.synced_metadata` when book is imported or synced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedMetadata {
+    #[serde(flatten)]
+    pub fields: BookFields,
+
+    /// Not recorded in books imported by old versions.
+    #[serde(default)]
+    pub links: Option<SyncedLinks>,
+}
+
+/// Metadata of book stored out of `books` table.
+///
+/// Lists are sorted, as order of links is not kept in database.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncedLinks {
+    pub authors: Vec<String>,
+    pub tags: Vec<String>,
+    pub series: Option<BookSeriesReq>,
+    pub publisher: Option<String>,
+    pub lang: Option<String>,
+    pub rating: Option<i32>,
+    pub comment: Option<String>,
+
+    /// Pairs of identifier type and value.
+    pub identifiers: Vec<(String, String)>,
+
+    /// Files in `name.FORMAT:size` form.
+    pub files: Vec<String>,
+
+    /// Values of custom columns and plugin data, only read from calibre.
+    pub custom_fields: Vec<(String, String)>,
+}
+
+/// Convert not found error of calibre to `None`.
+fn optional<T>(ret: Result<T, calibre::error::Error>) -> Result<Option<T>, Error> {
+    match ret {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.kind() == calibre::error::ErrorKind::DbNotFoundError => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn sorted<T: Ord>(mut list: Vec<T>) -> Vec<T> {
+    list.sort();
+    list
+}
+
+/// Read metadata of calibre book.
+pub fn read_calibre_metadata(
+    sqlite_conn: &mut SqliteConnection,
+    calibre_book: &CalibreBook,
+) -> Result<SyncedMetadata, Error> {
+    let book_id = calibre_book.id;
+    let mut custom_fields = Vec::new();
+    for column in get_custom_columns(sqlite_conn)? {
+        for value in get_custom_column_values(sqlite_conn, &column, book_id)? {
+            custom_fields.push((custom_column_label(&column.label), value));
+        }
+    }
+    for plugin_data in get_book_plugin_data(sqlite_conn, book_id)? {
+        custom_fields.push((plugin_data_label(&plugin_data.name), plugin_data.val));
+    }
+
+    let links = SyncedLinks {
+        authors: sorted(
+            get_book_authors(sqlite_conn, book_id)?
+                .into_iter()
+                .map(|author| author.name)
+                .collect(),
+        ),
+        tags: sorted(
+            get_book_tags(sqlite_conn, book_id)?
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect(),
+        ),
+        series: optional(get_book_series(sqlite_conn, book_id))?.map(|series| BookSeriesReq {
+            name: series.name,
+            index: calibre_book.series_index,
+        }),
+        publisher: optional(get_book_publisher(sqlite_conn, book_id))?
+            .map(|publisher| publisher.name),
+        lang: optional(get_book_language(sqlite_conn, book_id))?.map(|lang| lang.lang_code),
+        rating: optional(get_book_rating(sqlite_conn, book_id))?.map(|rating| rating.rating),
+        comment: optional(get_comment(sqlite_conn, book_id))?.map(|comment| comment.text),
+        identifiers: sorted(
+            get_identifiers(sqlite_conn, book_id)?
+                .into_iter()
+                .map(|identifier| (identifier.type_, identifier.val))
+                .collect(),
+        ),
+        files: sorted(
+            get_book_data(sqlite_conn, book_id)?
+                .into_iter()
+                .map(|file| format!("{}.{}:{}", file.name, file.format, file.uncompressed_size))
+                .collect(),
+        ),
+        custom_fields: sorted(custom_fields),
+    };
+
+    Ok(SyncedMetadata {
+        fields: BookFields::from(&new_book_from_calibre(calibre_book)),
+        links: Some(links),
+    })
+}
+
+/// Read metadata of pengzu book.
+///
+/// `custom_fields` is left empty, as it is not synced.
+pub fn read_book_metadata(
+    pg_conn: &mut PgConnection,
+    book: &Book,
+) -> Result<SyncedMetadata, Error> {
+    let book_id = book.id;
+    let rating = match get_rating(pg_conn, book_id) {
+        Ok(rating) => Some(rating.rating),
+        Err(err) if err.kind() == ErrorKind::DbNotFoundError => None,
+        Err(err) => return Err(err),
+    };
+    let comment = match comments::get_comment(pg_conn, book_id) {
+        Ok(comment) => Some(comment.text),
+        Err(err) if err.kind() == ErrorKind::DbNotFoundError => None,
+        Err(err) => return Err(err),
+    };
+    let series = get_series_by_book(pg_conn, book_id)?;
+    let series_index = get_series_index_by_book(pg_conn, book_id)?;
+
+    let links = SyncedLinks {
+        authors: sorted(
+            get_authors_by_book(pg_conn, book_id)?
+                .into_iter()
+                .map(|author| author.name)
+                .collect(),
+        ),
+        tags: sorted(
+            get_tags_by_book(pg_conn, book_id)?
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect(),
+        ),
+        series: series.map(|series| BookSeriesReq {
+            name: series.name,
+            index: series_index.unwrap_or_default(),
+        }),
+        publisher: get_publisher_by_book(pg_conn, book_id)?.map(|publisher| publisher.name),
+        lang: get_language_by_book(pg_conn, book_id)?.map(|lang| lang.lang_code),
+        rating,
+        comment,
+        identifiers: sorted(
+            get_identifiers_with_type(pg_conn, book_id)?
+                .into_iter()
+                .map(|identifier| (identifier.name, identifier.value))
+                .collect(),
+        ),
+        files: sorted(
+            get_book_files_and_formats(pg_conn, book_id)?
+                .into_iter()
+                .map(|file| format!("{}.{}:{}", file.name, file.format_name, file.size))
+                .collect(),
+        ),
+        custom_fields: Vec::new(),
+    };
+
+    Ok(SyncedMetadata {
+        fields: BookFields::from(book),
+        links: Some(links),
+    })
+}
+
+/// Build update request of links which differ between `old` and `new`.
+pub fn links_update_req(
+    pg_conn: &mut PgConnection,
+    book: &Book,
+    old: &SyncedLinks,
+    new: &SyncedLinks,
+) -> Result<BookMetadataUpdateReq, Error> {
+    let mut req = BookMetadataUpdateReq::new(book.last_modified);
+    if old.authors != new.authors {
+        req.authors = Some(new.authors.clone());
+    }
+    if old.tags != new.tags {
+        let mut tags = Vec::with_capacity(new.tags.len());
+        for name in &new.tags {
+            tags.push(get_or_add_tag(pg_conn, name)?);
+        }
+        req.tags = Some(tags);
+    }
+    if old.series != new.series {
+        req.series = Some(new.series.clone());
+    }
+    if old.publisher != new.publisher {
+        req.publisher = Some(new.publisher.clone());
+    }
+    if old.lang != new.lang {
+        req.lang = Some(new.lang.clone());
+    }
+    if old.rating != new.rating {
+        req.rating = Some(new.rating);
+    }
+    if old.comment != new.comment {
+        req.comment = Some(new.comment.clone());
+    }
+    if old.identifiers != new.identifiers {
+        let mut identifiers = Vec::with_capacity(new.identifiers.len());
+        for (scheme, value) in &new.identifiers {
+            identifiers.push(BookIdentifierReq {
+                scheme: get_or_add_identifier_type(pg_conn, scheme)?,
+                value: value.clone(),
+                url: None,
+            });
+        }
+        req.identifiers = Some(identifiers);
+    }
+    Ok(req)
+}
@@ -0,0 +1,165 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Move deleted books to trash, restore or purge them.
+//!
+//! Database rows of book are kept in `trash_books` table, and its directory
+//! is moved to `.trash/{book-id}` in library.
+
+use diesel::{Connection, PgConnection};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::db::DbPool;
+use crate::error::{Error, ErrorKind};
+use crate::import::journal::run_with_journal;
+use crate::models::books::{delete_book, get_book_record};
+use crate::models::trash_books::{
+    add_trash_book, delete_trash_book, get_book_links, get_expired_trash_books, get_trash_book,
+    get_trash_book_record, restore_book_links, NewTrashBook,
+};
+
+/// Directory of deleted books, relative to library root.
+const TRASH_DIR: &str = ".trash";
+
+/// Interval to check expired books in trash.
+const PURGE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn get_trash_path(book_id: i32) -> String {
+    format!("{TRASH_DIR}/{book_id}")
+}
+
+/// Move book to trash.
+pub fn trash_book(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+    user_id: i32,
+) -> Result<(), Error> {
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        let book = get_book_record(pg_conn, book_id)?;
+        let links = get_book_links(pg_conn, book_id)?;
+        let trash_path = get_trash_path(book_id);
+        add_trash_book(
+            pg_conn,
+            &NewTrashBook {
+                book: book_id,
+                title: book.title.clone(),
+                path: trash_path.clone(),
+                record: serde_json::to_string(&book)?,
+                links: serde_json::to_string(&links)?,
+                user_id,
+            },
+        )?;
+        delete_book(pg_conn, book_id)?;
+
+        // Book added without any files has no directory.
+        let book_dir = Path::new(library_path).join(&book.path);
+        if !book.path.is_empty() && book_dir.is_dir() {
+            let trash_dir = Path::new(library_path).join(&trash_path);
+            if trash_dir.exists() {
+                return Err(Error::from_string(
+                    ErrorKind::IoError,
+                    format!("Trash directory already exists: {}", trash_dir.display()),
+                ));
+            }
+            journal.create_dir_all(Path::new(library_path).join(TRASH_DIR), None, None)?;
+            journal.rename(book_dir, trash_dir)?;
+        }
+        log::info!("Book {book_id} is moved to trash");
+        Ok(())
+    })
+}
+
+/// Restore book in trash, with the same id.
+pub fn restore_book(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+) -> Result<(), Error> {
+    run_with_journal(pg_conn, |pg_conn, journal| {
+        let trash_book = get_trash_book(pg_conn, book_id)?;
+        let (book, links) = get_trash_book_record(pg_conn, book_id)?;
+        restore_book_links(pg_conn, &book, &links)?;
+        delete_trash_book(pg_conn, book_id)?;
+
+        let trash_dir = Path::new(library_path).join(&trash_book.path);
+        if !book.path.is_empty() && trash_dir.is_dir() {
+            let book_dir = Path::new(library_path).join(&book.path);
+            if book_dir.exists() {
+                return Err(Error::from_string(
+                    ErrorKind::IoError,
+                    format!("Book directory already exists: {}", book_dir.display()),
+                ));
+            }
+            if let Some(parent_dir) = book_dir.parent() {
+                journal.create_dir_all(parent_dir, None, None)?;
+            }
+            journal.rename(trash_dir, book_dir)?;
+        }
+        log::info!("Book {book_id} is restored from trash");
+        Ok(())
+    })
+}
+
+/// Remove book from trash permanently, with its files.
+pub fn purge_book(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    book_id: i32,
+) -> Result<(), Error> {
+    pg_conn.transaction::<(), Error, _>(|pg_conn| {
+        let trash_book = get_trash_book(pg_conn, book_id)?;
+        delete_trash_book(pg_conn, book_id)?;
+
+        // Files can not be recovered, so remove them as the last step.
+        let trash_dir = Path::new(library_path).join(&trash_book.path);
+        match fs::remove_dir_all(&trash_dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => (),
+        }
+        log::info!("Book {book_id} is purged from trash");
+        Ok(())
+    })
+}
+
+/// Purge books which were moved to trash more than `keep_days` days ago.
+///
+/// Returns number of purged books.
+pub fn purge_expired_books(
+    pg_conn: &mut PgConnection,
+    library_path: &str,
+    keep_days: u32,
+) -> Result<usize, Error> {
+    let keep_days = i32::try_from(keep_days).unwrap_or(i32::MAX);
+    let book_ids = get_expired_trash_books(pg_conn, keep_days)?;
+    for book_id in &book_ids {
+        purge_book(pg_conn, library_path, *book_id)?;
+    }
+    Ok(book_ids.len())
+}
+
+/// Purge expired books in trash periodically, in a background thread.
+///
+/// Nothing is done if `keep_days` is 0.
+pub fn start_auto_purge(pool: DbPool, library_path: String, keep_days: u32) {
+    if keep_days == 0 {
+        return;
+    }
+    thread::spawn(move || loop {
+        let ret = pool
+            .get()
+            .map_err(Into::into)
+            .and_then(|mut pg_conn| purge_expired_books(&mut pg_conn, &library_path, keep_days));
+        match ret {
+            Ok(0) => (),
+            Ok(count) => log::info!("Purged {count} books in trash"),
+            Err(err) => log::warn!("Failed to purge books in trash, err: {err:?}"),
+        }
+        thread::sleep(PURGE_INTERVAL);
+    });
+}
@@ -0,0 +1,275 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Import ebook files dropped into a folder.
+//!
+//! New files are picked up with inotify, and are imported after they are not
+//! modified for a while. Then they are moved to `done/` or `failed/`
+//! subdirectory of watched folder.
+
+use diesel::PgConnection;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::db::get_connection_pool;
+use crate::error::{Error, ErrorKind};
+use crate::import::file_util::{self, create_dir_all_and_chown};
+use crate::import::import_folder::{import_file, is_supported_file, ImportFileResult};
+use crate::import::models::logs::{add_import_log, NewImportLog};
+use crate::import::options::{ImportBookFileAction, ImportBookOptions};
+
+const DONE_DIR: &str = "done";
+const FAILED_DIR: &str = "failed";
+
+/// Files are imported only if they are not modified in this period.
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Size of `struct inotify_event`, without name.
+const EVENT_HEADER_LEN: usize = 16;
+
+const EVENT_BUF_LEN: usize = 64 * 1024;
+
+/// Inotify instance watching one directory.
+struct Watcher {
+    fd: i32,
+}
+
+impl Watcher {
+    fn new(dir: &Path) -> Result<Self, Error> {
+        let fd = unsafe { nc::inotify_init1(nc::IN_NONBLOCK | nc::IN_CLOEXEC) }
+            .map_err(|errno| errno_to_error("Failed to init inotify", errno))?;
+        let watcher = Self { fd };
+        let mask = nc::IN_CLOSE_WRITE | nc::IN_MOVED_TO | nc::IN_MODIFY;
+        unsafe { nc::inotify_add_watch(fd, dir, mask) }.map_err(|errno| {
+            errno_to_error(&format!("Failed to watch {}", dir.display()), errno)
+        })?;
+        Ok(watcher)
+    }
+
+    /// Get file names of available events, without blocking.
+    fn read_events(&self) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        let mut buf = vec![0_u8; EVENT_BUF_LEN];
+        loop {
+            let n_read = match unsafe { nc::read(self.fd, buf.as_mut_ptr() as usize, buf.len()) } {
+                Ok(n_read) => usize::try_from(n_read).unwrap_or_default(),
+                Err(nc::EAGAIN) => break,
+                Err(nc::EINTR) => continue,
+                Err(errno) => return Err(errno_to_error("Failed to read inotify events", errno)),
+            };
+            if n_read == 0 {
+                break;
+            }
+            parse_events(&buf[..n_read], &mut names);
+        }
+        Ok(names)
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let _ret = unsafe { nc::close(self.fd) };
+    }
+}
+
+fn errno_to_error(message: &str, errno: nc::Errno) -> Error {
+    Error::from_string(
+        ErrorKind::IoError,
+        format!("{message}, reason: {}", nc::strerror(errno)),
+    )
+}
+
+/// Parse file names in `struct inotify_event` list.
+fn parse_events(buf: &[u8], names: &mut Vec<String>) {
+    let mut offset = 0;
+    while offset + EVENT_HEADER_LEN <= buf.len() {
+        let len_bytes = [
+            buf[offset + 12],
+            buf[offset + 13],
+            buf[offset + 14],
+            buf[offset + 15],
+        ];
+        let name_len = u32::from_ne_bytes(len_bytes) as usize;
+        let name_start = offset + EVENT_HEADER_LEN;
+        let name_end = (name_start + name_len).min(buf.len());
+        let name = &buf[name_start..name_end];
+        // Name is padded with null bytes.
+        let name = name.split(|byte| *byte == 0).next().unwrap_or_default();
+        if !name.is_empty() {
+            names.push(String::from_utf8_lossy(name).to_string());
+        }
+        offset = name_start + name_len;
+    }
+}
+
+/// File waiting for writes to settle.
+#[derive(Debug)]
+struct PendingFile {
+    size: u64,
+    changed: Instant,
+}
+
+impl PendingFile {
+    fn new() -> Self {
+        Self {
+            size: 0,
+            changed: Instant::now(),
+        }
+    }
+}
+
+fn is_hidden_file(path: &Path) -> bool {
+    path.file_name()
+        .map_or(true, |name| name.to_string_lossy().starts_with('.'))
+}
+
+/// Update size of pending files and returns files which are ready to import.
+fn get_settled_files(pending: &mut HashMap<PathBuf, PendingFile>) -> Vec<PathBuf> {
+    let mut settled = Vec::new();
+    pending.retain(|path, file| {
+        let Ok(metadata) = fs::metadata(path) else {
+            // File is removed or renamed.
+            return false;
+        };
+        if metadata.len() != file.size {
+            file.size = metadata.len();
+            file.changed = Instant::now();
+        }
+        if file.changed.elapsed() >= SETTLE_TIME {
+            settled.push(path.clone());
+            return false;
+        }
+        true
+    });
+    settled.sort();
+    settled
+}
+
+/// Move file to `dir`, and add timestamp to file name if it already exists.
+fn move_file_to(path: &Path, dir: &Path, options: &ImportBookOptions) -> Result<PathBuf, Error> {
+    let file_name = path.file_name().unwrap_or_default();
+    let mut dest_path = dir.join(file_name);
+    if dest_path.exists() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        dest_path = dir.join(format!("{timestamp}-{}", file_name.to_string_lossy()));
+    }
+    fs::rename(path, &dest_path)?;
+    file_util::chown(&dest_path, options.uid, options.gid)?;
+    Ok(dest_path)
+}
+
+/// Import one file, move it to `done/` or `failed/` and record the result.
+fn ingest_file(
+    pg_conn: &mut PgConnection,
+    dir: &Path,
+    library_path: &str,
+    path: &Path,
+    options: &ImportBookOptions,
+) {
+    // Original file is kept in `done/`.
+    let import_options = ImportBookOptions {
+        file_action: ImportBookFileAction::Copy,
+        ..options.clone()
+    };
+    let ret = if is_supported_file(path) {
+        import_file(pg_conn, library_path, path, &import_options)
+    } else {
+        Err(Error::from_string(
+            ErrorKind::ImportError,
+            format!("Unsupported file format: {}", path.display()),
+        ))
+    };
+
+    let mut new_log = NewImportLog {
+        path: path.display().to_string(),
+        moved_to: None,
+        ok: ret.is_ok(),
+        book: None,
+        skip_reason: None,
+        error_kind: None,
+        error_message: None,
+    };
+    match ret {
+        Ok(ImportFileResult::Imported(book_id)) => {
+            log::info!("Imported {} as book {book_id}", path.display());
+            new_log.book = Some(book_id);
+        }
+        Ok(ImportFileResult::Duplicated(book_id)) => {
+            log::info!("Skip {}, same isbn as book {book_id}", path.display());
+            new_log.book = Some(book_id);
+            new_log.skip_reason = Some(format!("Same isbn as book {book_id}"));
+        }
+        Err(err) => {
+            log::warn!("Failed to import {}, err: {err:?}", path.display());
+            new_log.error_kind = Some(format!("{:?}", err.kind()));
+            new_log.error_message = Some(err.message().to_owned());
+        }
+    }
+
+    let target_dir = dir.join(if new_log.ok { DONE_DIR } else { FAILED_DIR });
+    match move_file_to(path, &target_dir, options) {
+        Ok(dest_path) => new_log.moved_to = Some(dest_path.display().to_string()),
+        Err(err) => log::error!("Failed to move {}, err: {err:?}", path.display()),
+    }
+
+    if let Err(err) = add_import_log(pg_conn, &new_log) {
+        log::error!("Failed to add import-log {new_log:?}, err: {err}");
+    }
+}
+
+/// Watch `dir` and import ebook files dropped into it.
+///
+/// Files already in `dir` are imported too. This function never returns
+/// unless an error occurs.
+pub fn watch_folder(
+    dir: &str,
+    library_path: &str,
+    options: &ImportBookOptions,
+) -> Result<(), Error> {
+    let dir = Path::new(dir);
+    create_dir_all_and_chown(dir.join(DONE_DIR), options.uid, options.gid)?;
+    create_dir_all_and_chown(dir.join(FAILED_DIR), options.uid, options.gid)?;
+
+    let watcher = Watcher::new(dir)?;
+    let pg_pool = get_connection_pool()?;
+    log::info!("Watching {}", dir.display());
+
+    let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && !is_hidden_file(&path) {
+            pending.insert(path, PendingFile::new());
+        }
+    }
+
+    loop {
+        for name in watcher.read_events()? {
+            let path = dir.join(name);
+            if path.is_file() && !is_hidden_file(&path) {
+                pending
+                    .entry(path)
+                    .and_modify(|file| file.changed = Instant::now())
+                    .or_insert_with(PendingFile::new);
+            }
+        }
+
+        let settled = get_settled_files(&mut pending);
+        if !settled.is_empty() {
+            let mut pg_conn = pg_pool.get()?;
+            for path in &settled {
+                ingest_file(&mut pg_conn, dir, library_path, path, options);
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
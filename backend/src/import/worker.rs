@@ -0,0 +1,75 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Only one worker may import or sync a library at a time.
+//!
+//! Worker holds a postgres advisory lock of the library, which is released
+//! when its database session ends. So a library held by a crashed worker can
+//! be taken over, no matter on which host or in which container it ran.
+
+use diesel::PgConnection;
+
+use crate::error::{Error, ErrorKind};
+use crate::import::models::libraries::{
+    acquire_import_library, get_import_library, release_import_library, try_lock_import_library,
+    unlock_import_library, ImportLibrary,
+};
+
+#[allow(clippy::cast_possible_wrap)]
+fn current_pid() -> i32 {
+    std::process::id() as i32
+}
+
+/// Mark current process as the only worker of import library.
+///
+/// Returns error if another living worker holds this library.
+fn acquire_worker(pg_conn: &mut PgConnection, library_id: i32) -> Result<i32, Error> {
+    if !try_lock_import_library(pg_conn, library_id)? {
+        let worker = get_import_library(pg_conn, library_id)?
+            .worker_pid
+            .map_or_else(|| "-".to_owned(), |pid| pid.to_string());
+        return Err(Error::from_string(
+            ErrorKind::ImportError,
+            format!("Import library {library_id} is held by another worker: {worker}"),
+        ));
+    }
+
+    let pid = current_pid();
+    let import_library = get_import_library(pg_conn, library_id)?;
+    if let Some(old_pid) = import_library.worker_pid {
+        log::warn!("Take over import library {library_id} from dead worker {old_pid}");
+    }
+    if let Err(err) = acquire_import_library(pg_conn, library_id, pid) {
+        release_worker(pg_conn, library_id, pid);
+        return Err(err);
+    }
+    Ok(pid)
+}
+
+/// Clear `worker_pid` and release lock of import library.
+fn release_worker(pg_conn: &mut PgConnection, library_id: i32, pid: i32) {
+    if let Err(err) = release_import_library(pg_conn, library_id, pid) {
+        log::error!("Failed to release import library {library_id}, err: {err:?}");
+    }
+    if let Err(err) = unlock_import_library(pg_conn, library_id) {
+        log::error!("Failed to unlock import library {library_id}, err: {err:?}");
+    }
+}
+
+/// Run `func` as the only worker of `import_library`.
+///
+/// `worker_pid` is cleared when `func` returns, even if it fails.
+pub fn run_as_worker<F>(
+    pg_conn: &mut PgConnection,
+    import_library: &ImportLibrary,
+    func: F,
+) -> Result<(), Error>
+where
+    F: FnOnce(&mut PgConnection) -> Result<(), Error>,
+{
+    let pid = acquire_worker(pg_conn, import_library.id)?;
+    let ret = func(pg_conn);
+    release_worker(pg_conn, import_library.id, pid);
+    ret
+}
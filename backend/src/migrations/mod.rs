@@ -1,3 +1,269 @@
 // Copyright (c) 2022 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
+
+//! Versioned database schema migrations.
+//!
+//! Every migration is embedded into the binary and applied in ascending order
+//! of version. Applied versions are recorded in `schema_version` table, so that
+//! each migration runs only once.
+
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, PgConnection};
+
+use crate::error::{Error, ErrorKind};
+use crate::migrations::schema_version::NewSchemaVersion;
+
+mod schema_version;
+mod v0_3_2;
+
+pub enum MigrationAction {
+    /// Sql statements to execute.
+    Sql(&'static str),
+
+    /// Data migration written in rust.
+    Rust(fn(&mut PgConnection) -> Result<(), Error>),
+}
+
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub action: MigrationAction,
+}
+
+/// Append new migrations to the end of list, and never modify an existing one.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_tables",
+        action: MigrationAction::Sql(include_str!("../../migrations/0001_create_tables.sql")),
+    },
+    Migration {
+        version: 2,
+        name: "v0_3_2_split_authors_and_tags",
+        action: MigrationAction::Rust(v0_3_2::migrate),
+    },
+    Migration {
+        version: 3,
+        name: "import_books_index",
+        action: MigrationAction::Sql(include_str!("../../migrations/0003_import_books_index.sql")),
+    },
+    Migration {
+        version: 4,
+        name: "import_books_skip_reason",
+        action: MigrationAction::Sql(include_str!(
+            "../../migrations/0004_import_books_skip_reason.sql"
+        )),
+    },
+    Migration {
+        version: 5,
+        name: "import_books_error",
+        action: MigrationAction::Sql(include_str!("../../migrations/0005_import_books_error.sql")),
+    },
+    Migration {
+        version: 6,
+        name: "calibre_book_metadata",
+        action: MigrationAction::Sql(include_str!(
+            "../../migrations/0006_calibre_book_metadata.sql"
+        )),
+    },
+    Migration {
+        version: 7,
+        name: "custom_fields",
+        action: MigrationAction::Sql(include_str!("../../migrations/0007_custom_fields.sql")),
+    },
+    Migration {
+        version: 8,
+        name: "import_sync",
+        action: MigrationAction::Sql(include_str!("../../migrations/0008_import_sync.sql")),
+    },
+    Migration {
+        version: 9,
+        name: "import_logs",
+        action: MigrationAction::Sql(include_str!("../../migrations/0009_import_logs.sql")),
+    },
+    Migration {
+        version: 10,
+        name: "file_logs",
+        action: MigrationAction::Sql(include_str!("../../migrations/0010_file_logs.sql")),
+    },
+    Migration {
+        version: 11,
+        name: "trash_books",
+        action: MigrationAction::Sql(include_str!("../../migrations/0011_trash_books.sql")),
+    },
+    Migration {
+        version: 12,
+        name: "foreign_keys",
+        action: MigrationAction::Sql(include_str!("../../migrations/0012_foreign_keys.sql")),
+    },
+    Migration {
+        version: 13,
+        name: "file_checksums",
+        action: MigrationAction::Sql(include_str!("../../migrations/0013_file_checksums.sql")),
+    },
+    Migration {
+        version: 14,
+        name: "book_search",
+        action: MigrationAction::Sql(include_str!("../../migrations/0014_book_search.sql")),
+    },
+    Migration {
+        version: 15,
+        name: "saved_searches",
+        action: MigrationAction::Sql(include_str!("../../migrations/0015_saved_searches.sql")),
+    },
+    Migration {
+        version: 16,
+        name: "normalized_title",
+        action: MigrationAction::Sql(include_str!("../../migrations/0016_normalized_title.sql")),
+    },
+    Migration {
+        version: 17,
+        name: "suggest_trigram_index",
+        action: MigrationAction::Sql(include_str!(
+            "../../migrations/0017_suggest_trigram_index.sql"
+        )),
+    },
+    Migration {
+        version: 18,
+        name: "import_jobs",
+        action: MigrationAction::Sql(include_str!("../../migrations/0018_import_jobs.sql")),
+    },
+    Migration {
+        version: 19,
+        name: "files_book_format_unique",
+        action: MigrationAction::Sql(include_str!(
+            "../../migrations/0019_files_book_format_unique.sql"
+        )),
+    },
+];
+
+impl Migration {
+    fn apply(&self, conn: &mut PgConnection) -> Result<(), Error> {
+        conn.transaction::<(), Error, _>(|conn| {
+            match self.action {
+                MigrationAction::Sql(sql) => conn.batch_execute(sql)?,
+                MigrationAction::Rust(func) => func(conn)?,
+            }
+            schema_version::add_version(
+                conn,
+                &NewSchemaVersion {
+                    version: self.version,
+                    name: self.name,
+                },
+            )
+        })
+    }
+}
+
+/// Get version of the newest migration.
+#[must_use]
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Get version of the newest migration applied to database.
+pub fn current_version(conn: &mut PgConnection) -> Result<i32, Error> {
+    schema_version::create_table(conn)?;
+    let versions = schema_version::get_versions(conn)?;
+    Ok(versions.last().map_or(0, |version| version.version))
+}
+
+/// Get migrations not applied yet, whose version is no greater than `target`.
+pub fn pending_migrations(
+    conn: &mut PgConnection,
+    target: i32,
+) -> Result<Vec<&'static Migration>, Error> {
+    schema_version::create_table(conn)?;
+    let applied: Vec<i32> = schema_version::get_versions(conn)?
+        .iter()
+        .map(|version| version.version)
+        .collect();
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version <= target && !applied.contains(&migration.version))
+        .collect())
+}
+
+/// Apply pending migrations up to `target` version.
+///
+/// If `target` is None, migrate to the latest version.
+/// If `dry_run` is true, only pending migrations are printed.
+pub fn migrate(conn: &mut PgConnection, target: Option<i32>, dry_run: bool) -> Result<(), Error> {
+    let target = target.unwrap_or_else(latest_version);
+    if !MIGRATIONS
+        .iter()
+        .any(|migration| migration.version == target)
+    {
+        return Err(Error::from_string(
+            ErrorKind::ConfigError,
+            format!(
+                "Invalid target schema version: {target}, latest version is {}",
+                latest_version()
+            ),
+        ));
+    }
+
+    let current = current_version(conn)?;
+    if current > target {
+        return Err(Error::from_string(
+            ErrorKind::ConfigError,
+            format!(
+                "Downgrade is not supported, current schema version: {current}, target: {target}"
+            ),
+        ));
+    }
+
+    let pending = pending_migrations(conn, target)?;
+    if pending.is_empty() {
+        log::info!("Database schema is up to date, version: {current}");
+        return Ok(());
+    }
+
+    for migration in pending {
+        if dry_run {
+            log::info!(
+                "Pending migration: {}, name: {}",
+                migration.version,
+                migration.name
+            );
+        } else {
+            log::info!(
+                "Apply migration: {}, name: {}",
+                migration.version,
+                migration.name
+            );
+            migration.apply(conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that all migrations have been applied to database.
+///
+/// Returns error if database schema is behind or ahead of current binary.
+pub fn check_schema_version(conn: &mut PgConnection) -> Result<(), Error> {
+    let latest = latest_version();
+    let current = current_version(conn)?;
+    if current > latest {
+        return Err(Error::from_string(
+            ErrorKind::DbGeneralError,
+            format!("Database schema version {current} is newer than supported version {latest}"),
+        ));
+    }
+
+    let pending = pending_migrations(conn, latest)?;
+    if !pending.is_empty() {
+        return Err(Error::from_string(
+            ErrorKind::DbGeneralError,
+            format!(
+                "Database schema is behind, {} migration(s) pending, \
+                 run `backend migrate` first",
+                pending.len()
+            ),
+        ));
+    }
+    Ok(())
+}
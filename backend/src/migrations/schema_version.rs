@@ -0,0 +1,58 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+use diesel::connection::SimpleConnection;
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
+
+use crate::error::Error;
+use crate::schema::schema_version;
+
+const CREATE_SCHEMA_VERSION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_version
+(
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+";
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = schema_version)]
+pub struct NewSchemaVersion<'a> {
+    pub version: i32,
+    pub name: &'a str,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Queryable)]
+pub struct SchemaVersion {
+    pub version: i32,
+    pub name: String,
+    pub applied: NaiveDateTime,
+}
+
+/// Create `schema_version` table if not exists.
+pub fn create_table(conn: &mut PgConnection) -> Result<(), Error> {
+    conn.batch_execute(CREATE_SCHEMA_VERSION_TABLE)
+        .map_err(Into::into)
+}
+
+pub fn add_version(conn: &mut PgConnection, new_version: &NewSchemaVersion) -> Result<(), Error> {
+    use crate::schema::schema_version::dsl::schema_version;
+    diesel::insert_into(schema_version)
+        .values(new_version)
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Get all applied versions, in ascending order.
+pub fn get_versions(conn: &mut PgConnection) -> Result<Vec<SchemaVersion>, Error> {
+    use crate::schema::schema_version::dsl::{schema_version, version};
+    schema_version
+        .order_by(version.asc())
+        .load::<SchemaVersion>(conn)
+        .map_err(Into::into)
+}
@@ -6,16 +6,13 @@
 
 use diesel::PgConnection;
 
-use crate::db;
 use crate::error::{Error, ErrorKind};
 use crate::models::{authors, books_authors, books_tags, tags};
 
-pub fn migrate() -> Result<(), Error> {
-    let db_pool = db::get_connection_pool()?;
-    let mut pg_conn = db_pool.get()?;
-
-    split_author_names(&mut pg_conn)?;
-    split_tag_names(&mut pg_conn)
+/// Split multiple author names and tag names into separated records.
+pub fn migrate(conn: &mut PgConnection) -> Result<(), Error> {
+    split_author_names(conn)?;
+    split_tag_names(conn)
 }
 
 fn split_tag_names(conn: &mut PgConnection) -> Result<(), Error> {
@@ -35,7 +32,7 @@ fn split_tag_names(conn: &mut PgConnection) -> Result<(), Error> {
                 if name.is_empty() {
                     continue;
                 }
-                let new_tag = tags::add_tag(conn, &tags::NewTag { name: name.clone() });
+                let new_tag = tags::add_tag(conn, &tags::NewTag::with_name(name.clone()));
                 match new_tag {
                     Ok(new_tag) => {
                         new_tag_ids.push(new_tag.id);
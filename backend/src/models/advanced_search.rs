@@ -2,104 +2,107 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use diesel::{
-    ExpressionMethods, JoinOnDsl, PgConnection, PgTextExpressionMethods, QueryDsl, RunQueryDsl,
-};
-use shared::advanced_search::AdvancedSearchQuery;
+//! Search books by conditions on their fields and links.
+//!
+//! Each condition is a filter on `books` table, they are combined into one
+//! `WHERE` clause, so that ordering and pagination are done in database.
+//! Filters are defined in `book_filters` module.
+
+use diesel::{BoolExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use shared::advanced_search::{AdvancedSearchQuery, MatchMode};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
 
 use crate::error::Error;
-use crate::models::books::get_books_by_ids;
+use crate::models::book_filters::{
+    author_filter, category_filter, format_filter, isbn_filter, language_filter, like_pattern,
+    pubdate_filter, publisher_filter, rating_filter, series_filter, tag_filter, title_filter,
+    user_tag_filter, BookFilter,
+};
+use crate::models::books::{book_list_to_book_authors, Book};
+use crate::models::books_query::sort_books_by_column;
+use crate::schema::books;
+use crate::settings::get_settings;
+
+fn get_filters(query: &AdvancedSearchQuery, user_id: i32) -> Vec<BookFilter> {
+    let mut filters = Vec::new();
+    if let Some(title) = &query.title {
+        filters.push(title_filter(like_pattern(title, false)));
+    }
+    if let Some(author) = &query.author {
+        filters.push(author_filter(like_pattern(author, false)));
+    }
+    if let Some(publisher) = &query.publisher {
+        filters.push(publisher_filter(like_pattern(publisher, false)));
+    }
+    if let Some(isbn) = &query.isbn {
+        filters.push(isbn_filter(isbn));
+    }
+    if let Some(tag) = &query.tag {
+        filters.push(tag_filter(like_pattern(tag, false)));
+    }
+    if let Some(series) = &query.series {
+        filters.push(series_filter(like_pattern(series, false)));
+    }
+    if let Some(serial_number) = &query.category {
+        filters.push(category_filter(serial_number));
+    }
+    if let Some(language) = &query.language {
+        filters.push(language_filter(language));
+    }
+    if let Some(format) = &query.format {
+        filters.push(format_filter(format));
+    }
+    if query.rating_min.is_some() || query.rating_max.is_some() {
+        filters.push(rating_filter(query.rating_min, query.rating_max));
+    }
+    if query.pubdate_from.is_some() || query.pubdate_to.is_some() {
+        filters.push(pubdate_filter(query.pubdate_from, query.pubdate_to));
+    }
+    if let Some(user_tag) = &query.user_tag {
+        filters.push(user_tag_filter(user_id, like_pattern(user_tag, false)));
+    }
+    filters
+}
+
+/// Combine conditions set in `query` into one filter.
+///
+/// Returns None if no condition is set.
+fn get_filter(query: &AdvancedSearchQuery, user_id: i32) -> Option<BookFilter> {
+    get_filters(query, user_id)
+        .into_iter()
+        .reduce(|acc, filter| match query.match_mode {
+            MatchMode::All => Box::new(acc.and(filter)),
+            MatchMode::Any => Box::new(acc.or(filter)),
+        })
+}
 
-#[allow(unused_assignments)]
 pub fn get_books_by_advanced_search(
     conn: &mut PgConnection,
     query: &AdvancedSearchQuery,
+    user_id: i32,
 ) -> Result<BookAndAuthorsList, Error> {
-    use crate::schema::authors;
-    use crate::schema::books;
-    use crate::schema::books_authors_link;
-    use crate::schema::books_publishers_link;
-    use crate::schema::identifier_types;
-    use crate::schema::identifiers;
-    use crate::schema::publishers;
-
     let books_query = GetBooksQuery {
         page: query.page,
         order: query.order,
     };
+    let each_page = get_settings().page_sizes.books;
+    let offset = books_query.backend_page_id() * each_page;
 
-    // TODO(Shaohua): Tuning query, replace with a subquery.
-
-    let mut book_ids = Vec::new();
-    let mut book_id_nil = true;
-    if let Some(author_name) = &query.author {
-        let author_pattern = format!("%{author_name}%");
-        let author_ids = authors::table
-            .filter(authors::name.ilike(author_pattern))
-            .select(authors::id)
-            .load::<i32>(conn)?;
-
-        book_ids = books_authors_link::table
-            .filter(books_authors_link::author.eq_any(author_ids))
-            .select(books_authors_link::book)
-            .load::<i32>(conn)?;
-        book_id_nil = false;
+    let mut total_query = books::table.into_boxed();
+    let mut list_query = sort_books_by_column(query.order);
+    if let Some(filter) = get_filter(query, user_id) {
+        total_query = total_query.filter(filter);
     }
-
-    if let Some(publisher_name) = &query.publisher {
-        let publisher_pattern = format!("%{publisher_name}%");
-        let publisher_ids = publishers::table
-            .filter(publishers::name.ilike(publisher_pattern))
-            .select(publishers::id)
-            .load::<i32>(conn)?;
-
-        if book_id_nil {
-            book_id_nil = false;
-            book_ids = books_publishers_link::table
-                .filter(books_publishers_link::publisher.eq_any(publisher_ids))
-                .select(books_publishers_link::book)
-                .load::<i32>(conn)?;
-        } else {
-            book_ids = books_publishers_link::table
-                .filter(books_publishers_link::publisher.eq_any(publisher_ids))
-                .filter(books_publishers_link::book.eq_any(book_ids))
-                .select(books_publishers_link::book)
-                .load::<i32>(conn)?;
-        }
+    if let Some(filter) = get_filter(query, user_id) {
+        list_query = list_query.filter(filter);
     }
 
-    if let Some(title) = &query.title {
-        let title_pattern = format!("%{title}%");
-        if book_id_nil {
-            book_id_nil = false;
-            book_ids = books::table
-                .filter(books::title.ilike(&title_pattern))
-                .select(books::id)
-                .load::<i32>(conn)?;
-        } else {
-            book_ids = books::table
-                .filter(books::title.ilike(&title_pattern))
-                .filter(books::id.eq_any(book_ids))
-                .select(books::id)
-                .load::<i32>(conn)?;
-        }
-    }
-
-    // SELECT book_id FROM identifiers
-    // INNER JOIN identifier_types ON identifiers.scheme = identifier_types.id
-    // WHERE identifier_types.name == 'isbn' AND identifier.value = "USER-ISBN"
-    if let Some(isbn) = &query.isbn {
-        book_id_nil = false;
-        let isbn_name = "isbn";
-        book_ids = identifiers::table
-            .inner_join(identifier_types::table.on(identifiers::scheme.eq(identifier_types::id)))
-            .filter(identifier_types::name.eq(isbn_name))
-            .filter(identifiers::value.eq(isbn))
-            .select(identifiers::book)
-            .load::<i32>(conn)?;
-    }
+    let total = total_query.count().first::<i64>(conn)?;
+    let book_list = list_query
+        .limit(each_page)
+        .offset(offset)
+        .load::<Book>(conn)?;
 
-    get_books_by_ids(conn, &books_query, &book_ids)
+    book_list_to_book_authors(conn, book_list, &books_query, total)
 }
@@ -10,11 +10,12 @@ use serde::Deserialize;
 use shared::authors::{Author, AuthorAndBook, AuthorAndBookList};
 use shared::books::AuthorAndBookId;
 use shared::general_query::{GeneralOrder, GeneralQuery};
-use shared::page::{Page, AUTHORS_EACH_PAGE};
+use shared::page::Page;
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::models::books::Book;
 use crate::schema::authors;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = authors)]
@@ -36,7 +37,8 @@ pub fn get_authors(
 ) -> Result<AuthorAndBookList, Error> {
     use crate::schema::books_authors_link;
 
-    let offset = query.backend_page_id() * AUTHORS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.authors;
+    let offset = query.backend_page_id() * each_page;
 
     let count_query = diesel::dsl::sql::<diesel::sql_types::BigInt>("count(books_authors_link.id)");
     let stmt = authors::table
@@ -48,7 +50,7 @@ pub fn get_authors(
             authors::link,
             count_query.clone(),
         ))
-        .limit(AUTHORS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset);
     let list = match query.order {
         GeneralOrder::IdDesc => stmt.order(authors::id.desc()).load::<AuthorAndBook>(conn),
@@ -64,7 +66,7 @@ pub fn get_authors(
     Ok(AuthorAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: AUTHORS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -129,3 +131,20 @@ pub fn delete_by_id(conn: &mut PgConnection, id: i32) -> Result<(), Error> {
     diesel::delete(authors::table.find(id)).execute(conn)?;
     Ok(())
 }
+
+/// Get id of author with `name`, add a new one if not found.
+pub fn get_or_add_author(conn: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    match get_author_by_name(conn, name) {
+        Ok(author) => Ok(author.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_author = NewAuthor {
+                    name: name.to_owned(),
+                    link: String::new(),
+                };
+                add_author(conn, &new_author).map(|author| author.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
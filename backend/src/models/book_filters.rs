@@ -0,0 +1,224 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Filters on `books` table, shared by advanced search and search query language.
+//!
+//! Each filter is a boxed boolean expression, so that they can be combined with
+//! `and`, `or` and `not` into one `WHERE` clause.
+
+use chrono::{NaiveDate, NaiveTime};
+use diesel::dsl::sql;
+use diesel::pg::Pg;
+use diesel::sql_types::{Bool, Text};
+use diesel::{
+    BoolExpressionMethods, BoxableExpression, ExpressionMethods, JoinOnDsl,
+    NullableExpressionMethods, PgTextExpressionMethods, QueryDsl,
+};
+
+use crate::schema::books;
+
+pub type BookFilter = Box<dyn BoxableExpression<books::table, Pg, SqlType = Bool>>;
+
+/// Convert text to pattern of `ILIKE`, which matches substring or the whole text.
+#[must_use]
+pub fn like_pattern(text: &str, exact: bool) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    if exact {
+        escaped
+    } else {
+        format!("%{escaped}%")
+    }
+}
+
+/// Books whose search document matches `keywords`.
+#[must_use]
+pub fn keywords_filter(keywords: &str) -> BookFilter {
+    Box::new(
+        sql::<Bool>(
+            "books.id IN (SELECT book FROM book_search \
+             WHERE document @@ pengzu_search_query(",
+        )
+        .bind::<Text, _>(keywords.to_owned())
+        .sql("))"),
+    )
+}
+
+#[must_use]
+pub fn title_filter(pattern: String) -> BookFilter {
+    Box::new(books::title.ilike(pattern))
+}
+
+#[must_use]
+pub fn author_filter(pattern: String) -> BookFilter {
+    use crate::schema::{authors, books_authors_link};
+
+    Box::new(
+        books::id.eq_any(
+            books_authors_link::table
+                .inner_join(authors::table.on(authors::id.eq(books_authors_link::author)))
+                .filter(authors::name.ilike(pattern))
+                .select(books_authors_link::book),
+        ),
+    )
+}
+
+#[must_use]
+pub fn publisher_filter(pattern: String) -> BookFilter {
+    use crate::schema::{books_publishers_link, publishers};
+
+    Box::new(
+        books::id.eq_any(
+            books_publishers_link::table
+                .inner_join(
+                    publishers::table.on(publishers::id.eq(books_publishers_link::publisher)),
+                )
+                .filter(publishers::name.ilike(pattern))
+                .select(books_publishers_link::book),
+        ),
+    )
+}
+
+#[must_use]
+pub fn tag_filter(pattern: String) -> BookFilter {
+    use crate::schema::{books_tags_link, tags};
+
+    Box::new(
+        books::id.eq_any(
+            books_tags_link::table
+                .inner_join(tags::table.on(tags::id.eq(books_tags_link::tag)))
+                .filter(tags::name.ilike(pattern))
+                .select(books_tags_link::book),
+        ),
+    )
+}
+
+#[must_use]
+pub fn series_filter(pattern: String) -> BookFilter {
+    use crate::schema::{books_series_link, series};
+
+    Box::new(
+        books::id.eq_any(
+            books_series_link::table
+                .inner_join(series::table.on(series::id.eq(books_series_link::series)))
+                .filter(series::name.ilike(pattern))
+                .select(books_series_link::book),
+        ),
+    )
+}
+
+#[must_use]
+pub fn isbn_filter(isbn: &str) -> BookFilter {
+    use crate::schema::{identifier_types, identifiers};
+
+    Box::new(
+        books::id.eq_any(
+            identifiers::table
+                .inner_join(
+                    identifier_types::table.on(identifier_types::id.eq(identifiers::scheme)),
+                )
+                .filter(identifier_types::name.eq("isbn"))
+                .filter(identifiers::value.eq(isbn.to_owned()))
+                .select(identifiers::book),
+        ),
+    )
+}
+
+/// Books in category with `serial_number` and all of its descendants.
+#[must_use]
+pub fn category_filter(serial_number: &str) -> BookFilter {
+    Box::new(
+        sql::<Bool>(
+            "books.id IN (SELECT book FROM books_categories_link WHERE category IN (\
+             WITH RECURSIVE sub_categories AS (\
+             SELECT id FROM categories WHERE serial_number = ",
+        )
+        .bind::<Text, _>(serial_number.to_owned())
+        .sql(
+            " UNION SELECT categories.id FROM categories \
+             INNER JOIN sub_categories ON categories.parent = sub_categories.id) \
+             SELECT id FROM sub_categories))",
+        ),
+    )
+}
+
+#[must_use]
+pub fn language_filter(lang_code: &str) -> BookFilter {
+    use crate::schema::{books_languages_link, languages};
+
+    Box::new(
+        books::id.eq_any(
+            books_languages_link::table
+                .inner_join(languages::table.on(languages::id.eq(books_languages_link::language)))
+                .filter(languages::lang_code.eq(lang_code.to_owned()))
+                .select(books_languages_link::book),
+        ),
+    )
+}
+
+#[must_use]
+pub fn format_filter(format: &str) -> BookFilter {
+    use crate::schema::{file_formats, files};
+
+    Box::new(
+        books::id.eq_any(
+            files::table
+                .inner_join(file_formats::table.on(file_formats::id.eq(files::format)))
+                .filter(file_formats::name.eq(format.to_ascii_uppercase()))
+                .select(files::book),
+        ),
+    )
+}
+
+/// Books with user tag of `user_id` matching `pattern`.
+#[must_use]
+pub fn user_tag_filter(user_id: i32, pattern: String) -> BookFilter {
+    use crate::schema::{books_user_tags_link, user_tags};
+
+    Box::new(
+        books::id.eq_any(
+            books_user_tags_link::table
+                .inner_join(user_tags::table.on(user_tags::id.eq(books_user_tags_link::tag)))
+                .filter(books_user_tags_link::user_id.eq(user_id))
+                .filter(user_tags::name.ilike(pattern))
+                .select(books_user_tags_link::book),
+        ),
+    )
+}
+
+/// Books rated in range, both ends are included.
+#[must_use]
+pub fn rating_filter(min: Option<i32>, max: Option<i32>) -> BookFilter {
+    use crate::schema::ratings;
+
+    Box::new(
+        books::id.eq_any(
+            ratings::table
+                .filter(ratings::rating.between(min.unwrap_or(i32::MIN), max.unwrap_or(i32::MAX)))
+                .select(ratings::book),
+        ),
+    )
+}
+
+/// Books published in date range, both ends are included.
+#[must_use]
+pub fn pubdate_filter(from: Option<NaiveDate>, to: Option<NaiveDate>) -> BookFilter {
+    // Postgres timestamps do not cover the whole range of chrono dates.
+    let first_day = NaiveDate::from_ymd_opt(1, 1, 1).unwrap_or_default();
+    let last_day = NaiveDate::from_ymd_opt(9999, 12, 31).unwrap_or_default();
+    let from = from.unwrap_or(first_day).and_time(NaiveTime::MIN);
+    // Include the whole last day.
+    let to = to
+        .unwrap_or(last_day)
+        .and_hms_opt(23, 59, 59)
+        .unwrap_or_default();
+
+    Box::new(
+        books::pubdate
+            .is_not_null()
+            .and(books::pubdate.assume_not_null().between(from, to)),
+    )
+}
@@ -3,19 +3,23 @@
 // that can be found in the LICENSE file.
 
 use chrono::NaiveDateTime;
-use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
+use diesel::{
+    define_sql_function, AsChangeset, ExpressionMethods, Insertable, PgConnection, QueryDsl,
+    Queryable, RunQueryDsl,
+};
 use serde::{Deserialize, Serialize};
 use shared::books::{
     AuthorAndBookId, BookAndAuthors, BookAndAuthorsList, BookUpdateReq, BookWithCover,
 };
 use shared::books_query::GetBooksQuery;
-use shared::page::{Page, BOOKS_EACH_PAGE};
+use shared::page::Page;
 
 use crate::error::{Error, ErrorKind};
 use crate::models::authors::get_authors_by_book_id;
 use crate::models::books_query::sort_books_by_column;
 use crate::models::file_data;
 use crate::schema::books;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books)]
@@ -25,9 +29,18 @@ pub struct NewBook {
     pub author_sort: String,
     pub uuid: String,
     pub has_cover: bool,
+    pub pubdate: Option<NaiveDateTime>,
+
+    /// Use current timestamp if not set.
+    pub created: Option<NaiveDateTime>,
+
+    /// Use current timestamp if not set.
+    pub last_modified: Option<NaiveDateTime>,
+    pub title_sort: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Queryable, Insertable)]
+#[diesel(table_name = books)]
 pub struct Book {
     pub id: i32,
     pub title: String,
@@ -38,6 +51,58 @@ pub struct Book {
     pub pubdate: Option<NaiveDateTime>,
     pub created: NaiveDateTime,
     pub last_modified: NaiveDateTime,
+    pub title_sort: String,
+}
+
+/// Leading articles of title, moved to the end in sort key like calibre does.
+const TITLE_SORT_ARTICLES: &[&str] = &["a", "an", "the"];
+
+/// Generate sort key of title, like `Metamorphosis, The` for `The Metamorphosis`.
+///
+/// This matches default `title_sort` of calibre, so that books imported from calibre
+/// and books edited in pengzu are sorted in the same way.
+#[must_use]
+pub fn title_sort_of(title: &str) -> String {
+    let title = title.trim();
+    if let Some((article, rest)) = title.split_once(char::is_whitespace) {
+        let rest = rest.trim_start();
+        if !rest.is_empty() && TITLE_SORT_ARTICLES.contains(&article.to_lowercase().as_str()) {
+            return format!("{rest}, {article}");
+        }
+    }
+    title.to_owned()
+}
+
+/// Metadata fields of book which can be synced from calibre.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, AsChangeset)]
+#[diesel(table_name = books, treat_none_as_null = true)]
+pub struct BookFields {
+    pub title: String,
+    pub title_sort: String,
+    pub author_sort: String,
+    pub pubdate: Option<NaiveDateTime>,
+}
+
+impl From<&Book> for BookFields {
+    fn from(book: &Book) -> Self {
+        Self {
+            title: book.title.clone(),
+            title_sort: book.title_sort.clone(),
+            author_sort: book.author_sort.clone(),
+            pubdate: book.pubdate,
+        }
+    }
+}
+
+impl From<&NewBook> for BookFields {
+    fn from(book: &NewBook) -> Self {
+        Self {
+            title: book.title.clone(),
+            title_sort: book.title_sort.clone(),
+            author_sort: book.author_sort.clone(),
+            pubdate: book.pubdate,
+        }
+    }
 }
 
 pub fn add_book(conn: &mut PgConnection, new_book: &NewBook) -> Result<Book, Error> {
@@ -56,6 +121,43 @@ pub fn get_book_by_id(conn: &mut PgConnection, book_id: i32) -> Result<BookWithC
         .map_err(Into::into)
 }
 
+pub fn get_book_record(conn: &mut PgConnection, book_id: i32) -> Result<Book, Error> {
+    books::table
+        .find(book_id)
+        .first::<Book>(conn)
+        .map_err(Into::into)
+}
+
+/// Get book record and lock it until current transaction ends.
+pub fn get_book_record_for_update(conn: &mut PgConnection, book_id: i32) -> Result<Book, Error> {
+    books::table
+        .find(book_id)
+        .for_update()
+        .first(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_book_id_by_uuid(conn: &mut PgConnection, uuid: &str) -> Result<i32, Error> {
+    books::table
+        .filter(books::uuid.eq(uuid))
+        .select(books::id)
+        .first(conn)
+        .map_err(Into::into)
+}
+
+/// Get books with the same normalized title.
+///
+/// Case, whitespaces and ascii punctuations are ignored, see `pengzu_normalize_title()`
+/// in migrations, which is indexed.
+pub fn get_books_by_title(conn: &mut PgConnection, title: &str) -> Result<Vec<Book>, Error> {
+    define_sql_function!(fn pengzu_normalize_title(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+    books::table
+        .filter(pengzu_normalize_title(books::title).eq(pengzu_normalize_title(title)))
+        .load::<Book>(conn)
+        .map_err(Into::into)
+}
+
 pub fn get_book_path_by_id(conn: &mut PgConnection, book_id: i32) -> Result<String, Error> {
     books::table
         .find(book_id)
@@ -75,6 +177,8 @@ pub fn book_to_book_cover(book: Book) -> BookWithCover {
         large_cover: file_data::get_large_cover(&book.path, book.has_cover),
         created: book.created,
         pubdate: book.pubdate,
+        last_modified: book.last_modified,
+        title_sort: book.title_sort,
     }
 }
 
@@ -104,10 +208,11 @@ pub fn get_books(
     conn: &mut PgConnection,
     query: &GetBooksQuery,
 ) -> Result<BookAndAuthorsList, Error> {
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
 
     let book_list = sort_books_by_column(query.order)
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
     let author_list = get_authors_by_book_id(conn, &book_list)?;
@@ -118,36 +223,7 @@ pub fn get_books(
     Ok(BookAndAuthorsList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: BOOKS_EACH_PAGE,
-            total,
-        },
-        list,
-    })
-}
-
-#[allow(clippy::cast_possible_wrap)]
-pub fn get_books_by_ids(
-    conn: &mut PgConnection,
-    query: &GetBooksQuery,
-    book_ids: &[i32],
-) -> Result<BookAndAuthorsList, Error> {
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
-    let total = book_ids.len() as i64;
-
-    // TODO(Shaohua): Apply query order
-    let book_list = books::table
-        .filter(books::id.eq_any(book_ids))
-        .order_by(books::id.asc())
-        .limit(BOOKS_EACH_PAGE)
-        .offset(offset)
-        .load::<Book>(conn)?;
-    let author_list = get_authors_by_book_id(conn, &book_list)?;
-    let list = merge_books_and_authors(book_list, &author_list);
-
-    Ok(BookAndAuthorsList {
-        page: Page {
-            page_num: query.frontend_page_id(),
-            each_page: BOOKS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -166,7 +242,7 @@ pub fn book_list_to_book_authors(
     Ok(BookAndAuthorsList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: BOOKS_EACH_PAGE,
+            each_page: get_settings().page_sizes.books,
             total,
         },
         list,
@@ -202,6 +278,85 @@ pub fn update_book(
     Ok(())
 }
 
+/// Update path and cover of book, after its files are copied to library.
+pub fn update_book_path(
+    conn: &mut PgConnection,
+    book_id: i32,
+    path: &str,
+    has_cover: bool,
+) -> Result<(), Error> {
+    diesel::update(books::table.find(book_id))
+        .set((books::path.eq(path), books::has_cover.eq(has_cover)))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Update cover state of book, after its cover images are changed.
+pub fn update_book_cover(
+    conn: &mut PgConnection,
+    book_id: i32,
+    has_cover: bool,
+) -> Result<(), Error> {
+    diesel::update(books::table.find(book_id))
+        .set((
+            books::has_cover.eq(has_cover),
+            books::last_modified.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Get id and path of books which have cover.
+pub fn get_book_paths_with_cover(conn: &mut PgConnection) -> Result<Vec<(i32, String)>, Error> {
+    books::table
+        .filter(books::has_cover.eq(true))
+        .select((books::id, books::path))
+        .order_by(books::id.asc())
+        .load::<(i32, String)>(conn)
+        .map_err(Into::into)
+}
+
+/// Get id, path and cover state of all books.
+pub fn get_book_paths(conn: &mut PgConnection) -> Result<Vec<(i32, String, bool)>, Error> {
+    books::table
+        .select((books::id, books::path, books::has_cover))
+        .order_by(books::id.asc())
+        .load::<(i32, String, bool)>(conn)
+        .map_err(Into::into)
+}
+
+/// Update modification time of book, after its files are changed.
+pub fn touch_book(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::update(books::table.find(book_id))
+        .set(books::last_modified.eq(diesel::dsl::now))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Update metadata fields of book.
+pub fn update_book_fields(
+    conn: &mut PgConnection,
+    book_id: i32,
+    fields: &BookFields,
+) -> Result<(), Error> {
+    diesel::update(books::table.find(book_id))
+        .set((fields, books::last_modified.eq(diesel::dsl::now)))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Delete book and all of rows referring to it.
+///
+/// Rows referring to book are removed by foreign keys. Files of book are not touched.
+pub fn delete_book(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books::table.find(book_id)).execute(conn)?;
+    Ok(())
+}
+
 pub fn get_previous_book(conn: &mut PgConnection, book_id: i32) -> Result<i32, Error> {
     let previous_id = books::table
         .filter(books::id.lt(book_id))
@@ -8,12 +8,12 @@ use serde::{Deserialize, Serialize};
 use shared::authors::Author;
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::books_authors_link;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books_authors_link)]
@@ -61,6 +61,13 @@ pub fn delete_by_id(conn: &mut PgConnection, id: i32) -> Result<(), Error> {
     Ok(())
 }
 
+/// Remove all authors of book.
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_authors_link::table.filter(books_authors_link::book.eq(book_id)))
+        .execute(conn)?;
+    Ok(())
+}
+
 pub fn get_authors_by_book(conn: &mut PgConnection, book_id: i32) -> Result<Vec<Author>, Error> {
     use crate::schema::authors;
 
@@ -93,7 +100,8 @@ pub fn get_books_by_author(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = books_authors_link::table
         .filter(books_authors_link::author.eq(author_id))
         .count()
@@ -108,7 +116,7 @@ pub fn get_books_by_author(
                     .select(books_authors_link::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
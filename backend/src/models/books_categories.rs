@@ -6,12 +6,12 @@ use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
 use shared::categories::Category;
-use shared::page::BOOKS_EACH_PAGE;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::books_categories_link;
+use crate::settings::get_settings;
 
 pub fn get_books_by_category(
     conn: &mut PgConnection,
@@ -20,7 +20,8 @@ pub fn get_books_by_category(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = books_categories_link::table
         .filter(books_categories_link::category.eq(category_id))
         .count()
@@ -35,7 +36,7 @@ pub fn get_books_by_category(
                     .select(books_categories_link::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -62,9 +63,11 @@ pub fn get_categories_by_book(
 }
 
 pub fn add_book(conn: &mut PgConnection, category_id: i32, book_id: i32) -> Result<(), Error> {
-    diesel::delete(books_categories_link::table)
-        .filter(books_categories_link::category.eq(category_id))
-        .filter(books_categories_link::book.eq(book_id))
+    diesel::insert_into(books_categories_link::table)
+        .values((
+            books_categories_link::category.eq(category_id),
+            books_categories_link::book.eq(book_id),
+        ))
         .execute(conn)?;
     Ok(())
 }
@@ -76,3 +79,9 @@ pub fn delete_book(conn: &mut PgConnection, category_id: i32, book_id: i32) -> R
         .execute(conn)?;
     Ok(())
 }
+
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_categories_link::table.filter(books_categories_link::book.eq(book_id)))
+        .execute(conn)?;
+    Ok(())
+}
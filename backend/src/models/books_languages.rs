@@ -49,6 +49,13 @@ pub fn delete_book_language(conn: &mut PgConnection, book_id: i32) -> Result<(),
     Ok(())
 }
 
+/// Remove language of book, no error if book has no language.
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_languages_link::table.filter(books_languages_link::book.eq(book_id)))
+        .execute(conn)?;
+    Ok(())
+}
+
 pub fn get_language_by_book(
     conn: &mut PgConnection,
     book_id: i32,
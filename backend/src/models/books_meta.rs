@@ -2,21 +2,42 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use diesel::PgConnection;
-use shared::books_meta::BookMetadata;
+use diesel::{Connection, PgConnection};
+use shared::books_meta::{BookMetadata, BookMetadataUpdateReq};
 
 use crate::error::{Error, ErrorKind};
-use crate::models::books::{get_book_by_id, get_next_book, get_previous_book};
-use crate::models::books_authors::get_authors_by_book;
-use crate::models::books_categories::get_categories_by_book;
-use crate::models::books_languages::get_language_by_book;
-use crate::models::books_publishers::get_publisher_by_book;
-use crate::models::books_series::get_series_by_book;
-use crate::models::books_tags::get_tags_by_book;
-use crate::models::books_user_tags::get_user_tags_by_book;
+use crate::models::authors::get_or_add_author;
+use crate::models::books::{
+    get_book_by_id, get_book_record_for_update, get_next_book, get_previous_book, title_sort_of,
+    update_book_fields, BookFields,
+};
+use crate::models::books_authors::{self, add_book_author, get_authors_by_book, NewBookAuthor};
+use crate::models::books_categories::{self, get_categories_by_book};
+use crate::models::books_languages::{
+    self, add_book_language, get_language_by_book, NewBookLanguage,
+};
+use crate::models::books_publishers::{
+    self, add_book_publisher, get_publisher_by_book, NewBookPublisher,
+};
+use crate::models::books_series::{
+    self, add_book_series, get_series_by_book, get_series_index_by_book, NewBookSeries,
+};
+use crate::models::books_tags::{self, add_book_tag, get_tags_by_book, NewBookTag};
+use crate::models::books_user_tags::{self, get_user_tags_by_book};
+use crate::models::comments::{
+    add_comment, delete_comment, get_comment, update_comment, NewComment,
+};
+use crate::models::custom_fields::get_custom_fields_by_book;
 use crate::models::files::get_book_files_and_formats;
-use crate::models::identifiers::get_identifiers_with_type;
-use crate::models::ratings::get_rating;
+use crate::models::identifiers::{self, add_identifier, get_identifiers_with_type, NewIdentifier};
+use crate::models::languages::get_or_add_language;
+use crate::models::publishers::get_or_add_publisher;
+use crate::models::ratings::{add_rating, delete_rating, get_rating, update_rating, NewRating};
+use crate::models::series::get_or_add_series;
+use crate::models::user_tags::count_tags_of_user;
+
+/// Max value of book rating.
+const MAX_RATING: i32 = 10;
 
 // TODO(Shaohua): Replace subquery with a meta table in postgres.
 pub fn get_book_metadata(
@@ -31,6 +52,7 @@ pub fn get_book_metadata(
     let files = get_book_files_and_formats(conn, book_id)?;
     let publisher = get_publisher_by_book(conn, book_id)?;
     let series = get_series_by_book(conn, book_id)?;
+    let series_index = get_series_index_by_book(conn, book_id)?;
     let categories = get_categories_by_book(conn, book_id)?;
     let rating = match get_rating(conn, book_id) {
         Ok(rating) => Some(rating),
@@ -41,6 +63,7 @@ pub fn get_book_metadata(
     };
     let lang = get_language_by_book(conn, book_id)?;
     let identifiers = get_identifiers_with_type(conn, book_id)?;
+    let custom_fields = get_custom_fields_by_book(conn, book_id)?;
     let previous_book = get_previous_book(conn, book_id).ok();
     let next_book = get_next_book(conn, book_id).ok();
 
@@ -52,11 +75,322 @@ pub fn get_book_metadata(
         files,
         publisher,
         series,
+        series_index,
         categories,
         identifiers,
         lang,
         rating,
+        custom_fields,
         previous_book,
         next_book,
     })
 }
+
+/// Treat not found error as ok, used when removing optional fields.
+fn ignore_not_found(ret: Result<(), Error>) -> Result<(), Error> {
+    match ret {
+        Err(err) if err.kind() != ErrorKind::DbNotFoundError => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn validate_update_req(req: &BookMetadataUpdateReq) -> Result<(), Error> {
+    if let Some(title) = &req.title {
+        if title.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::RequestFormError,
+                "Invalid book title",
+            ));
+        }
+    }
+    if let Some(Some(series)) = &req.series {
+        if series.name.trim().is_empty() || !series.index.is_finite() {
+            return Err(Error::new(ErrorKind::RequestFormError, "Invalid series"));
+        }
+    }
+    if let Some(authors) = &req.authors {
+        if authors.iter().any(|name| name.trim().is_empty()) {
+            return Err(Error::new(
+                ErrorKind::RequestFormError,
+                "Invalid author name",
+            ));
+        }
+    }
+    if let Some(Some(rating)) = req.rating {
+        if !(0..=MAX_RATING).contains(&rating) {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Rating shall be in range 0..={MAX_RATING}, got {rating}"),
+            ));
+        }
+    }
+    if let Some(identifiers) = &req.identifiers {
+        if identifiers
+            .iter()
+            .any(|identifier| identifier.value.trim().is_empty())
+        {
+            return Err(Error::new(
+                ErrorKind::RequestFormError,
+                "Invalid identifier value",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check modification time of book, then update fields in `books` table and authors.
+fn update_book_record(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    let book = get_book_record_for_update(conn, book_id)?;
+    if book.last_modified != req.last_modified {
+        return Err(Error::from_string(
+            ErrorKind::ConflictError,
+            format!(
+                "Book {book_id} was modified at {}, expected {}",
+                book.last_modified, req.last_modified
+            ),
+        ));
+    }
+
+    let mut fields = BookFields::from(&book);
+    if let Some(title) = &req.title {
+        title.trim().clone_into(&mut fields.title);
+        fields.title_sort = title_sort_of(&fields.title);
+    }
+    if let Some(title_sort) = &req.title_sort {
+        title_sort.trim().clone_into(&mut fields.title_sort);
+    }
+    if let Some(pubdate) = req.pubdate {
+        fields.pubdate = pubdate;
+    }
+
+    if let Some(authors) = &req.authors {
+        books_authors::delete_by_book_id(conn, book_id)?;
+        for name in authors {
+            let author = get_or_add_author(conn, name.trim())?;
+            add_book_author(
+                conn,
+                &NewBookAuthor {
+                    book: book_id,
+                    author,
+                },
+            )?;
+        }
+        fields.author_sort = authors
+            .first()
+            .map(|name| name.trim().to_owned())
+            .unwrap_or_default();
+    }
+    // Also updates last_modified.
+    update_book_fields(conn, book_id, &fields)
+}
+
+/// Update publisher, language, identifiers and tags of book.
+fn update_book_links(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    if let Some(publisher) = &req.publisher {
+        books_publishers::delete_by_book_id(conn, book_id)?;
+        if let Some(name) = publisher.as_deref().map(str::trim) {
+            if !name.is_empty() {
+                let publisher = get_or_add_publisher(conn, name)?;
+                add_book_publisher(
+                    conn,
+                    &NewBookPublisher {
+                        book: book_id,
+                        publisher,
+                    },
+                )?;
+            }
+        }
+    }
+
+    if let Some(lang) = &req.lang {
+        books_languages::delete_by_book_id(conn, book_id)?;
+        if let Some(lang_code) = lang.as_deref().map(str::trim) {
+            if !lang_code.is_empty() {
+                let language = get_or_add_language(conn, lang_code)?;
+                add_book_language(
+                    conn,
+                    &NewBookLanguage {
+                        book: book_id,
+                        language,
+                    },
+                )?;
+            }
+        }
+    }
+
+    if let Some(identifiers) = &req.identifiers {
+        identifiers::delete_by_book_id(conn, book_id)?;
+        for identifier in identifiers {
+            add_identifier(
+                conn,
+                &NewIdentifier {
+                    book: book_id,
+                    scheme: identifier.scheme,
+                    value: identifier.value.trim().to_owned(),
+                    url: identifier.url.clone(),
+                },
+            )?;
+        }
+    }
+
+    if let Some(tags) = &req.tags {
+        books_tags::delete_by_book_id(conn, book_id)?;
+        for tag in tags {
+            add_book_tag(
+                conn,
+                &NewBookTag {
+                    book: book_id,
+                    tag: *tag,
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn update_rating_and_comment(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    match req.rating {
+        Some(Some(rating)) => {
+            let new_rating = NewRating {
+                book: book_id,
+                rating,
+            };
+            match get_rating(conn, book_id) {
+                Ok(_rating) => update_rating(conn, &new_rating)?,
+                Err(err) if err.kind() == ErrorKind::DbNotFoundError => {
+                    add_rating(conn, &new_rating)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Some(None) => ignore_not_found(delete_rating(conn, book_id))?,
+        None => (),
+    }
+
+    match &req.comment {
+        Some(Some(text)) => {
+            let new_comment = NewComment {
+                book: book_id,
+                text: text.clone(),
+            };
+            match get_comment(conn, book_id) {
+                Ok(_comment) => update_comment(conn, &new_comment)?,
+                Err(err) if err.kind() == ErrorKind::DbNotFoundError => {
+                    add_comment(conn, &new_comment)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Some(None) => ignore_not_found(delete_comment(conn, book_id))?,
+        None => (),
+    }
+    Ok(())
+}
+
+/// Update series and categories.
+fn update_series_and_categories(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    if let Some(series) = &req.series {
+        books_series::delete_by_book_id(conn, book_id)?;
+        if let Some(series) = series {
+            let series_id = get_or_add_series(conn, series.name.trim())?;
+            add_book_series(
+                conn,
+                &NewBookSeries {
+                    book: book_id,
+                    series: series_id,
+                    series_index: series.index,
+                },
+            )?;
+        }
+    }
+
+    if let Some(categories) = &req.categories {
+        books_categories::delete_by_book_id(conn, book_id)?;
+        for category in categories {
+            books_categories::add_book(conn, *category, book_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace tags of user on book, tags of other users are kept.
+fn update_user_tags(
+    conn: &mut PgConnection,
+    user_id: i32,
+    book_id: i32,
+    user_tags: &[i32],
+) -> Result<(), Error> {
+    let mut tag_ids = user_tags.to_vec();
+    tag_ids.sort_unstable();
+    tag_ids.dedup();
+    if count_tags_of_user(conn, user_id, &tag_ids)? != tag_ids.len() {
+        return Err(Error::new(ErrorKind::RequestFormError, "Invalid user tags"));
+    }
+    books_user_tags::delete_by_user_and_book(conn, user_id, book_id)?;
+    for tag in tag_ids {
+        books_user_tags::add_book(conn, tag, user_id, book_id)?;
+    }
+    Ok(())
+}
+
+/// Apply metadata shared by all users, that is all fields except `user_tags`.
+fn apply_common_metadata(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    update_book_record(conn, book_id, req)?;
+    update_book_links(conn, book_id, req)?;
+    update_rating_and_comment(conn, book_id, req)?;
+    update_series_and_categories(conn, book_id, req)
+}
+
+/// Apply metadata update of book in one transaction, `user_tags` is ignored.
+///
+/// Used when metadata is changed on behalf of no user, like syncing with calibre.
+/// Returns `ConflictError` if book was modified after `req.last_modified`.
+pub fn apply_book_metadata(
+    conn: &mut PgConnection,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<(), Error> {
+    validate_update_req(req)?;
+    conn.transaction::<(), Error, _>(|conn| apply_common_metadata(conn, book_id, req))
+}
+
+/// Update metadata of book in one transaction, and returns new metadata.
+///
+/// Returns `ConflictError` if book was modified after `req.last_modified`.
+pub fn update_book_metadata(
+    conn: &mut PgConnection,
+    user_id: i32,
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<BookMetadata, Error> {
+    validate_update_req(req)?;
+    conn.transaction::<(), Error, _>(|conn| {
+        apply_common_metadata(conn, book_id, req)?;
+        if let Some(user_tags) = &req.user_tags {
+            update_user_tags(conn, user_id, book_id, user_tags)?;
+        }
+        Ok(())
+    })?;
+
+    get_book_metadata(conn, user_id, book_id)
+}
@@ -7,13 +7,13 @@ use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, R
 use serde::{Deserialize, Serialize};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
 use shared::publishers::Publisher;
 
 use crate::error::{Error, ErrorKind};
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::books_publishers_link;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books_publishers_link)]
@@ -61,11 +61,10 @@ pub fn delete_book_publisher(
     Ok(())
 }
 
-pub fn delete_by_publisher_id(conn: &mut PgConnection, publisher_id: i32) -> Result<(), Error> {
-    diesel::delete(
-        books_publishers_link::table.filter(books_publishers_link::publisher.eq(publisher_id)),
-    )
-    .execute(conn)?;
+/// Remove publisher of book.
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_publishers_link::table.filter(books_publishers_link::book.eq(book_id)))
+        .execute(conn)?;
     Ok(())
 }
 
@@ -102,7 +101,8 @@ pub fn get_books_by_publisher(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = books_publishers_link::table
         .filter(books_publishers_link::publisher.eq(publisher_id))
         .count()
@@ -117,7 +117,7 @@ pub fn get_books_by_publisher(
                     .select(books_publishers_link::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -13,8 +13,8 @@ pub fn sort_books_by_column(order: GetBooksOrder) -> books::BoxedQuery<'static,
     match order {
         GetBooksOrder::IdDesc => query.order(books::id.desc()),
         GetBooksOrder::IdAsc => query.order(books::id.asc()),
-        GetBooksOrder::TitleDesc => query.order(books::title.desc()),
-        GetBooksOrder::TitleAsc => query.order(books::title.asc()),
+        GetBooksOrder::TitleDesc => query.order((books::title_sort.desc(), books::id.desc())),
+        GetBooksOrder::TitleAsc => query.order((books::title_sort.asc(), books::id.asc())),
         GetBooksOrder::AuthorDesc => query.order(books::author_sort.desc()),
         GetBooksOrder::AuthorAsc => query.order(books::author_sort.asc()),
         GetBooksOrder::PubdateDesc => query.order(books::pubdate.desc()),
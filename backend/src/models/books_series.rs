@@ -3,23 +3,26 @@
 // that can be found in the LICENSE file.
 
 use chrono::NaiveDateTime;
-use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
+use diesel::{
+    ExpressionMethods, Insertable, JoinOnDsl, PgConnection, QueryDsl, Queryable, RunQueryDsl,
+};
 use serde::{Deserialize, Serialize};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
-use shared::series::Series;
+use shared::series::{GetSeriesBooksQuery, Series};
 
 use crate::error::{Error, ErrorKind};
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::books_series_link;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books_series_link)]
 pub struct NewBookSeries {
     pub book: i32,
     pub series: i32,
+    pub series_index: f32,
 }
 
 #[derive(Debug, Serialize, Queryable)]
@@ -28,6 +31,7 @@ pub struct BookSeries {
     pub book: i32,
     pub series: i32,
     pub created: NaiveDateTime,
+    pub series_index: f32,
 }
 
 pub fn add_book_series(
@@ -60,8 +64,8 @@ pub fn delete_book_series(
     Ok(())
 }
 
-pub fn delete_by_series_id(conn: &mut PgConnection, series_id: i32) -> Result<(), Error> {
-    diesel::delete(books_series_link::table.filter(books_series_link::series.eq(series_id)))
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_series_link::table.filter(books_series_link::book.eq(book_id)))
         .execute(conn)?;
     Ok(())
 }
@@ -89,31 +93,62 @@ pub fn get_series_by_book(conn: &mut PgConnection, book_id: i32) -> Result<Optio
     }
 }
 
+/// Get position of book in its series.
+pub fn get_series_index_by_book(
+    conn: &mut PgConnection,
+    book_id: i32,
+) -> Result<Option<f32>, Error> {
+    match get_book_series(conn, book_id) {
+        Ok(link) => Ok(Some(link.series_index)),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => Ok(None),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Get books in series, ordered by series index if order is not set in `query`.
 pub fn get_books_by_series(
     conn: &mut PgConnection,
     series_id: i32,
-    query: &GetBooksQuery,
+    query: &GetSeriesBooksQuery,
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let books_query = GetBooksQuery {
+        page: query.page,
+        order: query.order.unwrap_or_default(),
+    };
+    let offset = books_query.backend_page_id() * each_page;
     let total = books_series_link::table
         .filter(books_series_link::series.eq(series_id))
         .count()
         .first::<i64>(conn)?;
 
-    // Get book list based on a subquery.
-    let book_list = sort_books_by_column(query.order)
-        .filter(
-            books::id.eq_any(
-                books_series_link::table
-                    .filter(books_series_link::series.eq(series_id))
-                    .select(books_series_link::book),
-            ),
-        )
-        .limit(BOOKS_EACH_PAGE)
-        .offset(offset)
-        .load::<Book>(conn)?;
+    let book_list = if let Some(order) = query.order {
+        // Get book list based on a subquery.
+        sort_books_by_column(order)
+            .filter(
+                books::id.eq_any(
+                    books_series_link::table
+                        .filter(books_series_link::series.eq(series_id))
+                        .select(books_series_link::book),
+                ),
+            )
+            .limit(each_page)
+            .offset(offset)
+            .load::<Book>(conn)?
+    } else {
+        books::table
+            .inner_join(books_series_link::table.on(books_series_link::book.eq(books::id)))
+            .filter(books_series_link::series.eq(series_id))
+            .order_by((books_series_link::series_index.asc(), books::id.asc()))
+            .select(books::all_columns)
+            .limit(each_page)
+            .offset(offset)
+            .load::<Book>(conn)?
+    };
 
-    book_list_to_book_authors(conn, book_list, query, total)
+    book_list_to_book_authors(conn, book_list, &books_query, total)
 }
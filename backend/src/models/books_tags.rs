@@ -10,13 +10,13 @@ use diesel::{
 use serde::{Deserialize, Serialize};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
 use shared::tags::Tag;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::{books_tags_link, tags};
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books_tags_link)]
@@ -78,8 +78,10 @@ pub fn delete_by_id(conn: &mut PgConnection, id: i32) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn delete_by_tag_id(conn: &mut PgConnection, tag_id: i32) -> Result<(), Error> {
-    diesel::delete(books_tags_link::table.filter(books_tags_link::tag.eq(tag_id))).execute(conn)?;
+/// Remove all tags of book.
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(books_tags_link::table.filter(books_tags_link::book.eq(book_id)))
+        .execute(conn)?;
     Ok(())
 }
 
@@ -90,7 +92,8 @@ pub fn get_books_by_tag(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = books_tags_link::table
         .filter(books_tags_link::tag.eq(tag_id))
         .count()
@@ -105,7 +108,7 @@ pub fn get_books_by_tag(
                     .select(books_tags_link::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -6,13 +6,13 @@ use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, RunQueryDsl}
 use serde::Deserialize;
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
 use shared::user_tags::UserTag;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::books_user_tags_link;
+use crate::settings::get_settings;
 
 pub fn get_books_by_user_tag(
     conn: &mut PgConnection,
@@ -21,7 +21,8 @@ pub fn get_books_by_user_tag(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = books_user_tags_link::table
         .filter(books_user_tags_link::tag.eq(tag_id))
         .count()
@@ -36,7 +37,7 @@ pub fn get_books_by_user_tag(
                     .select(books_user_tags_link::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -64,17 +65,6 @@ pub fn get_user_tags_by_book(
         .map_err(Into::into)
 }
 
-pub fn delete_by_tag_id(conn: &mut PgConnection, tag_id: i32, user_id: i32) -> Result<(), Error> {
-    diesel::delete(
-        books_user_tags_link::table
-            .filter(books_user_tags_link::tag.eq(tag_id))
-            .filter(books_user_tags_link::user_id.eq(user_id)),
-    )
-    .execute(conn)
-    .map(drop)
-    .map_err(Into::into)
-}
-
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = books_user_tags_link)]
 pub struct NewLink {
@@ -117,3 +107,19 @@ pub fn delete_book(
     .map(drop)
     .map_err(Into::into)
 }
+
+/// Remove all tags of user from book.
+pub fn delete_by_user_and_book(
+    conn: &mut PgConnection,
+    user_id: i32,
+    book_id: i32,
+) -> Result<(), Error> {
+    diesel::delete(
+        books_user_tags_link::table
+            .filter(books_user_tags_link::user_id.eq(user_id))
+            .filter(books_user_tags_link::book.eq(book_id)),
+    )
+    .execute(conn)
+    .map(drop)
+    .map_err(Into::into)
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+use diesel::{
+    ExpressionMethods, Insertable, JoinOnDsl, PgConnection, QueryDsl, Queryable, RunQueryDsl,
+};
+use serde::Deserialize;
+use shared::custom_fields::{BookCustomField, CustomField, CustomValue};
+
+use crate::error::Error;
+use crate::schema::{custom_field_values, custom_fields};
+
+#[derive(Debug, Deserialize, Insertable)]
+#[diesel(table_name = custom_fields)]
+pub struct NewCustomField {
+    pub label: String,
+    pub name: String,
+    pub datatype: String,
+    pub is_multiple: bool,
+    pub display: String,
+}
+
+/// Only one of the value columns is set, based on datatype of field.
+#[derive(Debug, Default, Deserialize, Insertable)]
+#[diesel(table_name = custom_field_values)]
+pub struct NewCustomFieldValue {
+    pub book: i32,
+    pub field: i32,
+    pub text_value: Option<String>,
+    pub int_value: Option<i64>,
+    pub float_value: Option<f64>,
+    pub bool_value: Option<bool>,
+    pub date_value: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Queryable)]
+pub struct CustomFieldValue {
+    pub id: i32,
+    pub book: i32,
+    pub field: i32,
+    pub text_value: Option<String>,
+    pub int_value: Option<i64>,
+    pub float_value: Option<f64>,
+    pub bool_value: Option<bool>,
+    pub date_value: Option<NaiveDateTime>,
+    pub created: NaiveDateTime,
+}
+
+impl CustomFieldValue {
+    #[must_use]
+    pub fn value(self) -> Option<CustomValue> {
+        self.text_value
+            .map(CustomValue::Text)
+            .or_else(|| self.int_value.map(CustomValue::Int))
+            .or_else(|| self.float_value.map(CustomValue::Float))
+            .or_else(|| self.bool_value.map(CustomValue::Bool))
+            .or_else(|| self.date_value.map(CustomValue::Datetime))
+    }
+}
+
+pub fn add_custom_field(
+    conn: &mut PgConnection,
+    new_field: &NewCustomField,
+) -> Result<CustomField, Error> {
+    use crate::schema::custom_fields::dsl::custom_fields;
+    diesel::insert_into(custom_fields)
+        .values(new_field)
+        .get_result::<CustomField>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_custom_field_by_label(
+    conn: &mut PgConnection,
+    label_val: &str,
+) -> Result<CustomField, Error> {
+    use crate::schema::custom_fields::dsl::{custom_fields, label};
+    custom_fields
+        .filter(label.eq(label_val))
+        .first::<CustomField>(conn)
+        .map_err(Into::into)
+}
+
+pub fn add_custom_field_value(
+    conn: &mut PgConnection,
+    new_value: &NewCustomFieldValue,
+) -> Result<(), Error> {
+    use crate::schema::custom_field_values::dsl::custom_field_values;
+    diesel::insert_into(custom_field_values)
+        .values(new_value)
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Get custom fields of book, with values grouped by field.
+pub fn get_custom_fields_by_book(
+    conn: &mut PgConnection,
+    book_id: i32,
+) -> Result<Vec<BookCustomField>, Error> {
+    let rows = custom_field_values::table
+        .inner_join(custom_fields::table.on(custom_fields::id.eq(custom_field_values::field)))
+        .filter(custom_field_values::book.eq(book_id))
+        .order_by((custom_fields::id.asc(), custom_field_values::id.asc()))
+        .select((custom_fields::all_columns, custom_field_values::all_columns))
+        .load::<(CustomField, CustomFieldValue)>(conn)?;
+
+    let mut list: Vec<BookCustomField> = Vec::new();
+    for (field, value) in rows {
+        let Some(value) = value.value() else {
+            continue;
+        };
+        match list.last_mut() {
+            Some(book_field) if book_field.field == field.id => book_field.values.push(value),
+            _ => list.push(BookCustomField {
+                field: field.id,
+                label: field.label,
+                name: field.name,
+                datatype: field.datatype,
+                values: vec![value],
+            }),
+        }
+    }
+    Ok(list)
+}
@@ -4,11 +4,12 @@
 
 use diesel::{define_sql_function, PgConnection, QueryDsl, RunQueryDsl};
 use shared::books::BookAndAuthorsList;
-use shared::page::{default_page_id, Page, BOOKS_EACH_PAGE};
+use shared::page::{default_page_id, Page};
 
 use crate::error::Error;
 use crate::models::authors::get_authors_by_book_id;
 use crate::models::books::{merge_books_and_authors, Book};
+use crate::settings::get_settings;
 
 #[allow(clippy::redundant_pub_crate)]
 pub fn get_books(conn: &mut PgConnection) -> Result<BookAndAuthorsList, Error> {
@@ -19,9 +20,10 @@ pub fn get_books(conn: &mut PgConnection) -> Result<BookAndAuthorsList, Error> {
         fn random() -> Integer;
     );
 
+    let each_page = get_settings().page_sizes.books;
     let book_list = books::table
         .order(random())
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .load::<Book>(conn)?;
 
     let author_list = get_authors_by_book_id(conn, &book_list)?;
@@ -30,8 +32,8 @@ pub fn get_books(conn: &mut PgConnection) -> Result<BookAndAuthorsList, Error> {
     Ok(BookAndAuthorsList {
         page: Page {
             page_num: default_page_id(),
-            each_page: BOOKS_EACH_PAGE,
-            total: BOOKS_EACH_PAGE,
+            each_page,
+            total: each_page,
         },
         list,
     })
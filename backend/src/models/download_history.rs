@@ -9,11 +9,11 @@ use diesel::{
 use serde::{Deserialize, Serialize};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::schema::download_history;
+use crate::settings::get_settings;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Queryable)]
 pub struct DownloadHistory {
@@ -47,7 +47,8 @@ pub fn get_books(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::books;
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
 
     // TODO(Shaohua): Replace with `SELECT *`
     // NOTE(Shaohua): Subquery does not keep download order as expected,
@@ -73,9 +74,10 @@ pub fn get_books(
             books::pubdate,
             books::created,
             books::last_modified,
+            books::title_sort,
         ))
         .offset(offset)
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .load::<Book>(conn)?;
 
     let total = download_history::table
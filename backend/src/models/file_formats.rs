@@ -7,12 +7,13 @@ use serde::Deserialize;
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
 use shared::file_formats::{FileFormat, FileFormatAndBook, FileFormatAndBookList};
-use shared::page::{Page, PageQuery, BOOKS_EACH_PAGE, FILE_FORMATS_EACH_PAGE};
+use shared::page::{Page, PageQuery};
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
 use crate::schema::file_formats;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = file_formats)]
@@ -60,7 +61,8 @@ pub fn get_formats(
 ) -> Result<FileFormatAndBookList, Error> {
     use crate::schema::files;
 
-    let offset = query.backend_page_id() * FILE_FORMATS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.file_formats;
+    let offset = query.backend_page_id() * each_page;
 
     let list = file_formats::table
         .left_join(files::table.on(files::format.eq(file_formats::id)))
@@ -70,7 +72,7 @@ pub fn get_formats(
             file_formats::name,
             diesel::dsl::sql::<diesel::sql_types::BigInt>("count(files.id)"),
         ))
-        .limit(FILE_FORMATS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<FileFormatAndBook>(conn)?;
 
@@ -79,7 +81,7 @@ pub fn get_formats(
     Ok(FileFormatAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: FILE_FORMATS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -93,7 +95,8 @@ pub fn get_books_by_file_format(
 ) -> Result<BookAndAuthorsList, Error> {
     use crate::schema::{books, files};
 
-    let offset = query.backend_page_id() * BOOKS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.books;
+    let offset = query.backend_page_id() * each_page;
     let total = files::table
         .filter(files::format.eq(format_id))
         .count()
@@ -108,7 +111,7 @@ pub fn get_books_by_file_format(
                     .select(files::book),
             ),
         )
-        .limit(BOOKS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -0,0 +1,53 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use diesel::{Insertable, PgConnection, RunQueryDsl};
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::schema::file_logs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileLogAction {
+    Add,
+    Replace,
+    Delete,
+}
+
+impl FileLogAction {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Replace => "replace",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Insertable)]
+#[diesel(table_name = file_logs)]
+pub struct NewFileLog {
+    pub book: i32,
+    pub file: i32,
+    pub format: i32,
+    pub action: String,
+
+    /// Size of file before this change, None if it is added.
+    pub old_size: Option<i32>,
+
+    /// Size of file after this change, None if it is deleted.
+    pub new_size: Option<i32>,
+
+    pub user_id: i32,
+}
+
+pub fn add_file_log(conn: &mut PgConnection, new_log: &NewFileLog) -> Result<(), Error> {
+    use crate::schema::file_logs::dsl::file_logs;
+    diesel::insert_into(file_logs)
+        .values(new_log)
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
@@ -19,12 +19,94 @@ pub struct NewFile {
     pub format: i32,
     pub size: i32,
     pub name: String,
+    pub sha256: Option<String>,
 }
 
-pub fn add_file(conn: &mut PgConnection, new_file: &NewFile) -> Result<(), Error> {
+pub fn add_file(conn: &mut PgConnection, new_file: &NewFile) -> Result<File, Error> {
     use crate::schema::files::dsl::files;
-    diesel::insert_into(files).values(new_file).execute(conn)?;
-    Ok(())
+    diesel::insert_into(files)
+        .values(new_file)
+        .get_result::<File>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_file(conn: &mut PgConnection, book_id: i32, file_id: i32) -> Result<File, Error> {
+    files::table
+        .filter(files::id.eq(file_id))
+        .filter(files::book.eq(book_id))
+        .first::<File>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_file_by_format(
+    conn: &mut PgConnection,
+    book_id: i32,
+    format_id: i32,
+) -> Result<File, Error> {
+    files::table
+        .filter(files::book.eq(book_id))
+        .filter(files::format.eq(format_id))
+        .first::<File>(conn)
+        .map_err(Into::into)
+}
+
+/// Update size of file after its content is replaced.
+pub fn update_file_size(conn: &mut PgConnection, file_id: i32, size: i32) -> Result<(), Error> {
+    diesel::update(files::table.find(file_id))
+        .set((
+            files::size.eq(size),
+            files::last_modified.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Update size and checksum of file after its content is replaced.
+pub fn update_file_content(
+    conn: &mut PgConnection,
+    file_id: i32,
+    size: i32,
+    sha256: &str,
+) -> Result<(), Error> {
+    diesel::update(files::table.find(file_id))
+        .set((
+            files::size.eq(size),
+            files::sha256.eq(sha256),
+            files::last_modified.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+/// Set checksum of file, without changing its modification time.
+pub fn update_file_checksum(
+    conn: &mut PgConnection,
+    file_id: i32,
+    sha256: &str,
+) -> Result<(), Error> {
+    diesel::update(files::table.find(file_id))
+        .set(files::sha256.eq(sha256))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+pub fn get_file_checksum(conn: &mut PgConnection, file_id: i32) -> Result<Option<String>, Error> {
+    files::table
+        .find(file_id)
+        .select(files::sha256)
+        .first::<Option<String>>(conn)
+        .map_err(Into::into)
+}
+
+/// Delete file, its download history is removed by foreign key.
+pub fn delete_file(conn: &mut PgConnection, file_id: i32) -> Result<(), Error> {
+    diesel::delete(files::table.find(file_id))
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
 }
 
 #[must_use]
@@ -83,7 +165,59 @@ pub fn get_book_files_and_formats(
                 format_name: format.name.clone(),
                 name: file.name.clone(),
                 path,
+                sha256: file.sha256.clone(),
             }
         })
         .collect())
 }
+
+/// Get all files with their path relative to library root.
+pub fn get_all_files_with_path(conn: &mut PgConnection) -> Result<Vec<FileWithPath>, Error> {
+    use crate::schema::{books, file_formats};
+
+    let list = files::table
+        .inner_join(books::table.on(books::id.eq(files::book)))
+        .inner_join(file_formats::table.on(file_formats::id.eq(files::format)))
+        .select((
+            files::id,
+            files::book,
+            files::size,
+            files::format,
+            file_formats::name,
+            files::name,
+            books::path,
+            files::sha256,
+        ))
+        .order_by(files::id.asc())
+        .load::<(i32, i32, i32, i32, String, String, String, Option<String>)>(conn)?;
+
+    Ok(list
+        .into_iter()
+        .map(
+            |(id, book, size, format_id, format_name, name, book_path, sha256)| FileWithPath {
+                id,
+                book,
+                size,
+                format_id,
+                path: get_book_format_path(&book_path, &name, &format_name),
+                format_name,
+                name,
+                sha256,
+            },
+        )
+        .collect())
+}
+
+/// Get checksums shared by files of more than one book.
+pub fn get_duplicate_checksums(conn: &mut PgConnection) -> Result<Vec<String>, Error> {
+    use diesel::dsl::count_distinct;
+
+    let list = files::table
+        .filter(files::sha256.is_not_null())
+        .group_by(files::sha256)
+        .having(count_distinct(files::book).gt(1))
+        .select(files::sha256)
+        .order_by(files::sha256.asc())
+        .load::<Option<String>>(conn)?;
+    Ok(list.into_iter().flatten().collect())
+}
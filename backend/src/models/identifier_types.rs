@@ -6,7 +6,7 @@ use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, RunQueryDsl}
 use serde::Deserialize;
 use shared::identifier_type::IdentifierType;
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::schema::identifier_types;
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -41,3 +41,20 @@ pub fn get_identifier_type(conn: &mut PgConnection, type_id: i32) -> Result<Iden
         .first(conn)
         .map_err(Into::into)
 }
+
+/// Get id of identifier type with `name`, add a new one if not found.
+pub fn get_or_add_identifier_type(conn: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    match get_identifier_type_by_name(conn, name) {
+        Ok(identifier_type) => Ok(identifier_type.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_type = NewIdentifierType {
+                    name: name.to_owned(),
+                };
+                add_identifier_type(conn, &new_type)?;
+                get_identifier_type_by_name(conn, name).map(|identifier_type| identifier_type.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
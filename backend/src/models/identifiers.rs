@@ -28,6 +28,12 @@ pub fn add_identifier(
     Ok(())
 }
 
+/// Remove all identifiers of book.
+pub fn delete_by_book_id(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    diesel::delete(identifiers::table.filter(identifiers::book.eq(book_id))).execute(conn)?;
+    Ok(())
+}
+
 pub fn get_identifiers(conn: &mut PgConnection, book_id: i32) -> Result<Vec<Identifier>, Error> {
     identifiers::table
         .filter(identifiers::book.eq(book_id))
@@ -54,3 +60,18 @@ pub fn get_identifiers_with_type(
         .load::<IdentifierWithType>(conn)
         .map_err(Into::into)
 }
+
+/// Get id of books which contain any of identifier `values` in `scheme`.
+pub fn get_books_by_identifier(
+    conn: &mut PgConnection,
+    scheme_id: i32,
+    values: &[String],
+) -> Result<Vec<i32>, Error> {
+    identifiers::table
+        .filter(identifiers::scheme.eq(scheme_id))
+        .filter(identifiers::value.eq_any(values))
+        .select(identifiers::book)
+        .distinct()
+        .load::<i32>(conn)
+        .map_err(Into::into)
+}
@@ -6,7 +6,7 @@ use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, RunQueryDsl}
 use serde::Deserialize;
 use shared::languages::Language;
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::schema::languages;
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -35,3 +35,20 @@ pub fn get_language_by_name(
 pub fn get_all_languages(conn: &mut PgConnection) -> Result<Vec<Language>, Error> {
     languages::table.load::<Language>(conn).map_err(Into::into)
 }
+
+/// Get id of language with `lang_code`, add a new one if not found.
+pub fn get_or_add_language(conn: &mut PgConnection, lang_code: &str) -> Result<i32, Error> {
+    match get_language_by_name(conn, lang_code) {
+        Ok(language) => Ok(language.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_language = NewLanguage {
+                    lang_code: lang_code.to_owned(),
+                };
+                add_language(conn, &new_language)?;
+                get_language_by_name(conn, lang_code).map(|language| language.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
@@ -5,6 +5,7 @@
 pub mod advanced_search;
 pub mod auth;
 pub mod authors;
+pub mod book_filters;
 pub mod books;
 pub mod books_authors;
 pub mod books_categories;
@@ -17,10 +18,12 @@ pub mod books_tags;
 pub mod books_user_tags;
 pub mod categories;
 pub mod comments;
+pub mod custom_fields;
 pub mod discover;
 pub mod download_history;
 mod file_data;
 pub mod file_formats;
+pub mod file_logs;
 pub mod files;
 pub mod identifier_types;
 pub mod identifiers;
@@ -28,8 +31,12 @@ pub mod languages;
 pub mod publishers;
 pub mod ratings;
 pub mod reading_history;
+pub mod saved_searches;
+pub mod search_query;
 pub mod series;
 pub mod simple_search;
+pub mod suggest;
 pub mod tags;
+pub mod trash_books;
 pub mod user_tags;
 pub mod users;
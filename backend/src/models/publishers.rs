@@ -5,11 +5,12 @@
 use diesel::{ExpressionMethods, Insertable, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl};
 use serde::Deserialize;
 use shared::general_query::{GeneralOrder, GeneralQuery};
-use shared::page::{Page, PUBLISHERS_EACH_PAGE};
+use shared::page::Page;
 use shared::publishers::{Publisher, PublisherAndBook, PublisherAndBookList};
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::schema::publishers;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = publishers)]
@@ -31,7 +32,8 @@ pub fn get_publishers(
 ) -> Result<PublisherAndBookList, Error> {
     use crate::schema::books_publishers_link;
 
-    let offset = query.backend_page_id() * PUBLISHERS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.publishers;
+    let offset = query.backend_page_id() * each_page;
 
     let count_query =
         diesel::dsl::sql::<diesel::sql_types::BigInt>("count(books_publishers_link.id)");
@@ -41,7 +43,7 @@ pub fn get_publishers(
         )
         .group_by(publishers::id)
         .select((publishers::id, publishers::name, count_query.clone()))
-        .limit(PUBLISHERS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset);
 
     let list = match query.order {
@@ -71,7 +73,7 @@ pub fn get_publishers(
     Ok(PublisherAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: PUBLISHERS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -112,3 +114,20 @@ pub fn delete_by_id(conn: &mut PgConnection, publisher_id: i32) -> Result<(), Er
     diesel::delete(publishers::table.find(publisher_id)).execute(conn)?;
     Ok(())
 }
+
+/// Get id of publisher with `name`, add a new one if not found.
+pub fn get_or_add_publisher(conn: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    match get_publisher_by_name(conn, name) {
+        Ok(publisher) => Ok(publisher.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_publisher = NewPublisher {
+                    name: name.to_owned(),
+                };
+                add_publisher(conn, &new_publisher)?;
+                get_publisher_by_name(conn, name).map(|publisher| publisher.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
@@ -6,10 +6,11 @@ use chrono::NaiveDateTime;
 use diesel::{ExpressionMethods, PgConnection, QueryDsl, Queryable, RunQueryDsl};
 use serde::{Deserialize, Serialize};
 use shared::books_query::GetBooksQuery;
-use shared::page::{Page, READING_HISTORY_EACH_PAGE};
+use shared::page::Page;
 
 use crate::error::Error;
 use crate::schema::reading_history;
+use crate::settings::get_settings;
 
 #[derive(Debug, Serialize, Queryable)]
 pub struct History {
@@ -32,10 +33,11 @@ pub fn get_history_list(
     conn: &mut PgConnection,
     query: &GetBooksQuery,
 ) -> Result<GetHistoryResp, Error> {
-    let offset = query.backend_page_id() * READING_HISTORY_EACH_PAGE;
+    let each_page = get_settings().page_sizes.reading_history;
+    let offset = query.backend_page_id() * each_page;
 
     let list = reading_history::table
-        .limit(READING_HISTORY_EACH_PAGE)
+        .limit(each_page)
         .offset(offset)
         .load::<History>(conn)?;
 
@@ -44,7 +46,7 @@ pub fn get_history_list(
     Ok(GetHistoryResp {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: READING_HISTORY_EACH_PAGE,
+            each_page,
             total,
         },
         list,
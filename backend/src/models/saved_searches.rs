@@ -0,0 +1,184 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Searches saved by users.
+//!
+//! Query of each search is stored in json, and evaluated again each time its
+//! books are listed.
+
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, Queryable, RunQueryDsl};
+use shared::advanced_search::AdvancedSearchQuery;
+use shared::books::BookAndAuthorsList;
+use shared::saved_searches::{GetSavedSearchBooksQuery, NewSavedSearch, SavedQuery, SavedSearch};
+use shared::search_query;
+use shared::simple_search::SimpleSearchQuery;
+
+use crate::error::{Error, ErrorKind};
+use crate::models::{advanced_search, simple_search};
+use crate::schema::saved_searches;
+
+#[derive(Debug, Clone, Queryable)]
+struct SavedSearchRow {
+    id: i32,
+    user_id: i32,
+    name: String,
+    query: String,
+    created: NaiveDateTime,
+    last_modified: NaiveDateTime,
+}
+
+impl TryFrom<SavedSearchRow> for SavedSearch {
+    type Error = Error;
+
+    fn try_from(row: SavedSearchRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            query: serde_json::from_str(&row.query)?,
+            created: row.created,
+            last_modified: row.last_modified,
+        })
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = saved_searches)]
+struct NewSavedSearchRow<'a> {
+    user_id: i32,
+    name: &'a str,
+    query: String,
+}
+
+/// Check name and query of saved search.
+fn validate_search(search: &NewSavedSearch) -> Result<(), Error> {
+    if search.name.trim().is_empty() {
+        return Err(Error::new(
+            ErrorKind::RequestFormError,
+            "Name of saved search is empty",
+        ));
+    }
+    if let SavedQuery::Simple(query) = &search.query {
+        search_query::parse(query)?;
+    }
+    Ok(())
+}
+
+pub fn add_search(
+    conn: &mut PgConnection,
+    user_id: i32,
+    new_search: &NewSavedSearch,
+) -> Result<SavedSearch, Error> {
+    validate_search(new_search)?;
+    let row = NewSavedSearchRow {
+        user_id,
+        name: new_search.name.trim(),
+        query: serde_json::to_string(&new_search.query)?,
+    };
+    diesel::insert_into(saved_searches::table)
+        .values(&row)
+        .get_result::<SavedSearchRow>(conn)?
+        .try_into()
+}
+
+pub fn get_search(
+    conn: &mut PgConnection,
+    search_id: i32,
+    user_id: i32,
+) -> Result<SavedSearch, Error> {
+    saved_searches::table
+        .filter(saved_searches::id.eq(search_id))
+        .filter(saved_searches::user_id.eq(user_id))
+        .first::<SavedSearchRow>(conn)?
+        .try_into()
+}
+
+/// Get all saved searches of user, sorted by name.
+///
+/// Searches with invalid query, like those saved by older versions, are skipped.
+pub fn get_searches(conn: &mut PgConnection, user_id: i32) -> Result<Vec<SavedSearch>, Error> {
+    let rows = saved_searches::table
+        .filter(saved_searches::user_id.eq(user_id))
+        .order((saved_searches::name.asc(), saved_searches::id.asc()))
+        .load::<SavedSearchRow>(conn)?;
+    let searches = rows
+        .into_iter()
+        .filter_map(|row| {
+            let search_id = row.id;
+            SavedSearch::try_from(row)
+                .map_err(|err| log::warn!("Invalid saved search {search_id}, err: {err:?}"))
+                .ok()
+        })
+        .collect();
+    Ok(searches)
+}
+
+pub fn update_search(
+    conn: &mut PgConnection,
+    search_id: i32,
+    user_id: i32,
+    new_search: &NewSavedSearch,
+) -> Result<SavedSearch, Error> {
+    validate_search(new_search)?;
+    diesel::update(
+        saved_searches::table
+            .filter(saved_searches::id.eq(search_id))
+            .filter(saved_searches::user_id.eq(user_id)),
+    )
+    .set((
+        saved_searches::name.eq(new_search.name.trim()),
+        saved_searches::query.eq(serde_json::to_string(&new_search.query)?),
+        saved_searches::last_modified.eq(diesel::dsl::now),
+    ))
+    .get_result::<SavedSearchRow>(conn)?
+    .try_into()
+}
+
+pub fn delete_search(conn: &mut PgConnection, search_id: i32, user_id: i32) -> Result<(), Error> {
+    let count = diesel::delete(
+        saved_searches::table
+            .filter(saved_searches::id.eq(search_id))
+            .filter(saved_searches::user_id.eq(user_id)),
+    )
+    .execute(conn)?;
+    if count == 0 {
+        return Err(Error::from_string(
+            ErrorKind::DbNotFoundError,
+            format!("Saved search {search_id} not found"),
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluate saved search, with page and order in `query`.
+///
+/// Advanced search keeps its saved order and simple search sorts by relevance,
+/// if order is not set in `query`.
+pub fn get_books(
+    conn: &mut PgConnection,
+    search_id: i32,
+    user_id: i32,
+    query: &GetSavedSearchBooksQuery,
+) -> Result<BookAndAuthorsList, Error> {
+    let search = get_search(conn, search_id, user_id)?;
+    match search.query {
+        SavedQuery::Advanced(advanced_query) => {
+            let advanced_query = AdvancedSearchQuery {
+                page: query.page,
+                order: query.order.unwrap_or(advanced_query.order),
+                ..*advanced_query
+            };
+            advanced_search::get_books_by_advanced_search(conn, &advanced_query, user_id)
+        }
+        SavedQuery::Simple(simple_query) => {
+            let simple_query = SimpleSearchQuery {
+                page: query.page,
+                order: query.order,
+                query: simple_query,
+            };
+            simple_search::get_books_by_simple_search(conn, &simple_query, user_id)
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Compile search query, parsed by `shared::search_query`, to filter on `books` table.
+//!
+//! Functions here recurse on expression tree, whose size is limited by parser
+//! with `MAX_TERMS` and `MAX_DEPTH`.
+
+use diesel::BoolExpressionMethods;
+use shared::search_query::{CompareOp, Expr, Term, TextField};
+
+use crate::models::book_filters::{
+    author_filter, category_filter, format_filter, isbn_filter, keywords_filter, language_filter,
+    like_pattern, pubdate_filter, publisher_filter, rating_filter, series_filter, tag_filter,
+    title_filter, user_tag_filter, BookFilter,
+};
+
+fn text_filter(field: TextField, value: &str, exact: bool, user_id: i32) -> BookFilter {
+    match field {
+        TextField::Title => title_filter(like_pattern(value, exact)),
+        TextField::Author => author_filter(like_pattern(value, exact)),
+        TextField::Publisher => publisher_filter(like_pattern(value, exact)),
+        TextField::Tag => tag_filter(like_pattern(value, exact)),
+        TextField::Series => series_filter(like_pattern(value, exact)),
+        TextField::Isbn => isbn_filter(value),
+        TextField::Category => category_filter(value),
+        TextField::Language => language_filter(value),
+        TextField::Format => format_filter(value),
+        TextField::UserTag => user_tag_filter(user_id, like_pattern(value, exact)),
+    }
+}
+
+fn term_filter(term: &Term, user_id: i32) -> BookFilter {
+    match term {
+        Term::Keywords(keywords) => keywords_filter(keywords),
+        Term::Text {
+            field,
+            value,
+            exact,
+        } => text_filter(*field, value, *exact, user_id),
+        Term::Rating { op, value } => {
+            let value = *value;
+            match op {
+                CompareOp::Equal => rating_filter(Some(value), Some(value)),
+                CompareOp::Less => rating_filter(None, Some(value.saturating_sub(1))),
+                CompareOp::LessEqual => rating_filter(None, Some(value)),
+                CompareOp::Greater => rating_filter(Some(value.saturating_add(1)), None),
+                CompareOp::GreaterEqual => rating_filter(Some(value), None),
+            }
+        }
+        Term::Pubdate { op, value } => {
+            let value = *value;
+            match op {
+                CompareOp::Equal => pubdate_filter(Some(value), Some(value)),
+                CompareOp::Less => pubdate_filter(None, value.pred_opt()),
+                CompareOp::LessEqual => pubdate_filter(None, Some(value)),
+                CompareOp::Greater => pubdate_filter(value.succ_opt(), None),
+                CompareOp::GreaterEqual => pubdate_filter(Some(value), None),
+            }
+        }
+    }
+}
+
+/// Convert search query to filter, `user_id` is used to match user tags.
+#[must_use]
+pub fn compile(expr: &Expr, user_id: i32) -> BookFilter {
+    match expr {
+        Expr::Term(term) => term_filter(term, user_id),
+        Expr::Not(expr) => Box::new(diesel::dsl::not(compile(expr, user_id))),
+        Expr::And(lhs, rhs) => Box::new(compile(lhs, user_id).and(compile(rhs, user_id))),
+        Expr::Or(lhs, rhs) => Box::new(compile(lhs, user_id).or(compile(rhs, user_id))),
+    }
+}
+
+/// Get keywords in query which are used to rank books by relevance.
+///
+/// Keywords in `not` terms are skipped.
+#[must_use]
+pub fn rank_keywords(expr: &Expr) -> String {
+    fn collect<'a>(expr: &'a Expr, keywords: &mut Vec<&'a str>) {
+        match expr {
+            Expr::Term(Term::Keywords(words)) => keywords.push(words),
+            Expr::Term(_) | Expr::Not(_) => (),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                collect(lhs, keywords);
+                collect(rhs, keywords);
+            }
+        }
+    }
+
+    let mut keywords = Vec::new();
+    collect(expr, &mut keywords);
+    keywords.join(" ")
+}
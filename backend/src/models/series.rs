@@ -5,11 +5,12 @@
 use diesel::{ExpressionMethods, Insertable, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl};
 use serde::Deserialize;
 use shared::general_query::{GeneralOrder, GeneralQuery};
-use shared::page::{Page, SERIES_EACH_PAGE};
+use shared::page::Page;
 use shared::series::{Series, SeriesAndBook, SeriesAndBookList};
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::schema::series;
+use crate::settings::get_settings;
 
 #[derive(Debug, Deserialize, Insertable)]
 #[diesel(table_name = series)]
@@ -31,14 +32,15 @@ pub fn get_series_list(
 ) -> Result<SeriesAndBookList, Error> {
     use crate::schema::books_series_link;
 
-    let offset = query.backend_page_id() * SERIES_EACH_PAGE;
+    let each_page = get_settings().page_sizes.series;
+    let offset = query.backend_page_id() * each_page;
 
     let count_query = diesel::dsl::sql::<diesel::sql_types::BigInt>("count(books_series_link.id)");
     let stmt = series::table
         .left_join(books_series_link::table.on(books_series_link::series.eq(series::id)))
         .group_by(series::id)
         .select((series::id, series::name, count_query.clone()))
-        .limit(SERIES_EACH_PAGE)
+        .limit(each_page)
         .offset(offset);
     let list = match query.order {
         GeneralOrder::IdDesc => stmt.order(series::id.desc()).load::<SeriesAndBook>(conn),
@@ -66,7 +68,7 @@ pub fn get_series_list(
     Ok(SeriesAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: SERIES_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -104,3 +106,20 @@ pub fn delete_by_id(conn: &mut PgConnection, series_id: i32) -> Result<(), Error
     diesel::delete(series::table.find(series_id)).execute(conn)?;
     Ok(())
 }
+
+/// Get id of series with `name`, add a new one if not found.
+pub fn get_or_add_series(conn: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    match get_series_by_name(conn, name) {
+        Ok(series) => Ok(series.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                let new_series = NewSeries {
+                    name: name.to_owned(),
+                };
+                add_series(conn, &new_series)?;
+                get_series_by_name(conn, name).map(|series| series.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
@@ -2,38 +2,67 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use diesel::{PgConnection, PgTextExpressionMethods, QueryDsl, RunQueryDsl};
+//! Search books by query of search box.
+//!
+//! Query is parsed by `shared::search_query`, and its keywords are matched against
+//! search documents, which are maintained by database triggers in `book_search` table,
+//! see `0014_book_search.sql` migration.
+
+use diesel::dsl::sql;
+use diesel::pg::Pg;
+use diesel::sql_types::{Float, Text};
+use diesel::{BoxableExpression, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use shared::books::BookAndAuthorsList;
 use shared::books_query::GetBooksQuery;
-use shared::page::BOOKS_EACH_PAGE;
+use shared::search_query;
 use shared::simple_search::SimpleSearchQuery;
 
 use crate::error::Error;
 use crate::models::books::{book_list_to_book_authors, Book};
 use crate::models::books_query::sort_books_by_column;
+use crate::models::search_query::{compile, rank_keywords};
+use crate::schema::books;
+use crate::settings::get_settings;
+
+/// Relevance of book to `keywords`.
+fn relevance(keywords: &str) -> Box<dyn BoxableExpression<books::table, Pg, SqlType = Float>> {
+    Box::new(
+        sql::<Float>("(SELECT ts_rank(document, pengzu_search_query(")
+            .bind::<Text, _>(keywords.to_owned())
+            .sql(")) FROM book_search WHERE book_search.book = books.id)"),
+    )
+}
 
 pub fn get_books_by_simple_search(
     conn: &mut PgConnection,
     query: &SimpleSearchQuery,
+    user_id: i32,
 ) -> Result<BookAndAuthorsList, Error> {
-    use crate::schema::books;
-
     let books_query = GetBooksQuery {
         page: query.page,
-        order: query.order,
+        order: query.order.unwrap_or_default(),
     };
-    let offset = books_query.backend_page_id() * BOOKS_EACH_PAGE;
-    let query_pattern = format!("%{}%", query.query);
-
-    let total = books::table
-        .filter(books::title.ilike(&query_pattern))
-        .count()
-        .first::<i64>(conn)?;
-
-    // Get book list based on a subquery.
-    let book_list = sort_books_by_column(query.order)
-        .filter(books::title.ilike(&query_pattern))
-        .limit(BOOKS_EACH_PAGE)
+    let each_page = get_settings().page_sizes.books;
+    let offset = books_query.backend_page_id() * each_page;
+    let expr = search_query::parse(&query.query)?;
+    let keywords = expr.as_ref().map(rank_keywords).unwrap_or_default();
+
+    let mut total_query = books::table.into_boxed();
+    let mut list_query = match query.order {
+        Some(order) => sort_books_by_column(order),
+        None if keywords.is_empty() => sort_books_by_column(books_query.order),
+        None => books::table
+            .into_boxed()
+            .order((relevance(&keywords).desc(), books::id.desc())),
+    };
+    if let Some(expr) = &expr {
+        total_query = total_query.filter(compile(expr, user_id));
+        list_query = list_query.filter(compile(expr, user_id));
+    }
+
+    let total = total_query.count().first::<i64>(conn)?;
+    let book_list = list_query
+        .limit(each_page)
         .offset(offset)
         .load::<Book>(conn)?;
 
@@ -0,0 +1,111 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Search-as-you-type suggestions.
+//!
+//! Names are matched by prefix of the whole name or of any word in it,
+//! whole-name matches come first, then the most frequently used ones.
+//! Chinese and Japanese text has no space between words, so it is matched
+//! anywhere in names. Names are indexed with trigrams for these `ILIKE` patterns.
+
+use diesel::sql_types::{BigInt, Integer, Text};
+use diesel::{sql_query, PgConnection, QueryableByName, RunQueryDsl};
+use shared::suggest::{Suggestion, SuggestionGroup, SuggestionKind, SuggestionList};
+
+use crate::error::Error;
+use crate::models::book_filters::like_pattern;
+
+/// Max number of suggestions in each group.
+const EACH_KIND: i64 = 5;
+
+/// Table, name column, and link table with column referring to that table,
+/// which is used to count frequency.
+const fn source_of(
+    kind: SuggestionKind,
+) -> (&'static str, &'static str, &'static str, &'static str) {
+    match kind {
+        SuggestionKind::Book => ("books", "title", "download_history", "book"),
+        SuggestionKind::Author => ("authors", "name", "books_authors_link", "author"),
+        SuggestionKind::Publisher => ("publishers", "name", "books_publishers_link", "publisher"),
+        SuggestionKind::Series => ("series", "name", "books_series_link", "series"),
+        SuggestionKind::Tag => ("tags", "name", "books_tags_link", "tag"),
+        SuggestionKind::Category => ("categories", "name", "books_categories_link", "category"),
+    }
+}
+
+/// Check whether text contains Chinese or Japanese chars, the same ranges as
+/// `pengzu_cjk_bigrams()` in migrations.
+fn has_cjk(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(
+            c,
+            '\u{3040}'..='\u{30ff}'
+                | '\u{3400}'..='\u{4dbf}'
+                | '\u{4e00}'..='\u{9fff}'
+                | '\u{f900}'..='\u{faff}'
+        )
+    })
+}
+
+#[derive(Debug, Clone, QueryableByName)]
+struct SuggestionRow {
+    #[diesel(sql_type = Integer)]
+    id: i32,
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+fn get_suggestions_of_kind(
+    conn: &mut PgConnection,
+    kind: SuggestionKind,
+    prefix: &str,
+) -> Result<Vec<Suggestion>, Error> {
+    let (table, name, link_table, link_column) = source_of(kind);
+    let pattern = like_pattern(prefix, true);
+    let word_pattern = if has_cjk(prefix) {
+        format!("%{pattern}%")
+    } else {
+        format!("% {pattern}%")
+    };
+    let rows = sql_query(format!(
+        "SELECT t.id, t.{name} AS name, COUNT(l.id) AS count \
+         FROM {table} t LEFT JOIN {link_table} l ON l.{link_column} = t.id \
+         WHERE t.{name} ILIKE $1 OR t.{name} ILIKE $2 \
+         GROUP BY t.id \
+         ORDER BY t.{name} ILIKE $1 DESC, count DESC, t.{name} \
+         LIMIT $3"
+    ))
+    .bind::<Text, _>(format!("{pattern}%"))
+    .bind::<Text, _>(word_pattern)
+    .bind::<BigInt, _>(EACH_KIND)
+    .load::<SuggestionRow>(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Suggestion {
+            id: row.id,
+            name: row.name,
+            count: row.count,
+        })
+        .collect())
+}
+
+/// Get suggestions of all kinds whose name starts with `prefix`.
+pub fn get_suggestions(conn: &mut PgConnection, prefix: &str) -> Result<SuggestionList, Error> {
+    let prefix = prefix.trim();
+    if prefix.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut groups = Vec::new();
+    for kind in SuggestionKind::ALL {
+        let list = get_suggestions_of_kind(conn, kind, prefix)?;
+        if !list.is_empty() {
+            groups.push(SuggestionGroup { kind, list });
+        }
+    }
+    Ok(groups)
+}
@@ -8,12 +8,13 @@ use diesel::{
 };
 use serde::Deserialize;
 use shared::general_query::GeneralOrder;
-use shared::page::{Page, TAGS_EACH_PAGE};
+use shared::page::Page;
 use shared::recursive_query::RecursiveQuery;
 use shared::tags::{SearchTagQuery, Tag, TagAndBook, TagAndBookList};
 
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::schema::tags;
+use crate::settings::get_settings;
 
 #[derive(Debug, Default, PartialEq, Eq, Deserialize, Insertable)]
 #[diesel(table_name = tags)]
@@ -75,7 +76,8 @@ pub fn get_tags_by_name_pattern(
 pub fn get_tags(conn: &mut PgConnection, query: &RecursiveQuery) -> Result<TagAndBookList, Error> {
     use crate::schema::books_tags_link;
 
-    let offset = query.backend_page_id() * TAGS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.tags;
+    let offset = query.backend_page_id() * each_page;
 
     // TODO(Shaohua): Get children count.
     let count_query = diesel::dsl::sql::<diesel::sql_types::BigInt>("count(books_tags_link.id)");
@@ -94,7 +96,7 @@ pub fn get_tags(conn: &mut PgConnection, query: &RecursiveQuery) -> Result<TagAn
             child_count_query,
         ))
         .filter(tags::parent.eq(query.parent))
-        .limit(TAGS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset);
 
     let list = match query.order {
@@ -126,7 +128,7 @@ pub fn get_tags(conn: &mut PgConnection, query: &RecursiveQuery) -> Result<TagAn
     Ok(TagAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: TAGS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -150,3 +152,16 @@ pub fn search(conn: &mut PgConnection, query: &SearchTagQuery) -> Result<Vec<Tag
     let name_pattern = format!("%{}%", query.keyword);
     get_tags_by_name_pattern(conn, &name_pattern)
 }
+
+/// Get id of tag with `name`, add a new one if not found.
+pub fn get_or_add_tag(conn: &mut PgConnection, name: &str) -> Result<i32, Error> {
+    match get_tag_by_name(conn, name) {
+        Ok(tag) => Ok(tag.id),
+        Err(err) => match err.kind() {
+            ErrorKind::DbNotFoundError => {
+                add_tag(conn, &NewTag::with_name(name.to_owned())).map(|tag| tag.id)
+            }
+            _ => Err(err),
+        },
+    }
+}
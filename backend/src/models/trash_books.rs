@@ -0,0 +1,398 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Keep deleted books and rows referring to them, so that they can be restored.
+//!
+//! Reading history and download history of book are not kept.
+
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Insertable, PgConnection, QueryDsl, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use shared::trash::TrashBook;
+
+use crate::error::Error;
+use crate::models::books::Book;
+use crate::schema::trash_books;
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = trash_books)]
+pub struct NewTrashBook {
+    pub book: i32,
+    pub title: String,
+    pub path: String,
+    pub record: String,
+    pub links: String,
+    pub user_id: i32,
+}
+
+/// Rows referring to book in other tables, without their ids.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookLinks {
+    pub authors: Vec<i32>,
+    pub categories: Vec<i32>,
+    pub languages: Vec<i32>,
+    pub publishers: Vec<i32>,
+
+    /// Series id and series index.
+    pub series: Vec<(i32, f32)>,
+    pub tags: Vec<i32>,
+
+    /// User id and user tag id.
+    pub user_tags: Vec<(i32, i32)>,
+    pub comments: Vec<String>,
+    pub custom_fields: Vec<CustomFieldValueRecord>,
+
+    /// File format id, file size and file name.
+    pub files: Vec<(i32, i32, String)>,
+
+    /// Checksum of each file in `files`, empty in books deleted before checksums are added.
+    #[serde(default)]
+    pub file_checksums: Vec<Option<String>>,
+
+    /// Scheme id, value and url.
+    pub identifiers: Vec<(i32, String, Option<String>)>,
+    pub ratings: Vec<i32>,
+
+    /// Id of `import_books` rows, which map calibre books to this book.
+    #[serde(default)]
+    pub import_books: Vec<i32>,
+}
+
+pub type CustomFieldValueRecord = (
+    i32,
+    Option<String>,
+    Option<i64>,
+    Option<f64>,
+    Option<bool>,
+    Option<NaiveDateTime>,
+);
+
+pub fn add_trash_book(conn: &mut PgConnection, new_book: &NewTrashBook) -> Result<(), Error> {
+    diesel::insert_into(trash_books::table)
+        .values(new_book)
+        .execute(conn)
+        .map(drop)
+        .map_err(Into::into)
+}
+
+const TRASH_BOOK_COLUMNS: (
+    trash_books::id,
+    trash_books::book,
+    trash_books::title,
+    trash_books::path,
+    trash_books::user_id,
+    trash_books::created,
+) = (
+    trash_books::id,
+    trash_books::book,
+    trash_books::title,
+    trash_books::path,
+    trash_books::user_id,
+    trash_books::created,
+);
+
+/// Get all books in trash, latest deleted first.
+pub fn get_trash_books(conn: &mut PgConnection) -> Result<Vec<TrashBook>, Error> {
+    trash_books::table
+        .select(TRASH_BOOK_COLUMNS)
+        .order_by(trash_books::created.desc())
+        .load::<TrashBook>(conn)
+        .map_err(Into::into)
+}
+
+pub fn get_trash_book(conn: &mut PgConnection, book_id: i32) -> Result<TrashBook, Error> {
+    trash_books::table
+        .filter(trash_books::book.eq(book_id))
+        .select(TRASH_BOOK_COLUMNS)
+        .first::<TrashBook>(conn)
+        .map_err(Into::into)
+}
+
+/// Get book record and its links kept in trash.
+pub fn get_trash_book_record(
+    conn: &mut PgConnection,
+    book_id: i32,
+) -> Result<(Book, BookLinks), Error> {
+    let (record, links) = trash_books::table
+        .filter(trash_books::book.eq(book_id))
+        .select((trash_books::record, trash_books::links))
+        .first::<(String, String)>(conn)?;
+    let book = serde_json::from_str(&record)?;
+    let links = serde_json::from_str(&links)?;
+    Ok((book, links))
+}
+
+/// Get id of books deleted more than `keep_days` days ago.
+pub fn get_expired_trash_books(conn: &mut PgConnection, keep_days: i32) -> Result<Vec<i32>, Error> {
+    use diesel::dsl::{now, IntervalDsl};
+    trash_books::table
+        .filter(trash_books::created.lt(now - keep_days.days()))
+        .select(trash_books::book)
+        .order_by(trash_books::id.asc())
+        .load::<i32>(conn)
+        .map_err(Into::into)
+}
+
+pub fn delete_trash_book(conn: &mut PgConnection, book_id: i32) -> Result<(), Error> {
+    let _trash_book = get_trash_book(conn, book_id)?;
+    diesel::delete(trash_books::table.filter(trash_books::book.eq(book_id))).execute(conn)?;
+    Ok(())
+}
+
+/// Read rows referring to book.
+pub fn get_book_links(conn: &mut PgConnection, book_id: i32) -> Result<BookLinks, Error> {
+    use crate::schema::{
+        books_authors_link, books_categories_link, books_languages_link, books_publishers_link,
+        books_series_link, books_tags_link, books_user_tags_link, comments, custom_field_values,
+        files, identifiers, import_books, ratings,
+    };
+
+    let authors = books_authors_link::table
+        .filter(books_authors_link::book.eq(book_id))
+        .order_by(books_authors_link::id.asc())
+        .select(books_authors_link::author)
+        .load(conn)?;
+    let categories = books_categories_link::table
+        .filter(books_categories_link::book.eq(book_id))
+        .order_by(books_categories_link::id.asc())
+        .select(books_categories_link::category)
+        .load(conn)?;
+    let languages = books_languages_link::table
+        .filter(books_languages_link::book.eq(book_id))
+        .order_by(books_languages_link::id.asc())
+        .select(books_languages_link::language)
+        .load(conn)?;
+    let publishers = books_publishers_link::table
+        .filter(books_publishers_link::book.eq(book_id))
+        .order_by(books_publishers_link::id.asc())
+        .select(books_publishers_link::publisher)
+        .load(conn)?;
+    let series = books_series_link::table
+        .filter(books_series_link::book.eq(book_id))
+        .order_by(books_series_link::id.asc())
+        .select((books_series_link::series, books_series_link::series_index))
+        .load(conn)?;
+    let tags = books_tags_link::table
+        .filter(books_tags_link::book.eq(book_id))
+        .order_by(books_tags_link::id.asc())
+        .select(books_tags_link::tag)
+        .load(conn)?;
+    let user_tags = books_user_tags_link::table
+        .filter(books_user_tags_link::book.eq(book_id))
+        .order_by(books_user_tags_link::id.asc())
+        .select((books_user_tags_link::user_id, books_user_tags_link::tag))
+        .load(conn)?;
+    let comments = comments::table
+        .filter(comments::book.eq(book_id))
+        .order_by(comments::id.asc())
+        .select(comments::text)
+        .load(conn)?;
+    let custom_fields = custom_field_values::table
+        .filter(custom_field_values::book.eq(book_id))
+        .order_by(custom_field_values::id.asc())
+        .select((
+            custom_field_values::field,
+            custom_field_values::text_value,
+            custom_field_values::int_value,
+            custom_field_values::float_value,
+            custom_field_values::bool_value,
+            custom_field_values::date_value,
+        ))
+        .load(conn)?;
+    let files = files::table
+        .filter(files::book.eq(book_id))
+        .order_by(files::id.asc())
+        .select((files::format, files::size, files::name))
+        .load(conn)?;
+    let file_checksums = files::table
+        .filter(files::book.eq(book_id))
+        .order_by(files::id.asc())
+        .select(files::sha256)
+        .load(conn)?;
+    let identifiers = identifiers::table
+        .filter(identifiers::book.eq(book_id))
+        .order_by(identifiers::id.asc())
+        .select((identifiers::scheme, identifiers::value, identifiers::url))
+        .load(conn)?;
+    let ratings = ratings::table
+        .filter(ratings::book.eq(book_id))
+        .order_by(ratings::id.asc())
+        .select(ratings::rating)
+        .load(conn)?;
+    let import_books = import_books::table
+        .filter(import_books::book.eq(book_id))
+        .order_by(import_books::id.asc())
+        .select(import_books::id)
+        .load(conn)?;
+
+    Ok(BookLinks {
+        authors,
+        categories,
+        languages,
+        publishers,
+        series,
+        tags,
+        user_tags,
+        comments,
+        custom_fields,
+        files,
+        file_checksums,
+        identifiers,
+        ratings,
+        import_books,
+    })
+}
+
+/// Insert rows of `books_*_link` tables.
+fn restore_link_tables(
+    conn: &mut PgConnection,
+    book_id: i32,
+    links: &BookLinks,
+) -> Result<(), Error> {
+    use crate::schema::{
+        books_authors_link, books_categories_link, books_languages_link, books_publishers_link,
+        books_series_link, books_tags_link, books_user_tags_link,
+    };
+
+    for author in &links.authors {
+        diesel::insert_into(books_authors_link::table)
+            .values((
+                books_authors_link::book.eq(book_id),
+                books_authors_link::author.eq(author),
+            ))
+            .execute(conn)?;
+    }
+    for category in &links.categories {
+        diesel::insert_into(books_categories_link::table)
+            .values((
+                books_categories_link::book.eq(book_id),
+                books_categories_link::category.eq(category),
+            ))
+            .execute(conn)?;
+    }
+    for language in &links.languages {
+        diesel::insert_into(books_languages_link::table)
+            .values((
+                books_languages_link::book.eq(book_id),
+                books_languages_link::language.eq(language),
+            ))
+            .execute(conn)?;
+    }
+    for publisher in &links.publishers {
+        diesel::insert_into(books_publishers_link::table)
+            .values((
+                books_publishers_link::book.eq(book_id),
+                books_publishers_link::publisher.eq(publisher),
+            ))
+            .execute(conn)?;
+    }
+    for (series, series_index) in &links.series {
+        diesel::insert_into(books_series_link::table)
+            .values((
+                books_series_link::book.eq(book_id),
+                books_series_link::series.eq(series),
+                books_series_link::series_index.eq(series_index),
+            ))
+            .execute(conn)?;
+    }
+    for tag in &links.tags {
+        diesel::insert_into(books_tags_link::table)
+            .values((
+                books_tags_link::book.eq(book_id),
+                books_tags_link::tag.eq(tag),
+            ))
+            .execute(conn)?;
+    }
+    for (user_id, tag) in &links.user_tags {
+        diesel::insert_into(books_user_tags_link::table)
+            .values((
+                books_user_tags_link::user_id.eq(user_id),
+                books_user_tags_link::book.eq(book_id),
+                books_user_tags_link::tag.eq(tag),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// Insert comments, custom fields, files, identifiers and ratings, and map
+/// calibre books to this book again.
+fn restore_book_data(
+    conn: &mut PgConnection,
+    book_id: i32,
+    links: &BookLinks,
+) -> Result<(), Error> {
+    use crate::schema::{comments, custom_field_values, files, identifiers, import_books, ratings};
+
+    for text in &links.comments {
+        diesel::insert_into(comments::table)
+            .values((comments::book.eq(book_id), comments::text.eq(text)))
+            .execute(conn)?;
+    }
+    for (field, text_value, int_value, float_value, bool_value, date_value) in &links.custom_fields
+    {
+        diesel::insert_into(custom_field_values::table)
+            .values((
+                custom_field_values::book.eq(book_id),
+                custom_field_values::field.eq(field),
+                custom_field_values::text_value.eq(text_value),
+                custom_field_values::int_value.eq(int_value),
+                custom_field_values::float_value.eq(float_value),
+                custom_field_values::bool_value.eq(bool_value),
+                custom_field_values::date_value.eq(date_value),
+            ))
+            .execute(conn)?;
+    }
+    for (index, (format, size, name)) in links.files.iter().enumerate() {
+        let sha256 = links.file_checksums.get(index).cloned().flatten();
+        diesel::insert_into(files::table)
+            .values((
+                files::book.eq(book_id),
+                files::format.eq(format),
+                files::size.eq(size),
+                files::name.eq(name),
+                files::sha256.eq(sha256),
+            ))
+            .execute(conn)?;
+    }
+    for (scheme, value, url) in &links.identifiers {
+        diesel::insert_into(identifiers::table)
+            .values((
+                identifiers::book.eq(book_id),
+                identifiers::scheme.eq(scheme),
+                identifiers::value.eq(value),
+                identifiers::url.eq(url),
+            ))
+            .execute(conn)?;
+    }
+    for rating in &links.ratings {
+        diesel::insert_into(ratings::table)
+            .values((ratings::book.eq(book_id), ratings::rating.eq(rating)))
+            .execute(conn)?;
+    }
+    // `book` column is set to null by foreign key after book is deleted.
+    diesel::update(
+        import_books::table
+            .filter(import_books::id.eq_any(&links.import_books))
+            .filter(import_books::book.is_null()),
+    )
+    .set(import_books::book.eq(book_id))
+    .execute(conn)?;
+    Ok(())
+}
+
+/// Insert book record and rows referring to it again.
+pub fn restore_book_links(
+    conn: &mut PgConnection,
+    book: &Book,
+    links: &BookLinks,
+) -> Result<(), Error> {
+    use crate::schema::books;
+
+    diesel::insert_into(books::table)
+        .values(book)
+        .execute(conn)?;
+    restore_link_tables(conn, book.id, links)?;
+    restore_book_data(conn, book.id, links)
+}
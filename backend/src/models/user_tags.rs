@@ -5,12 +5,13 @@
 use diesel::{ExpressionMethods, Insertable, JoinOnDsl, PgConnection, QueryDsl, RunQueryDsl};
 use serde::Deserialize;
 use shared::general_query::GeneralOrder;
-use shared::page::{Page, USER_TAGS_EACH_PAGE};
+use shared::page::Page;
 use shared::recursive_query::RecursiveQuery;
 use shared::user_tags::{UserTag, UserTagAndBook, UserTagAndBookList};
 
 use crate::error::Error;
 use crate::schema::user_tags;
+use crate::settings::get_settings;
 
 #[derive(Debug, Clone, Deserialize, Insertable)]
 #[diesel(table_name = user_tags)]
@@ -48,7 +49,8 @@ pub fn get_tags(
 ) -> Result<UserTagAndBookList, Error> {
     use crate::schema::books_user_tags_link;
 
-    let offset = query.backend_page_id() * USER_TAGS_EACH_PAGE;
+    let each_page = get_settings().page_sizes.user_tags;
+    let offset = query.backend_page_id() * each_page;
 
     // TODO(Shaohua): Get child count.
     let count_query =
@@ -64,7 +66,7 @@ pub fn get_tags(
             count_query.clone(),
         ))
         .filter(user_tags::parent.eq(query.parent))
-        .limit(USER_TAGS_EACH_PAGE)
+        .limit(each_page)
         .offset(offset);
 
     let list = match query.order {
@@ -98,7 +100,7 @@ pub fn get_tags(
     Ok(UserTagAndBookList {
         page: Page {
             page_num: query.frontend_page_id(),
-            each_page: USER_TAGS_EACH_PAGE,
+            each_page,
             total,
         },
         list,
@@ -125,3 +127,17 @@ pub fn delete_by_id(conn: &mut PgConnection, tag_id: i32, user_id: i32) -> Resul
     .execute(conn)?;
     Ok(())
 }
+
+/// Count tags in `tag_ids` which belong to user.
+pub fn count_tags_of_user(
+    conn: &mut PgConnection,
+    user_id: i32,
+    tag_ids: &[i32],
+) -> Result<usize, Error> {
+    let count = user_tags::table
+        .filter(user_tags::user_id.eq(user_id))
+        .filter(user_tags::id.eq_any(tag_ids))
+        .count()
+        .first::<i64>(conn)?;
+    Ok(usize::try_from(count).unwrap_or_default())
+}
@@ -7,16 +7,22 @@ use actix_web_httpauth::middleware::HttpAuthentication;
 
 use crate::db;
 use crate::error::Error;
-use crate::views::auth::{admin_guard, auth_validator};
+use crate::import::checksums::start_auto_verify;
+use crate::import::jobs::ImportJobs;
+use crate::import::trash::start_auto_purge;
+use crate::migrations;
+use crate::settings::{get_library_path, get_settings};
+use crate::views::auth::{admin_guard, auth_validator, cookie_auth_validator};
 use crate::views::{
-    advanced_search, authors, books, categories, comments, discover, download_history,
-    file_formats, files, images, publishers, ratings, series, simple_search, tags, user_tags,
-    users,
+    advanced_search, authors, books, categories, comments, covers, discover, download_history,
+    file_formats, files, fsck, images, import_jobs, publishers, ratings, saved_searches, series,
+    simple_search, suggest, tags, trash, user_tags, users,
 };
 
 #[allow(clippy::too_many_lines)]
 fn scoped_config(cfg: &mut web::ServiceConfig) {
     let auth = HttpAuthentication::bearer(auth_validator);
+    let cookie_auth = HttpAuthentication::with_fn(cookie_auth_validator);
 
     cfg
         // For /api/author
@@ -43,11 +49,51 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
                 .route(web::get().to(books::get_books))
                 .route(web::post().guard(admin_guard()).to(books::add_book)),
         )
+        .service(
+            web::resource("/book/upload")
+                .wrap(auth.clone())
+                .route(web::post().guard(admin_guard()).to(books::upload_book)),
+        )
         .service(
             web::resource("/book/{book_id}")
                 .wrap(auth.clone())
                 .route(web::get().to(books::get_book_detail))
-                .route(web::put().guard(admin_guard()).to(books::update_book)),
+                .route(web::put().guard(admin_guard()).to(books::update_book))
+                .route(web::delete().guard(admin_guard()).to(trash::delete_book)),
+        )
+        .service(
+            web::resource("/book/{book_id}/metadata")
+                .wrap(auth.clone())
+                .route(
+                    web::put()
+                        .guard(admin_guard())
+                        .to(books::update_book_metadata),
+                ),
+        )
+        .service(
+            web::resource("/book/{book_id}/cover")
+                .wrap(auth.clone())
+                .route(web::put().guard(admin_guard()).to(covers::upload_cover)),
+        )
+        .service(
+            web::resource("/book/{book_id}/cover/extract")
+                .wrap(auth.clone())
+                .route(web::post().guard(admin_guard()).to(covers::extract_cover)),
+        )
+        .service(
+            web::resource("/book/{book_id}/file")
+                .wrap(auth.clone())
+                .route(web::post().guard(admin_guard()).to(files::add_book_file)),
+        )
+        .service(
+            web::resource("/book/{book_id}/file/{file_id}")
+                .wrap(auth.clone())
+                .route(web::put().guard(admin_guard()).to(files::replace_book_file))
+                .route(
+                    web::delete()
+                        .guard(admin_guard())
+                        .to(files::delete_book_file),
+                ),
         )
         // For /api/categories
         .service(
@@ -110,6 +156,13 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
         // For /api/file
         // Note that authentication is checked in callback explicitly.
         .service(web::resource("/file").route(web::get().to(files::get_file_by_path)))
+        .service(
+            web::resource("/file/duplicates").wrap(auth.clone()).route(
+                web::get()
+                    .guard(admin_guard())
+                    .to(files::get_duplicate_files),
+            ),
+        )
         // For /api/formats
         .service(
             web::resource("/format")
@@ -126,8 +179,49 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
                 .wrap(auth.clone())
                 .route(web::get().to(file_formats::get_books_by_file_format)),
         )
+        // For /api/fsck
+        .service(
+            web::resource("/fsck")
+                .wrap(auth.clone())
+                .route(
+                    web::get()
+                        .guard(admin_guard())
+                        .to(fsck::check_library_integrity),
+                )
+                .route(
+                    web::post()
+                        .guard(admin_guard())
+                        .to(fsck::fix_library_integrity),
+                ),
+        )
         // For /api/image
         .service(web::resource("/image").route(web::get().to(images::get_image_by_path)))
+        // For /api/import
+        .service(
+            web::resource("/import")
+                .wrap(auth.clone())
+                .route(web::get().guard(admin_guard()).to(import_jobs::get_jobs))
+                .route(web::post().guard(admin_guard()).to(import_jobs::add_job)),
+        )
+        .service(
+            web::resource("/import/{job_id}")
+                .wrap(auth.clone())
+                .route(web::get().guard(admin_guard()).to(import_jobs::get_job)),
+        )
+        .service(
+            web::resource("/import/{job_id}/cancel")
+                .wrap(auth.clone())
+                .route(web::post().guard(admin_guard()).to(import_jobs::cancel_job)),
+        )
+        .service(
+            web::resource("/import/{job_id}/events")
+                .wrap(cookie_auth)
+                .route(
+                    web::get()
+                        .guard(admin_guard())
+                        .to(import_jobs::get_job_events),
+                ),
+        )
         // For /api/publisher
         .service(
             web::resource("/publisher")
@@ -242,6 +336,22 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
                 .wrap(auth.clone())
                 .route(web::delete().guard(admin_guard()).to(tags::delete_book)),
         )
+        // For /api/trash
+        .service(
+            web::resource("/trash")
+                .wrap(auth.clone())
+                .route(web::get().guard(admin_guard()).to(trash::get_trash_books)),
+        )
+        .service(
+            web::resource("/trash/{book_id}")
+                .wrap(auth.clone())
+                .route(web::delete().guard(admin_guard()).to(trash::purge_book)),
+        )
+        .service(
+            web::resource("/trash/{book_id}/restore")
+                .wrap(auth.clone())
+                .route(web::post().guard(admin_guard()).to(trash::restore_book)),
+        )
         // For /api/user-tag
         .service(
             web::resource("/user-tag")
@@ -267,6 +377,25 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
                 .wrap(auth.clone())
                 .route(web::delete().to(user_tags::delete_book)),
         )
+        // For /api/saved-search
+        .service(
+            web::resource("/saved-search")
+                .wrap(auth.clone())
+                .route(web::get().to(saved_searches::get_searches))
+                .route(web::post().to(saved_searches::add_search)),
+        )
+        .service(
+            web::resource("/saved-search/{search_id}")
+                .wrap(auth.clone())
+                .route(web::get().to(saved_searches::get_search))
+                .route(web::put().to(saved_searches::update_search))
+                .route(web::delete().to(saved_searches::delete_search)),
+        )
+        .service(
+            web::resource("/saved-search/{search_id}/book")
+                .wrap(auth.clone())
+                .route(web::get().to(saved_searches::get_books)),
+        )
         // For /api/search
         .service(
             web::resource("/search/books")
@@ -279,6 +408,12 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
                 .wrap(auth.clone())
                 .route(web::get().to(advanced_search::get_books_by_advanced_search)),
         )
+        // For /api/suggest
+        .service(
+            web::resource("/suggest")
+                .wrap(auth.clone())
+                .route(web::get().to(suggest::get_suggestions)),
+        )
         // For /api/user
         .route("/user/login", web::post().to(users::login))
         .service(
@@ -302,21 +437,39 @@ fn scoped_config(cfg: &mut web::ServiceConfig) {
 pub async fn run() -> Result<(), Error> {
     let pool = db::get_connection_pool()?;
     {
-        log::info!("Initialize database tables");
+        log::info!("Check database schema version");
         let mut conn = pool.get()?;
-        db::create_table_schema(&mut conn)?;
+        migrations::check_schema_version(&mut conn)?;
     }
 
-    let server = HttpServer::new(move || {
+    let library_path = get_library_path()?;
+    let settings = get_settings();
+    start_auto_purge(pool.clone(), library_path.clone(), settings.trash.keep_days);
+    start_auto_verify(
+        pool.clone(),
+        library_path.clone(),
+        settings.checksum.verify_interval_days,
+    );
+    let import_jobs = web::Data::new(ImportJobs::new(pool.clone(), library_path)?);
+
+    let mut server = HttpServer::new(move || {
         App::new()
             .wrap(middleware::Logger::default())
             .app_data(web::Data::new(pool.clone()))
+            .app_data(import_jobs.clone())
             .service(web::scope("/api").configure(scoped_config))
     });
+    if settings.server.workers > 0 {
+        server = server.workers(settings.server.workers);
+    }
+    for addr in &settings.server.listen {
+        log::info!("Listen on {addr}");
+        server = server.bind(addr)?;
+    }
+    if let Some(unix_socket) = &settings.server.unix_socket {
+        log::info!("Listen on {}", unix_socket.display());
+        server = server.bind_uds(unix_socket)?;
+    }
 
-    server
-        .bind(("0.0.0.0", 3000))?
-        .run()
-        .await
-        .map_err(Into::into)
+    server.run().await.map_err(Into::into)
 }
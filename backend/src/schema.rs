@@ -21,6 +21,7 @@ diesel::table! {
         pubdate -> Nullable<Timestamp>,
         created -> Timestamp,
         last_modified -> Timestamp,
+        title_sort -> Text,
     }
 }
 
@@ -67,6 +68,7 @@ diesel::table! {
         book -> Int4,
         series -> Int4,
         created -> Timestamp,
+        series_index -> Float4,
     }
 }
 
@@ -113,6 +115,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    custom_field_values (id) {
+        id -> Int4,
+        book -> Int4,
+        field -> Int4,
+        text_value -> Nullable<Text>,
+        int_value -> Nullable<Int8>,
+        float_value -> Nullable<Float8>,
+        bool_value -> Nullable<Bool>,
+        date_value -> Nullable<Timestamp>,
+        created -> Timestamp,
+    }
+}
+
+diesel::table! {
+    custom_fields (id) {
+        id -> Int4,
+        label -> Text,
+        name -> Text,
+        datatype -> Text,
+        is_multiple -> Bool,
+        display -> Text,
+        created -> Timestamp,
+        last_modified -> Timestamp,
+    }
+}
+
 diesel::table! {
     download_history (id) {
         id -> Int4,
@@ -132,6 +161,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    file_logs (id) {
+        id -> Int4,
+        book -> Int4,
+        file -> Int4,
+        format -> Int4,
+        action -> Text,
+        old_size -> Nullable<Int4>,
+        new_size -> Nullable<Int4>,
+        user_id -> Int4,
+        created -> Timestamp,
+    }
+}
+
 diesel::table! {
     files (id) {
         id -> Int4,
@@ -141,6 +184,7 @@ diesel::table! {
         name -> Text,
         created -> Timestamp,
         last_modified -> Timestamp,
+        sha256 -> Nullable<Text>,
     }
 }
 
@@ -175,6 +219,28 @@ diesel::table! {
         ok -> Bool,
         book -> Nullable<Int4>,
         created -> Timestamp,
+        skip_reason -> Nullable<Text>,
+        error_kind -> Nullable<Text>,
+        error_message -> Nullable<Text>,
+        calibre_last_modified -> Nullable<Timestamp>,
+        synced_metadata -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    import_jobs (id) {
+        id -> Int4,
+        kind -> Int4,
+        path -> Text,
+        request -> Text,
+        library -> Nullable<Int4>,
+        status -> Int4,
+        total -> Int8,
+        done -> Int8,
+        failed -> Int8,
+        message -> Nullable<Text>,
+        created -> Timestamp,
+        last_modified -> Timestamp,
     }
 }
 
@@ -189,6 +255,21 @@ diesel::table! {
         worker_pid -> Nullable<Int4>,
         created -> Timestamp,
         last_modified -> Timestamp,
+        last_synced -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    import_logs (id) {
+        id -> Int4,
+        path -> Text,
+        moved_to -> Nullable<Text>,
+        ok -> Bool,
+        book -> Nullable<Int4>,
+        skip_reason -> Nullable<Text>,
+        error_kind -> Nullable<Text>,
+        error_message -> Nullable<Text>,
+        created -> Timestamp,
     }
 }
 
@@ -231,6 +312,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    saved_searches (id) {
+        id -> Int4,
+        user_id -> Int4,
+        name -> Text,
+        query -> Text,
+        created -> Timestamp,
+        last_modified -> Timestamp,
+    }
+}
+
+diesel::table! {
+    schema_version (version) {
+        version -> Int4,
+        name -> Text,
+        applied -> Timestamp,
+    }
+}
+
 diesel::table! {
     series (id) {
         id -> Int4,
@@ -251,6 +351,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    trash_books (id) {
+        id -> Int4,
+        book -> Int4,
+        title -> Text,
+        path -> Text,
+        record -> Text,
+        links -> Text,
+        user_id -> Int4,
+        created -> Timestamp,
+    }
+}
+
 diesel::table! {
     user_tags (id) {
         id -> Int4,
@@ -290,19 +403,27 @@ diesel::allow_tables_to_appear_in_same_query!(
     books_user_tags_link,
     categories,
     comments,
+    custom_field_values,
+    custom_fields,
     download_history,
     file_formats,
+    file_logs,
     files,
     identifier_types,
     identifiers,
     import_books,
+    import_jobs,
     import_libraries,
+    import_logs,
     languages,
     publishers,
     ratings,
     reading_history,
+    saved_searches,
+    schema_version,
     series,
     tags,
+    trash_books,
     user_tags,
     users,
 );
@@ -2,20 +2,23 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use shared::advanced_search::AdvancedSearchQuery;
 
 use crate::db::DbPool;
 use crate::error::Error;
 use crate::models::advanced_search;
+use crate::views::auth::get_claims_from_auth;
 
 pub async fn get_books_by_advanced_search(
     pool: web::Data<DbPool>,
     query: web::Query<AdvancedSearchQuery>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
     let resp = web::block(move || {
         let mut conn = pool.get()?;
-        advanced_search::get_books_by_advanced_search(&mut conn, &query)
+        advanced_search::get_books_by_advanced_search(&mut conn, &query, user_id)
     })
     .await??;
     Ok(HttpResponse::Ok().json(resp))
@@ -3,21 +3,21 @@
 // that can be found in the LICENSE file.
 
 use actix_web::cookie::time::OffsetDateTime;
-use actix_web::dev::ServiceRequest;
+use actix_web::dev::{Payload, ServiceRequest};
 use actix_web::guard::{Guard, GuardContext};
-use actix_web::HttpRequest;
+use actix_web::{FromRequest, HttpRequest};
 use actix_web_grants::authorities::{AttachAuthorities, AuthDetails};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use shared::users::UserRole;
+use std::future::{ready, Ready};
 
 use crate::error::{Error, ErrorKind};
-use crate::settings::get_jwt_secret;
+use crate::settings::{get_jwt_secret, get_settings};
 
 pub const TOKEN_NAME: &str = "Token";
-const JWT_EXPIRATION_HOURS: i64 = 24 * 3;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct UserPermissions {
@@ -35,13 +35,22 @@ pub struct Claims {
 }
 
 impl Claims {
-    pub fn new(permission: &UserPermissions) -> Self {
-        Self {
+    pub fn new(permission: &UserPermissions) -> Result<Self, Error> {
+        let expiration_hours = get_settings().jwt_expiration_hours;
+        let exp = Duration::try_hours(expiration_hours)
+            .and_then(|duration| Utc::now().checked_add_signed(duration))
+            .ok_or_else(|| {
+                Error::from_string(
+                    ErrorKind::ConfigError,
+                    format!("Invalid jwt_expiration_hours: {expiration_hours}"),
+                )
+            })?;
+        Ok(Self {
             id: permission.id,
             name: permission.name.clone(),
             role: permission.role,
-            exp: (Utc::now() + Duration::try_hours(JWT_EXPIRATION_HOURS).unwrap()).timestamp(),
-        }
+            exp: exp.timestamp(),
+        })
     }
 
     #[must_use]
@@ -95,12 +104,12 @@ impl Claims {
     }
 }
 
-pub async fn auth_validator(
+fn attach_permission(
     req: ServiceRequest,
-    credentials: BearerAuth,
+    token: &str,
 ) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
     // We just get permissions from JWT
-    match Claims::decode(credentials.token()) {
+    match Claims::decode(token) {
         Ok(claims) => {
             req.attach(vec![claims.permission()]);
             Ok(req)
@@ -109,15 +118,45 @@ pub async fn auth_validator(
     }
 }
 
+pub async fn auth_validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
+    attach_permission(req, credentials.token())
+}
+
+/// Token read from `Token` cookie.
+///
+/// `EventSource` in browser can not set http headers, so that routes of
+/// server-sent events are authenticated with cookie.
+pub struct CookieAuth(String);
+
+impl FromRequest for CookieAuth {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.cookie(TOKEN_NAME).map_or_else(
+            || Err(Error::new(ErrorKind::InvalidToken, "invalid token")),
+            |cookie| Ok(Self(cookie.value().to_owned())),
+        ))
+    }
+}
+
+pub async fn cookie_auth_validator(
+    req: ServiceRequest,
+    credentials: CookieAuth,
+) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
+    attach_permission(req, &credentials.0)
+}
+
 pub struct UserRoleGuard(UserRole);
 
 impl Guard for UserRoleGuard {
     fn check(&self, ctx: &GuardContext<'_>) -> bool {
         ctx.req_data()
             .get::<AuthDetails<UserPermissions>>()
-            .map_or(false, |user_perm| {
-                user_perm.authorities.iter().any(|perm| perm.role == self.0)
-            })
+            .is_some_and(|user_perm| user_perm.authorities.iter().any(|perm| perm.role == self.0))
     }
 }
 
@@ -2,14 +2,29 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+use actix_multipart::{Field, Multipart};
 use actix_web::{web, HttpRequest, HttpResponse};
-use shared::books::BookUpdateReq;
+use cip::formats::metadata::{normalize_isbn, read_metadata, FileMetadata};
+use futures::TryStreamExt;
+use shared::books::{BookUpdateReq, UploadBookReq, UploadBookResp};
+use shared::books_meta::BookMetadataUpdateReq;
 use shared::books_query::GetBooksQuery;
+use std::path::PathBuf;
 
 use crate::db::DbPool;
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
+use crate::import::import_folder::{get_file_format, import_files};
+use crate::import::options::ImportBookOptions;
 use crate::models::{books, books_meta};
+use crate::settings::get_library_path;
 use crate::views::auth::get_claims_from_auth;
+use crate::views::upload::{read_field_bytes, save_file_field, UploadDir, FILE_FIELD};
+
+/// Name of multipart field which contains metadata json.
+const UPLOAD_METADATA_FIELD: &str = "metadata";
+
+/// Max size of metadata json.
+const MAX_METADATA_SIZE: usize = 64 * 1024;
 
 pub async fn add_book(
     pool: web::Data<DbPool>,
@@ -64,3 +79,143 @@ pub async fn update_book(
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub async fn update_book_metadata(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+    req: web::Json<BookMetadataUpdateReq>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let claims = get_claims_from_auth(&http_req)?;
+    let user_id = claims.id();
+    let resp_book = web::block(move || {
+        let mut conn = pool.get()?;
+        books_meta::update_book_metadata(&mut conn, user_id, book_id.into_inner(), &req)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp_book))
+}
+
+#[derive(Debug, Default)]
+struct UploadForm {
+    req: UploadBookReq,
+    files: Vec<PathBuf>,
+}
+
+async fn read_metadata_field(field: &mut Field) -> Result<UploadBookReq, Error> {
+    let buf = read_field_bytes(field, MAX_METADATA_SIZE).await?;
+    serde_json::from_slice(&buf).map_err(|err| {
+        Error::from_string(
+            ErrorKind::RequestFormError,
+            format!("Invalid metadata of uploaded book, err: {err}"),
+        )
+    })
+}
+
+/// Read metadata and save ebook files in multipart form to `upload_dir`.
+async fn read_upload_form(
+    payload: &mut Multipart,
+    upload_dir: &UploadDir,
+) -> Result<UploadForm, Error> {
+    let mut form = UploadForm::default();
+    while let Some(mut field) = payload.try_next().await? {
+        match field.name() {
+            Some(UPLOAD_METADATA_FIELD) => {
+                form.req = read_metadata_field(&mut field).await?;
+            }
+            Some(FILE_FIELD) => {
+                let path = upload_dir.file_path(form.files.len(), &field)?;
+                let format = get_file_format(&path);
+                if form
+                    .files
+                    .iter()
+                    .any(|file| get_file_format(file) == format)
+                {
+                    return Err(Error::from_string(
+                        ErrorKind::RequestFormError,
+                        format!("Duplicated file format: {}", path.display()),
+                    ));
+                }
+                save_file_field(&mut field, path.clone()).await?;
+                form.files.push(path);
+            }
+            name => {
+                return Err(Error::from_string(
+                    ErrorKind::RequestFormError,
+                    format!("Unknown field in multipart form: {name:?}"),
+                ));
+            }
+        }
+    }
+
+    if form.files.is_empty() {
+        return Err(Error::new(
+            ErrorKind::RequestFormError,
+            "No ebook file is uploaded",
+        ));
+    }
+    Ok(form)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Merge metadata in request with metadata embedded in uploaded files.
+fn get_upload_metadata(form: UploadForm) -> (FileMetadata, Vec<PathBuf>) {
+    let req = form.req;
+    let mut metadata = FileMetadata {
+        title: non_empty(req.title),
+        authors: req
+            .authors
+            .iter()
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .collect(),
+        publisher: non_empty(req.publisher),
+        isbn: req.isbn.as_deref().and_then(normalize_isbn),
+        language: non_empty(req.language),
+        cover: None,
+    };
+    for path in &form.files {
+        match read_metadata(path) {
+            Ok(file_metadata) => metadata.fill_missing(file_metadata),
+            Err(err) => log::warn!(
+                "Failed to read metadata of {}, err: {err:?}",
+                path.display()
+            ),
+        }
+    }
+    (metadata, form.files)
+}
+
+/// Add a new book with uploaded ebook files.
+///
+/// Request is a multipart form, with optional `metadata` field in json and
+/// one or more `file` fields, each of them shall be in different format.
+pub async fn upload_book(
+    pool: web::Data<DbPool>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, Error> {
+    let upload_dir = UploadDir::new()?;
+    let form = read_upload_form(&mut payload, &upload_dir).await?;
+
+    let book = web::block(move || {
+        let (metadata, files) = get_upload_metadata(form);
+        let library_path = get_library_path()?;
+        let options = ImportBookOptions {
+            allow_duplication: true,
+            ..ImportBookOptions::default()
+        };
+        let mut conn = pool.get()?;
+        import_files(&mut conn, &library_path, &files, &metadata, &options)
+    })
+    .await??;
+    log::info!("Book {} is uploaded: {}", book.id, book.title);
+    Ok(HttpResponse::Ok().json(UploadBookResp {
+        id: book.id,
+        title: book.title,
+    }))
+}
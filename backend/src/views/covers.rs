@@ -0,0 +1,68 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use futures::TryStreamExt;
+
+use crate::db::DbPool;
+use crate::error::{Error, ErrorKind};
+use crate::import::covers;
+use crate::models::books::get_book_by_id;
+use crate::settings::get_library_path;
+use crate::views::upload::read_field_bytes;
+
+/// Name of multipart field which contains cover image.
+const COVER_FIELD: &str = "cover";
+
+/// Max size of uploaded cover image.
+const MAX_COVER_SIZE: usize = 20 * 1024 * 1024;
+
+/// Upload or replace cover of book.
+///
+/// Request is a multipart form with one `cover` field, in jpeg, png or webp format.
+pub async fn upload_cover(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, Error> {
+    let mut image = None;
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() != Some(COVER_FIELD) {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Unknown field in multipart form: {:?}", field.name()),
+            ));
+        }
+        image = Some(read_field_bytes(&mut field, MAX_COVER_SIZE).await?);
+    }
+    let image =
+        image.ok_or_else(|| Error::new(ErrorKind::RequestFormError, "No cover is uploaded"))?;
+
+    let book = web::block(move || {
+        let library_path = get_library_path()?;
+        let book_id = book_id.into_inner();
+        let mut conn = pool.get()?;
+        covers::set_book_cover(&mut conn, &library_path, book_id, &image)?;
+        get_book_by_id(&mut conn, book_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(book))
+}
+
+/// Use cover embedded in epub, mobi or pdf files of book.
+pub async fn extract_cover(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let book = web::block(move || {
+        let library_path = get_library_path()?;
+        let book_id = book_id.into_inner();
+        let mut conn = pool.get()?;
+        covers::extract_book_cover(&mut conn, &library_path, book_id)?;
+        get_book_by_id(&mut conn, book_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(book))
+}
@@ -3,20 +3,25 @@
 // that can be found in the LICENSE file.
 
 use actix_files::NamedFile;
-use actix_web::{web, HttpRequest};
+use actix_multipart::Multipart;
+use actix_web::http::header::{self, EntityTag, HeaderName, HeaderValue};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use data_encoding::{BASE64, HEXLOWER};
 use shared::files::FileQuery;
 
 use crate::db::DbPool;
 use crate::error::{Error, ErrorKind};
+use crate::import::{book_files, checksums};
 use crate::models::{download_history, files};
 use crate::settings;
-use crate::views::auth::get_claims_from_cookie;
+use crate::views::auth::{get_claims_from_auth, get_claims_from_cookie};
+use crate::views::upload::{read_single_file, UploadDir};
 
 pub async fn get_file_by_path(
     pool: web::Data<DbPool>,
     req: HttpRequest,
     query: web::Query<FileQuery>,
-) -> Result<NamedFile, Error> {
+) -> Result<HttpResponse, Error> {
     log::info!("filepath: {:?}", query.path);
 
     // 1. check auth token
@@ -26,9 +31,14 @@ pub async fn get_file_by_path(
     // 2. check book file exists
     let book_id = query.book;
     let file_id = query.file;
-    let book_file_path = {
+    let (book_file_path, sha256) = {
         let mut conn = pool.get()?;
-        web::block(move || files::get_book_file_path(&mut conn, book_id, file_id)).await??
+        web::block(move || {
+            let path = files::get_book_file_path(&mut conn, book_id, file_id)?;
+            let sha256 = files::get_file_checksum(&mut conn, file_id)?;
+            Ok::<_, Error>((path, sha256))
+        })
+        .await??
     };
     if book_file_path != query.path {
         return Err(Error::from_string(
@@ -37,7 +47,23 @@ pub async fn get_file_by_path(
         ));
     }
 
-    // 3. add download history record
+    // 3. file is not modified if its checksum matches the etag of client
+    let etag = sha256
+        .as_ref()
+        .map(|sha256| EntityTag::new_strong(sha256.clone()));
+    if let (Some(etag), Some(if_none_match)) = (&etag, req.get_header::<header::IfNoneMatch>()) {
+        let matched = match if_none_match {
+            header::IfNoneMatch::Any => true,
+            header::IfNoneMatch::Items(tags) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        };
+        if matched {
+            return Ok(HttpResponse::NotModified()
+                .insert_header(header::ETag(etag.clone()))
+                .finish());
+        }
+    }
+
+    // 4. add download history record
     {
         let mut conn = pool.get()?;
         web::block(move || {
@@ -51,7 +77,7 @@ pub async fn get_file_by_path(
         .await??;
     }
 
-    // 4. return real file path
+    // 5. return real file path
     let root_dir = settings::get_library_root_dir()?;
     let filepath = root_dir.join(&query.path);
     log::info!("real path: {filepath:?}");
@@ -61,6 +87,112 @@ pub async fn get_file_by_path(
             format!("Invalid filepath: {query:?}"),
         ));
     }
-    let file = NamedFile::open(filepath)?;
-    Ok(file.use_last_modified(true))
+    let file = NamedFile::open(filepath)?.use_last_modified(true);
+    let (Some(sha256), Some(etag)) = (sha256, etag) else {
+        return Ok(file.into_response(&req));
+    };
+
+    // 6. use checksum as etag, and send it in digest header
+    let mut resp = file.use_etag(false).into_response(&req);
+    let digest = BASE64.encode(&HEXLOWER.decode(sha256.as_bytes())?);
+    let headers = resp.headers_mut();
+    headers.insert(header::ETAG, header_value(&etag.to_string())?);
+    headers.insert(
+        HeaderName::from_static("digest"),
+        header_value(&format!("sha-256={digest}"))?,
+    );
+    Ok(resp)
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, Error> {
+    HeaderValue::from_str(value).map_err(|err| {
+        Error::from_string(
+            ErrorKind::IoError,
+            format!("Invalid header value: {value}, err: {err:?}"),
+        )
+    })
+}
+
+/// Get files with the same content which are attached to different books.
+pub async fn get_duplicate_files(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        checksums::get_duplicate_files(&mut conn)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Add uploaded file as a new format of book.
+pub async fn add_book_file(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let claims = get_claims_from_auth(&req)?;
+    let user_id = claims.id();
+    let upload_dir = UploadDir::new()?;
+    let path = read_single_file(&mut payload, &upload_dir).await?;
+
+    let file = web::block(move || {
+        let library_path = settings::get_library_path()?;
+        let mut conn = pool.get()?;
+        book_files::add_book_file(
+            &mut conn,
+            &library_path,
+            book_id.into_inner(),
+            &path,
+            user_id,
+        )
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(file))
+}
+
+/// Replace content of book file with uploaded file in the same format.
+pub async fn replace_book_file(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, i32)>,
+    mut payload: Multipart,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let claims = get_claims_from_auth(&req)?;
+    let user_id = claims.id();
+    let (book_id, file_id) = path.into_inner();
+    let upload_dir = UploadDir::new()?;
+    let src_path = read_single_file(&mut payload, &upload_dir).await?;
+
+    let file = web::block(move || {
+        let library_path = settings::get_library_path()?;
+        let mut conn = pool.get()?;
+        book_files::replace_book_file(
+            &mut conn,
+            &library_path,
+            book_id,
+            file_id,
+            &src_path,
+            user_id,
+        )
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(file))
+}
+
+pub async fn delete_book_file(
+    pool: web::Data<DbPool>,
+    path: web::Path<(i32, i32)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let claims = get_claims_from_auth(&req)?;
+    let user_id = claims.id();
+    let (book_id, file_id) = path.into_inner();
+
+    web::block(move || {
+        let library_path = settings::get_library_path()?;
+        let mut conn = pool.get()?;
+        book_files::delete_book_file(&mut conn, &library_path, book_id, file_id, user_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().finish())
 }
@@ -0,0 +1,30 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_web::{web, HttpResponse};
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::import::fsck::check_library;
+use crate::settings::get_library_path;
+
+async fn run_check(pool: web::Data<DbPool>, fix: bool) -> Result<HttpResponse, Error> {
+    let report = web::block(move || {
+        let library_path = get_library_path()?;
+        let mut conn = pool.get()?;
+        check_library(&mut conn, &library_path, fix)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Scan database and library, without changing anything.
+pub async fn check_library_integrity(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+    run_check(pool, false).await
+}
+
+/// Scan database and library, and repair safe cases.
+pub async fn fix_library_integrity(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+    run_check(pool, true).await
+}
@@ -18,7 +18,7 @@ pub async fn get_image_by_path(
     log::info!("filepath: {path:?}");
     let valid_extension = path
         .extension()
-        .map_or(false, |extension| extension == "webp" || extension == "jpg");
+        .is_some_and(|extension| extension == "webp" || extension == "jpg");
     if !valid_extension {
         return Err(Error::from_string(
             ErrorKind::IoError,
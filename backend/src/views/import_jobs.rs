@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{rt, web, HttpResponse};
+use shared::import::{ImportJob, NewImportJobReq};
+use std::time::Duration;
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::import::jobs::ImportJobs;
+
+/// Interval to send progress events.
+const EVENT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub async fn add_job(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+    req: web::Json<NewImportJobReq>,
+) -> Result<HttpResponse, Error> {
+    let job = web::block(move || {
+        let mut conn = pool.get()?;
+        import_jobs.add_job(&mut conn, req.into_inner())
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+pub async fn get_jobs(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+) -> Result<HttpResponse, Error> {
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        import_jobs.get_jobs(&mut conn)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+/// Read progress of job from memory, or from database if it is stopped.
+async fn read_job(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+    job_id: i32,
+) -> Result<ImportJob, Error> {
+    if let Some(job) = import_jobs.get_active_job(job_id) {
+        return Ok(job);
+    }
+    web::block(move || {
+        let mut conn = pool.get()?;
+        import_jobs.get_job(&mut conn, job_id)
+    })
+    .await?
+}
+
+pub async fn get_job(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+    job_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let job = read_job(pool, import_jobs, job_id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+pub async fn cancel_job(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+    job_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let job = web::block(move || {
+        let mut conn = pool.get()?;
+        import_jobs.cancel_job(&mut conn, job_id.into_inner())
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// Stream progress of job as server-sent events, until it is stopped.
+pub async fn get_job_events(
+    pool: web::Data<DbPool>,
+    import_jobs: web::Data<ImportJobs>,
+    job_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let job_id = job_id.into_inner();
+    read_job(pool.clone(), import_jobs.clone(), job_id).await?;
+
+    // State is (pool, import_jobs, is_first_event), and is None after job is stopped.
+    let init_state = Some((pool, import_jobs, true));
+    let stream = futures::stream::unfold(init_state, move |state| async move {
+        let (pool, import_jobs, is_first) = state?;
+        if !is_first {
+            rt::time::sleep(EVENT_INTERVAL).await;
+        }
+        let job = read_job(pool.clone(), import_jobs.clone(), job_id).await;
+        let event = job.and_then(|job| {
+            let data = serde_json::to_string(&job)?;
+            Ok((
+                job.status.is_done(),
+                Bytes::from(format!("data: {data}\n\n")),
+            ))
+        });
+        match event {
+            Ok((true, bytes)) => Some((Ok::<_, Error>(bytes), None)),
+            Ok((false, bytes)) => Some((Ok(bytes), Some((pool, import_jobs, false)))),
+            Err(err) => Some((Err(err), None)),
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "text/event-stream"))
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream))
+}
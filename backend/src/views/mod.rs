@@ -8,18 +8,25 @@ pub mod authors;
 pub mod books;
 pub mod categories;
 pub mod comments;
+pub mod covers;
 pub mod discover;
 pub mod download_history;
 pub mod file_formats;
 pub mod files;
+pub mod fsck;
 pub mod identifiers;
 pub mod images;
+pub mod import_jobs;
 pub mod languages;
 pub mod publishers;
 pub mod ratings;
 pub mod reading_history;
+pub mod saved_searches;
 pub mod series;
 pub mod simple_search;
+pub mod suggest;
 pub mod tags;
+pub mod trash;
+pub mod upload;
 pub mod user_tags;
 pub mod users;
@@ -78,9 +78,7 @@ pub async fn delete_publisher(
 ) -> Result<HttpResponse, Error> {
     web::block(move || {
         let mut conn = pool.get()?;
-        let publisher_id = publisher_id.into_inner();
-        books_publishers::delete_by_publisher_id(&mut conn, publisher_id)?;
-        publishers::delete_by_id(&mut conn, publisher_id)
+        publishers::delete_by_id(&mut conn, publisher_id.into_inner())
     })
     .await??;
     Ok(HttpResponse::Ok().finish())
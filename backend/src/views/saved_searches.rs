@@ -0,0 +1,96 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use shared::saved_searches::{GetSavedSearchBooksQuery, NewSavedSearch};
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::models::saved_searches;
+use crate::views::auth::get_claims_from_auth;
+
+pub async fn add_search(
+    pool: web::Data<DbPool>,
+    new_search: web::Json<NewSavedSearch>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::add_search(&mut conn, user_id, &new_search)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+pub async fn get_searches(
+    pool: web::Data<DbPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::get_searches(&mut conn, user_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+pub async fn get_search(
+    pool: web::Data<DbPool>,
+    search_id: web::Path<i32>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::get_search(&mut conn, search_id.into_inner(), user_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+pub async fn update_search(
+    pool: web::Data<DbPool>,
+    search_id: web::Path<i32>,
+    new_search: web::Json<NewSavedSearch>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::update_search(&mut conn, search_id.into_inner(), user_id, &new_search)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+pub async fn delete_search(
+    pool: web::Data<DbPool>,
+    search_id: web::Path<i32>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::delete_search(&mut conn, search_id.into_inner(), user_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn get_books(
+    pool: web::Data<DbPool>,
+    search_id: web::Path<i32>,
+    query: web::Query<GetSavedSearchBooksQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        saved_searches::get_books(&mut conn, search_id.into_inner(), user_id, &query)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
@@ -3,8 +3,8 @@
 // that can be found in the LICENSE file.
 
 use actix_web::{web, HttpResponse};
-use shared::books_query::GetBooksQuery;
 use shared::general_query::GeneralQuery;
+use shared::series::GetSeriesBooksQuery;
 
 use crate::db::DbPool;
 use crate::error::Error;
@@ -49,7 +49,7 @@ pub async fn get_series(
 pub async fn get_books_by_series(
     pool: web::Data<DbPool>,
     series_id: web::Path<i32>,
-    query: web::Query<GetBooksQuery>,
+    query: web::Query<GetSeriesBooksQuery>,
 ) -> Result<HttpResponse, Error> {
     let resp = web::block(move || {
         let mut conn = pool.get()?;
@@ -78,9 +78,7 @@ pub async fn delete_series(
 ) -> Result<HttpResponse, Error> {
     web::block(move || {
         let mut conn = pool.get()?;
-        let series_id = series_id.into_inner();
-        books_series::delete_by_series_id(&mut conn, series_id)?;
-        series::delete_by_id(&mut conn, series_id)
+        series::delete_by_id(&mut conn, series_id.into_inner())
     })
     .await??;
     Ok(HttpResponse::Ok().finish())
@@ -96,6 +94,7 @@ pub async fn add_book(
         let new_book_series = books_series::NewBookSeries {
             book: book_id.into_inner(),
             series: series_id.into_inner(),
+            series_index: 1.0,
         };
         books_series::add_book_series(&mut conn, &new_book_series)
     })
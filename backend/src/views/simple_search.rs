@@ -2,20 +2,23 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use shared::simple_search::SimpleSearchQuery;
 
 use crate::db::DbPool;
 use crate::error::Error;
 use crate::models::simple_search;
+use crate::views::auth::get_claims_from_auth;
 
 pub async fn get_books_by_simple_search(
     pool: web::Data<DbPool>,
     query: web::Query<SimpleSearchQuery>,
+    req: HttpRequest,
 ) -> Result<HttpResponse, Error> {
+    let user_id = get_claims_from_auth(&req)?.id();
     let resp = web::block(move || {
         let mut conn = pool.get()?;
-        simple_search::get_books_by_simple_search(&mut conn, &query)
+        simple_search::get_books_by_simple_search(&mut conn, &query, user_id)
     })
     .await??;
     Ok(HttpResponse::Ok().json(resp))
@@ -0,0 +1,22 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_web::{web, HttpResponse};
+use shared::suggest::SuggestQuery;
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::models::suggest;
+
+pub async fn get_suggestions(
+    pool: web::Data<DbPool>,
+    query: web::Query<SuggestQuery>,
+) -> Result<HttpResponse, Error> {
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        suggest::get_suggestions(&mut conn, &query.q)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
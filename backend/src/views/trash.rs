@@ -0,0 +1,65 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use crate::db::DbPool;
+use crate::error::Error;
+use crate::import::trash;
+use crate::models::trash_books;
+use crate::settings::get_library_path;
+use crate::views::auth::get_claims_from_auth;
+
+/// Move book to trash.
+pub async fn delete_book(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let claims = get_claims_from_auth(&req)?;
+    let user_id = claims.id();
+    web::block(move || {
+        let library_path = get_library_path()?;
+        let mut conn = pool.get()?;
+        trash::trash_book(&mut conn, &library_path, book_id.into_inner(), user_id)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn get_trash_books(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+    let resp = web::block(move || {
+        let mut conn = pool.get()?;
+        trash_books::get_trash_books(&mut conn)
+    })
+    .await??;
+    Ok(HttpResponse::Ok().json(resp))
+}
+
+pub async fn restore_book(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    web::block(move || {
+        let library_path = get_library_path()?;
+        let mut conn = pool.get()?;
+        trash::restore_book(&mut conn, &library_path, book_id.into_inner())
+    })
+    .await??;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Remove book in trash permanently.
+pub async fn purge_book(
+    pool: web::Data<DbPool>,
+    book_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    web::block(move || {
+        let library_path = get_library_path()?;
+        let mut conn = pool.get()?;
+        trash::purge_book(&mut conn, &library_path, book_id.into_inner())
+    })
+    .await??;
+    Ok(HttpResponse::Ok().finish())
+}
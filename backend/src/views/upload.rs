@@ -0,0 +1,134 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Save ebook files uploaded in multipart form.
+
+use actix_multipart::{Field, Multipart};
+use actix_web::web;
+use futures::TryStreamExt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::error::{Error, ErrorKind};
+use crate::import::import_folder::{is_supported_file, sanitize_file_name};
+use crate::settings::get_settings;
+
+/// Name of multipart fields which contain ebook files.
+pub const FILE_FIELD: &str = "file";
+
+/// Temporary directory of uploaded files, which is removed on drop.
+#[derive(Debug)]
+pub struct UploadDir {
+    path: PathBuf,
+}
+
+impl UploadDir {
+    pub fn new() -> Result<Self, Error> {
+        let path = std::env::temp_dir().join(format!("pengzu-upload-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// Get path to save the `index`th uploaded file.
+    ///
+    /// Each file is saved in its own directory to keep its original name.
+    pub fn file_path(&self, index: usize, field: &Field) -> Result<PathBuf, Error> {
+        let file_name = field
+            .content_disposition()
+            .and_then(|content_disposition| content_disposition.get_filename())
+            .map(sanitize_file_name)
+            .ok_or_else(|| Error::new(ErrorKind::RequestFormError, "File name is required"))?;
+        let path = self.path.join(index.to_string()).join(file_name);
+        if is_supported_file(&path) {
+            Ok(path)
+        } else {
+            Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Unsupported file format: {}", path.display()),
+            ))
+        }
+    }
+}
+
+impl Drop for UploadDir {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_dir_all(&self.path) {
+            log::warn!(
+                "Failed to remove upload dir {}, err: {err:?}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Read content of field into memory.
+///
+/// Returns error if it is larger than `max_size` bytes.
+pub async fn read_field_bytes(field: &mut Field, max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    while let Some(chunk) = field.try_next().await? {
+        if buf.len() + chunk.len() > max_size {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Field {:?} is larger than {max_size} bytes", field.name()),
+            ));
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(buf)
+}
+
+/// Save uploaded file to `path`.
+///
+/// Returns error if it is larger than `upload.max_file_size` setting.
+pub async fn save_file_field(field: &mut Field, path: PathBuf) -> Result<(), Error> {
+    let max_size = get_settings().upload.max_file_size;
+    let mut file = web::block(move || {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+        fs::File::create(path)
+    })
+    .await??;
+
+    let mut size: u64 = 0;
+    while let Some(chunk) = field.try_next().await? {
+        size += chunk.len() as u64;
+        if size > max_size {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Uploaded file is larger than {max_size} bytes"),
+            ));
+        }
+        file = web::block(move || file.write_all(&chunk).map(|()| file)).await??;
+    }
+    Ok(())
+}
+
+/// Save the only ebook file in multipart form to `upload_dir`.
+pub async fn read_single_file(
+    payload: &mut Multipart,
+    upload_dir: &UploadDir,
+) -> Result<PathBuf, Error> {
+    let mut file_path = None;
+    while let Some(mut field) = payload.try_next().await? {
+        if field.name() != Some(FILE_FIELD) {
+            return Err(Error::from_string(
+                ErrorKind::RequestFormError,
+                format!("Unknown field in multipart form: {:?}", field.name()),
+            ));
+        }
+        if file_path.is_some() {
+            return Err(Error::new(
+                ErrorKind::RequestFormError,
+                "Only one file can be uploaded",
+            ));
+        }
+        let path = upload_dir.file_path(0, &field)?;
+        save_file_field(&mut field, path.clone()).await?;
+        file_path = Some(path);
+    }
+    file_path.ok_or_else(|| Error::new(ErrorKind::RequestFormError, "No file is uploaded"))
+}
@@ -104,9 +104,7 @@ pub async fn delete_tag(
 
     web::block(move || {
         let mut conn = pool.get()?;
-        let tag_id = tag_id.into_inner();
-        books_user_tags::delete_by_tag_id(&mut conn, tag_id, user_id)?;
-        user_tags::delete_by_id(&mut conn, tag_id, user_id)
+        user_tags::delete_by_id(&mut conn, tag_id.into_inner(), user_id)
     })
     .await??;
     Ok(HttpResponse::Ok().finish())
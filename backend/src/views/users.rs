@@ -26,7 +26,7 @@ pub async fn login(
         name: user_info.name.clone(),
         role: user_info.role,
     };
-    let claims = Claims::new(&permission);
+    let claims = Claims::new(&permission)?;
     let token = claims.encode()?;
     let mut cookie = Cookie::new(TOKEN_NAME, &token);
     cookie.set_path("/");
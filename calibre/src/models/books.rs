@@ -25,6 +25,14 @@ pub struct CalibreBook {
     pub last_modified: NaiveDateTime,
 }
 
+pub fn get_book(conn: &mut SqliteConnection, book_id: i32) -> Result<CalibreBook, Error> {
+    use crate::schema::books::dsl::books;
+    books
+        .find(book_id)
+        .first::<CalibreBook>(conn)
+        .map_err(Into::into)
+}
+
 pub fn get_next_book(conn: &mut SqliteConnection, book_id: i32) -> Result<CalibreBook, Error> {
     use crate::schema::books::dsl::{books, id};
     books
@@ -34,6 +42,18 @@ pub fn get_next_book(conn: &mut SqliteConnection, book_id: i32) -> Result<Calibr
         .map_err(Into::into)
 }
 
+/// Get id and last modified time of all books.
+pub fn get_book_modified_times(
+    conn: &mut SqliteConnection,
+) -> Result<Vec<(i32, NaiveDateTime)>, Error> {
+    use crate::schema::books::dsl::{books, id, last_modified};
+    books
+        .select((id, last_modified))
+        .order(id.asc())
+        .load::<(i32, NaiveDateTime)>(conn)
+        .map_err(Into::into)
+}
+
 pub fn get_total_books(conn: &mut SqliteConnection) -> Result<i64, Error> {
     use crate::schema::books::dsl::books;
     books.count().get_result(conn).map_err(Into::into)
@@ -0,0 +1,39 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use diesel::{ExpressionMethods, QueryDsl, Queryable, RunQueryDsl, SqliteConnection};
+
+use crate::error::Error;
+
+/// Data stored by calibre plugins, `val` is a json string.
+#[derive(Debug, Queryable)]
+pub struct PluginData {
+    pub id: i32,
+    pub book: i32,
+    pub name: String,
+    pub val: String,
+}
+
+pub fn get_book_plugin_data(
+    conn: &mut SqliteConnection,
+    book_id: i32,
+) -> Result<Vec<PluginData>, Error> {
+    use crate::schema::books_plugin_data::dsl::{book, books_plugin_data, id};
+    books_plugin_data
+        .filter(book.eq(book_id))
+        .order(id.asc())
+        .load::<PluginData>(conn)
+        .map_err(Into::into)
+}
+
+/// Get names of all plugins which stored data in calibre library.
+pub fn get_plugin_names(conn: &mut SqliteConnection) -> Result<Vec<String>, Error> {
+    use crate::schema::books_plugin_data::dsl::{books_plugin_data, name};
+    books_plugin_data
+        .select(name)
+        .distinct()
+        .order(name.asc())
+        .load::<String>(conn)
+        .map_err(Into::into)
+}
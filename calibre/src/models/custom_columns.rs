@@ -0,0 +1,98 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use diesel::sql_types::{Integer, Text};
+use diesel::{
+    sql_query, ExpressionMethods, QueryDsl, Queryable, QueryableByName, RunQueryDsl,
+    SqliteConnection,
+};
+
+use crate::error::Error;
+
+/// Definition of a calibre custom column.
+///
+/// Values of column are stored in `custom_column_{id}` table, and if the column
+/// is `normalized`, books are linked to values via `books_custom_column_{id}_link` table.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Queryable)]
+pub struct CustomColumn {
+    pub id: i32,
+
+    /// Lookup name used in calibre, without `#` prefix.
+    pub label: String,
+
+    /// Display name.
+    pub name: String,
+
+    /// One of `text`, `comments`, `series`, `enumeration`, `int`, `float`,
+    /// `bool`, `datetime`, `rating` and `composite`.
+    pub datatype: String,
+    pub mark_for_delete: bool,
+    pub editable: bool,
+
+    /// Display options in json.
+    pub display: String,
+    pub is_multiple: bool,
+    pub normalized: bool,
+}
+
+impl CustomColumn {
+    /// Composite columns are computed from templates, and have no values stored.
+    #[must_use]
+    pub fn has_values(&self) -> bool {
+        self.datatype != "composite"
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct CustomColumnValue {
+    #[diesel(sql_type = Text)]
+    value: String,
+}
+
+/// Get definitions of custom columns, except deleted ones.
+pub fn get_custom_columns(conn: &mut SqliteConnection) -> Result<Vec<CustomColumn>, Error> {
+    use crate::schema::custom_columns::dsl::{custom_columns, id, mark_for_delete};
+    custom_columns
+        .filter(mark_for_delete.eq(false))
+        .order(id.asc())
+        .load::<CustomColumn>(conn)
+        .map_err(Into::into)
+}
+
+/// Get values of custom column for a book, converted to text.
+///
+/// Multiple values are returned in the order they were linked.
+pub fn get_custom_column_values(
+    conn: &mut SqliteConnection,
+    column: &CustomColumn,
+    book_id: i32,
+) -> Result<Vec<String>, Error> {
+    if !column.has_values() {
+        return Ok(Vec::new());
+    }
+
+    // Table names cannot be bound as parameters, column id is an integer so it is safe here.
+    let sql = if column.normalized {
+        format!(
+            "SELECT CAST(v.value AS TEXT) AS value \
+             FROM books_custom_column_{id}_link l \
+             JOIN custom_column_{id} v ON l.value = v.id \
+             WHERE l.book = ? ORDER BY l.id",
+            id = column.id
+        )
+    } else {
+        format!(
+            "SELECT CAST(value AS TEXT) AS value \
+             FROM custom_column_{} WHERE book = ? ORDER BY id",
+            column.id
+        )
+    };
+
+    sql_query(sql)
+        .bind::<Integer, _>(book_id)
+        .load::<CustomColumnValue>(conn)
+        .map(|values| values.into_iter().map(|value| value.value).collect())
+        .map_err(Into::into)
+}
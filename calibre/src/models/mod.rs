@@ -6,11 +6,13 @@ pub mod authors;
 pub mod books;
 pub mod books_authors;
 pub mod books_languages;
+pub mod books_plugin_data;
 pub mod books_publishers;
 pub mod books_ratings;
 pub mod books_series;
 pub mod books_tags;
 pub mod comments;
+pub mod custom_columns;
 pub mod data;
 pub mod file_formats;
 pub mod identifier_types;
@@ -92,6 +92,20 @@ table! {
     }
 }
 
+table! {
+    custom_columns (id) {
+        id -> Integer,
+        label -> Text,
+        name -> Text,
+        datatype -> Text,
+        mark_for_delete -> Bool,
+        editable -> Bool,
+        display -> Text,
+        is_multiple -> Bool,
+        normalized -> Bool,
+    }
+}
+
 table! {
     data (id) {
         id -> Integer,
@@ -160,6 +174,7 @@ allow_tables_to_appear_in_same_query!(
     books_series_link,
     books_tags_link,
     comments,
+    custom_columns,
     data,
     identifiers,
     languages,
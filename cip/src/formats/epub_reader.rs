@@ -8,6 +8,7 @@ use std::io::BufReader;
 use std::path::Path;
 
 use crate::error::{Error, ErrorKind};
+use crate::formats::metadata::{normalize_isbn, FileMetadata};
 
 pub struct EpubReader {
     doc: EpubDoc<BufReader<File>>,
@@ -23,6 +24,29 @@ impl EpubReader {
         Ok(Self { doc })
     }
 
+    /// Read metadata from content.opf.
+    #[must_use]
+    pub fn metadata(&mut self) -> FileMetadata {
+        let isbn = self
+            .doc
+            .metadata
+            .get("identifier")
+            .and_then(|ids| ids.iter().find_map(|id| normalize_isbn(id)));
+        FileMetadata {
+            title: self.doc.mdata("title"),
+            authors: self
+                .doc
+                .metadata
+                .get("creator")
+                .cloned()
+                .unwrap_or_default(),
+            publisher: self.doc.mdata("publisher"),
+            isbn,
+            language: self.doc.mdata("language"),
+            cover: self.doc.get_cover().map(|(data, _mime)| data),
+        }
+    }
+
     #[must_use]
     pub fn pages(&self) -> usize {
         self.doc.get_num_pages()
@@ -0,0 +1,186 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use std::path::Path;
+
+use crate::error::{Error, ErrorKind};
+use crate::formats::epub_reader::EpubReader;
+use crate::formats::mobi_reader::MobiReader;
+use crate::formats::pdf_reader::PdfReader;
+
+/// Metadata embedded in ebook file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    pub isbn: Option<String>,
+
+    /// Language code, like `en` or `zh-CN`.
+    pub language: Option<String>,
+
+    /// Image data of cover.
+    pub cover: Option<Vec<u8>>,
+}
+
+impl FileMetadata {
+    /// Fill fields which are not set with values in `other`.
+    pub fn fill_missing(&mut self, other: Self) {
+        if self.title.is_none() {
+            self.title = other.title;
+        }
+        if self.authors.is_empty() {
+            self.authors = other.authors;
+        }
+        if self.publisher.is_none() {
+            self.publisher = other.publisher;
+        }
+        if self.isbn.is_none() {
+            self.isbn = other.isbn;
+        }
+        if self.language.is_none() {
+            self.language = other.language;
+        }
+        if self.cover.is_none() {
+            self.cover = other.cover;
+        }
+    }
+}
+
+/// Check digit of ISBN-10 or ISBN-13, which contains only digits and `X`.
+fn is_valid_isbn(isbn: &str) -> bool {
+    let digits: Vec<u32> = isbn
+        .chars()
+        .map(|c| {
+            if c == 'X' {
+                10
+            } else {
+                c.to_digit(10).unwrap_or(0)
+            }
+        })
+        .collect();
+    match digits.len() {
+        10 => {
+            // `X` is only valid as check digit.
+            !isbn[..9].contains('X')
+                && digits
+                    .iter()
+                    .zip((1..=10).rev())
+                    .map(|(digit, weight)| digit * weight)
+                    .sum::<u32>()
+                    % 11
+                    == 0
+        }
+        13 => {
+            !isbn.contains('X')
+                && (isbn.starts_with("978") || isbn.starts_with("979"))
+                && digits
+                    .iter()
+                    .zip([1, 3].iter().cycle())
+                    .map(|(digit, weight)| digit * weight)
+                    .sum::<u32>()
+                    % 10
+                    == 0
+        }
+        _ => false,
+    }
+}
+
+/// Keep digits and `X` in isbn, and returns None if it is not a valid ISBN-10 or ISBN-13.
+///
+/// Identifiers of other schemes, like `urn:uuid:`, are rejected.
+#[must_use]
+pub fn normalize_isbn(s: &str) -> Option<String> {
+    let s = s.trim();
+    let lower = s.to_ascii_lowercase();
+    let s = ["urn:isbn:", "isbn:", "isbn"]
+        .iter()
+        .find(|prefix| lower.starts_with(*prefix))
+        .map_or(s, |prefix| &s[prefix.len()..]);
+    if !s
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, 'X' | 'x' | '-' | ' '))
+    {
+        return None;
+    }
+    let isbn: String = s
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if is_valid_isbn(&isbn) {
+        Some(isbn)
+    } else {
+        None
+    }
+}
+
+/// Find the first valid isbn written like `ISBN 978-7-02-000220-7` in text.
+#[must_use]
+pub fn find_isbn(text: &str) -> Option<String> {
+    let lower = text.to_ascii_lowercase();
+    lower.match_indices("isbn").find_map(|(index, _)| {
+        let rest = text[index + 4..].trim_start_matches([':', '-', ' ', '\u{ff1a}']);
+        // Prefixes with 10 or 13 digits, as isbn may be followed by other numbers.
+        let mut value = String::new();
+        let mut candidates = Vec::new();
+        for c in rest.chars() {
+            if c.is_ascii_digit() || c == 'X' || c == 'x' {
+                value.push(c);
+                let len = value.chars().filter(char::is_ascii_alphanumeric).count();
+                if len == 10 || len == 13 {
+                    candidates.push(value.clone());
+                }
+                if len == 13 {
+                    break;
+                }
+            } else if c == '-' || c == ' ' {
+                value.push(c);
+            } else {
+                break;
+            }
+        }
+        candidates
+            .iter()
+            .rev()
+            .find_map(|value| normalize_isbn(value))
+    })
+}
+
+/// Split author names joined with `;` or `&`.
+#[must_use]
+pub fn split_authors(s: &str) -> Vec<String> {
+    s.split([';', '&'])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Read metadata from ebook file, based on its extension.
+///
+/// # Errors
+/// Returns error if file format is not supported or file is invalid.
+pub fn read_metadata<P: AsRef<Path>>(path: P) -> Result<FileMetadata, Error> {
+    let path_ref: &Path = path.as_ref();
+    let extension = path_ref
+        .extension()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if extension == "pdf" {
+        return PdfReader::open(path).map(|reader| reader.metadata());
+    }
+    if extension == "epub" {
+        return EpubReader::open(path).map(|mut reader| reader.metadata());
+    }
+    if extension == "azw" || extension == "azw3" || extension == "mobi" {
+        return MobiReader::open(path).map(|reader| reader.metadata());
+    }
+
+    Err(Error::from_string(
+        ErrorKind::UnsupportedFile,
+        format!("File not supported, {}", path_ref.display()),
+    ))
+}
@@ -3,11 +3,12 @@
 // that can be found in the LICENSE file.
 
 use encoding_rs::WINDOWS_1252;
-use mobi::headers::TextEncoding;
+use mobi::headers::{ExthRecord, TextEncoding};
 use mobi::Mobi;
 use std::path::Path;
 
 use crate::error::{Error, ErrorKind};
+use crate::formats::metadata::{normalize_isbn, split_authors, FileMetadata};
 
 pub struct MobiReader {
     doc: Mobi,
@@ -23,6 +24,46 @@ impl MobiReader {
         Ok(Self { doc })
     }
 
+    /// Read metadata from EXTH header.
+    #[must_use]
+    pub fn metadata(&self) -> FileMetadata {
+        let language = self
+            .doc
+            .metadata
+            .exth_record(ExthRecord::Language)
+            .and_then(|values| values.first())
+            .map(|value| String::from_utf8_lossy(value).trim().to_owned())
+            .filter(|value| !value.is_empty());
+
+        FileMetadata {
+            title: Some(self.doc.title()).filter(|title| !title.is_empty()),
+            authors: self
+                .doc
+                .author()
+                .map(|author| split_authors(&author))
+                .unwrap_or_default(),
+            publisher: self.doc.publisher(),
+            isbn: self.doc.isbn().and_then(|isbn| normalize_isbn(&isbn)),
+            language,
+            cover: self.cover(),
+        }
+    }
+
+    /// Cover offset in EXTH header is index of image records.
+    fn cover(&self) -> Option<Vec<u8>> {
+        let offset = self
+            .doc
+            .metadata
+            .exth_record(ExthRecord::CoverOffset)
+            .and_then(|values| values.first())?;
+        let offset: [u8; 4] = offset.as_slice().try_into().ok()?;
+        let index = usize::try_from(u32::from_be_bytes(offset)).ok()?;
+        self.doc
+            .image_records()
+            .get(index)
+            .map(|record| record.content.to_vec())
+    }
+
     #[must_use]
     pub fn pages(&self) -> usize {
         self.doc.readable_records_range().len()
@@ -3,5 +3,6 @@
 // that can be found in the LICENSE file.
 
 pub mod epub_reader;
+pub mod metadata;
 pub mod mobi_reader;
 pub mod pdf_reader;
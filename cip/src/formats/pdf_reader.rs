@@ -6,6 +6,7 @@ use lopdf::{Document, Object};
 use std::path::Path;
 
 use crate::error::{Error, ErrorKind};
+use crate::formats::metadata::{find_isbn, normalize_isbn, split_authors, FileMetadata};
 
 const IGNORE: &[&str] = &[
     "Length",
@@ -59,6 +60,59 @@ fn filter_func(object_id: (u32, u16), object: &mut Object) -> Option<((u32, u16)
     Some((object_id, object.clone()))
 }
 
+/// Decode pdf text string, which is either UTF-16BE with BOM or `PDFDocEncoding`.
+fn decode_text(bytes: &[u8]) -> String {
+    bytes.strip_prefix(&[0xfe, 0xff]).map_or_else(
+        || bytes.iter().map(|byte| char::from(*byte)).collect(),
+        |bytes| {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        },
+    )
+}
+
+/// Replace predefined entities of xml.
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Get text values of `tag` element in XMP metadata.
+///
+/// Values in `rdf:li` items of `rdf:Alt`, `rdf:Bag` or `rdf:Seq` are returned one by one.
+fn xmp_values(xmp: &str, tag: &str) -> Vec<String> {
+    let open_tag = format!("<{tag}>");
+    let close_tag = format!("</{tag}>");
+    let Some(start) = xmp.find(&open_tag) else {
+        return Vec::new();
+    };
+    let content = &xmp[start + open_tag.len()..];
+    let content = &content[..content.find(&close_tag).unwrap_or(content.len())];
+    if !content.contains("<rdf:li") {
+        let value = content.trim();
+        return if value.is_empty() {
+            Vec::new()
+        } else {
+            vec![unescape_xml(value)]
+        };
+    }
+    content
+        .split("<rdf:li")
+        .skip(1)
+        .filter_map(|item| {
+            let value = &item[item.find('>')? + 1..];
+            let value = value[..value.find("</rdf:li>")?].trim();
+            (!value.is_empty()).then(|| unescape_xml(value))
+        })
+        .collect()
+}
+
 impl PdfReader {
     /// Open a pdf file.
     ///
@@ -75,6 +129,75 @@ impl PdfReader {
         Ok(Self { doc })
     }
 
+    /// Read XMP metadata stream of document catalog.
+    fn xmp_metadata(&self) -> Option<String> {
+        let stream = self
+            .doc
+            .catalog()
+            .and_then(|catalog| catalog.get_deref(b"Metadata", &self.doc))
+            .and_then(Object::as_stream)
+            .ok()?;
+        let content = stream
+            .decompressed_content()
+            .unwrap_or_else(|_err| stream.content.clone());
+        Some(String::from_utf8_lossy(&content).into_owned())
+    }
+
+    /// Read metadata from document information dictionary, catalog and XMP metadata.
+    ///
+    /// Values in information dictionary come first. Cover image is not extracted
+    /// from pdf files.
+    #[must_use]
+    pub fn metadata(&self) -> FileMetadata {
+        let get_text = |key: &[u8]| {
+            self.doc
+                .trailer
+                .get_deref(b"Info", &self.doc)
+                .and_then(Object::as_dict)
+                .and_then(|info| info.get_deref(key, &self.doc))
+                .and_then(Object::as_str)
+                .map(decode_text)
+                .ok()
+                .map(|text| text.trim().to_owned())
+                .filter(|text| !text.is_empty())
+        };
+        let xmp = self.xmp_metadata().unwrap_or_default();
+        let xmp_value = |tag: &str| xmp_values(&xmp, tag).into_iter().next();
+
+        let isbn = [b"Subject".as_slice(), b"Keywords".as_slice()]
+            .iter()
+            .filter_map(|key| get_text(key))
+            .find_map(|text| find_isbn(&text))
+            .or_else(|| {
+                ["prism:isbn", "pdfx:ISBN", "dc:identifier"]
+                    .iter()
+                    .flat_map(|tag| xmp_values(&xmp, tag))
+                    .find_map(|value| normalize_isbn(&value))
+            });
+        let language = self
+            .doc
+            .catalog()
+            .and_then(|catalog| catalog.get_deref(b"Lang", &self.doc))
+            .and_then(Object::as_str)
+            .map(decode_text)
+            .ok()
+            .map(|lang| lang.trim().to_owned())
+            .filter(|lang| !lang.is_empty())
+            .or_else(|| xmp_value("dc:language"));
+
+        FileMetadata {
+            title: get_text(b"Title").or_else(|| xmp_value("dc:title")),
+            authors: get_text(b"Author").map_or_else(
+                || xmp_values(&xmp, "dc:creator"),
+                |author| split_authors(&author),
+            ),
+            publisher: xmp_value("dc:publisher"),
+            isbn,
+            language,
+            cover: None,
+        }
+    }
+
     /// Get number of pages in pdf file.
     #[must_use]
     pub fn pages(&self) -> u32 {
@@ -9,6 +9,15 @@ use yew::prelude::*;
 pub struct Props {
     pub current_order: GetBooksOrder,
     pub onchange: Callback<GetBooksOrder>,
+
+    /// Show a button to sort by relevance, used by search results.
+    #[prop_or_default]
+    pub on_relevance: Option<Callback<()>>,
+
+    /// Books are sorted by relevance, or by default order of the list like series
+    /// index, and `current_order` is ignored.
+    #[prop_or_default]
+    pub relevance_active: bool,
 }
 
 #[function_component(BookFilterComponent)]
@@ -23,8 +32,9 @@ pub fn book_filter(props: &Props) -> Html {
 
     let get_button_cls = {
         let active_order = props.current_order;
+        let relevance_active = props.relevance_active;
         move |order: GetBooksOrder| {
-            if order == active_order {
+            if !relevance_active && order == active_order {
                 "btn btn-primary active"
             } else {
                 "btn btn-primary"
@@ -32,9 +42,30 @@ pub fn book_filter(props: &Props) -> Html {
         }
     };
 
+    let relevance_button = props.on_relevance.clone().map(|on_relevance| {
+        let onclick = Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            on_relevance.emit(());
+        });
+        let cls = if props.relevance_active {
+            "btn btn-primary active"
+        } else {
+            "btn btn-primary"
+        };
+        html! {
+            <button type="button" class={ cls }
+                title={ "Sort according to relevance" }
+                onclick={ onclick }>
+                <i class="bi bi-stars"></i>
+            </button>
+        }
+    });
+
     html! {
         <div class="btn-group mt-2 mb-2">
 
+        { relevance_button }
+
         <button type="button" class={ get_button_cls(GetBooksOrder::IdAsc) }
             title={ "Sort according to book date, newest first" }
             onclick={ button_onclick(GetBooksOrder::IdAsc) }>
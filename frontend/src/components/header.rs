@@ -42,6 +42,23 @@ pub fn header() -> Html {
             // </div>
 
             <ul class="navbar-nav">
+            if user_ctx.is_admin() {
+                <li class="nav-item">
+                    <Link<Route> to={ Route::UploadBook } classes="nav-link">
+                        { "Upload" }
+                    </Link<Route>>
+                </li>
+                <li class="nav-item">
+                    <Link<Route> to={ Route::Import } classes="nav-link">
+                        { "Import" }
+                    </Link<Route>>
+                </li>
+                <li class="nav-item">
+                    <Link<Route> to={ Route::Trash } classes="nav-link">
+                        { "Trash" }
+                    </Link<Route>>
+                </li>
+            }
             if user_ctx.is_login() {
                 <li class="nav-item">
                     <Link<Route> to={ Route::UserInfo } classes="nav-link">
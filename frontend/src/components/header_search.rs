@@ -2,19 +2,75 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+use gloo_timers::future::TimeoutFuture;
+use shared::search_query::{self, ParseError};
 use shared::simple_search::SimpleSearchQuery;
+use shared::suggest::{SuggestQuery, SuggestionList};
 use std::ops::Deref;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::hooks::use_navigator;
 
+use crate::components::search_suggestions::SearchSuggestionsComponent;
 use crate::router::Route;
+use crate::services::suggest::fetch_suggestions;
+
+/// Wait for typing to pause before fetching suggestions, in milliseconds.
+const SUGGEST_DELAY: u32 = 300;
+
+/// Convert char offset to UTF-16 offset, which is used by input element.
+fn utf16_offset(text: &str, char_offset: usize) -> u32 {
+    let offset: usize = text.chars().take(char_offset).map(char::len_utf16).sum();
+    u32::try_from(offset).unwrap_or(u32::MAX)
+}
+
+/// Check syntax of query before searching, and select the invalid part if any.
+fn check_query(input: &HtmlInputElement) -> Result<String, ParseError> {
+    let query = input.value();
+    if let Err(err) = search_query::parse(&query) {
+        let _ret = input.focus();
+        let _ret = input.set_selection_range(
+            utf16_offset(&query, err.start),
+            utf16_offset(&query, err.end),
+        );
+        return Err(err);
+    }
+    Ok(query)
+}
+
+/// Fetch suggestions of text in `input` after typing pauses,
+/// responses of outdated text are dropped.
+fn update_suggestions(input: HtmlInputElement, suggestions: UseStateHandle<SuggestionList>) {
+    let q = input.value();
+    if q.trim().is_empty() {
+        suggestions.set(SuggestionList::new());
+        return;
+    }
+    wasm_bindgen_futures::spawn_local(async move {
+        TimeoutFuture::new(SUGGEST_DELAY).await;
+        if input.value() != q {
+            return;
+        }
+        let query = SuggestQuery { q };
+        if let Ok(list) = fetch_suggestions(&query).await {
+            if input.value() == query.q {
+                suggestions.set(list);
+            }
+        }
+    });
+}
 
 #[function_component(HeaderSearchComponent)]
 pub fn header_search() -> Html {
     let input_ref = use_node_ref();
     let navigator = use_navigator().expect("Navigator object is invalid");
     let query_state = use_state(String::new);
+    let error_state = use_state(|| Option::<ParseError>::None);
+    let suggestions = use_state(SuggestionList::new);
+    let clear_suggestions = {
+        let suggestions_clone = suggestions.clone();
+        Callback::from(move |()| suggestions_clone.set(SuggestionList::new()))
+    };
 
     use_effect_with(query_state.clone(), move |query_state| {
         let query = query_state.deref().clone();
@@ -29,38 +85,80 @@ pub fn header_search() -> Html {
         || ()
     });
 
-    let search_onsubmit = {
+    let submit_query = {
         let input_ref_clone = input_ref.clone();
-        let query_state_clone = query_state.clone();
+        let error_state_clone = error_state.clone();
+        let clear_suggestions_clone = clear_suggestions.clone();
+        Callback::from(move |()| {
+            let Some(input) = input_ref_clone.cast::<HtmlInputElement>() else {
+                return;
+            };
+            clear_suggestions_clone.emit(());
+            match check_query(&input) {
+                Ok(query) => {
+                    error_state_clone.set(None);
+                    query_state.set(query);
+                }
+                Err(err) => error_state_clone.set(Some(err)),
+            }
+        })
+    };
+
+    let search_onsubmit = {
+        let submit_query_clone = submit_query.clone();
         Callback::from(move |event: SubmitEvent| {
             event.prevent_default();
-            if let Some(input) = input_ref_clone.cast::<HtmlInputElement>() {
-                query_state_clone.set(input.value());
-            }
+            submit_query_clone.emit(());
         })
     };
 
     let input_onkeydown = {
+        let clear_suggestions_clone = clear_suggestions.clone();
+        Callback::from(move |event: KeyboardEvent| match event.code().as_str() {
+            "Enter" => {
+                event.prevent_default();
+                submit_query.emit(());
+            }
+            "Escape" => clear_suggestions_clone.emit(()),
+            _ => (),
+        })
+    };
+
+    let input_oninput = {
         let input_ref_clone = input_ref.clone();
-        Callback::from(move |event: KeyboardEvent| {
-            if event.code() != "Enter" {
-                return;
+        let error_state_clone = error_state.clone();
+        let suggestions_clone = suggestions.clone();
+        Callback::from(move |_event: InputEvent| {
+            if error_state_clone.is_some() {
+                error_state_clone.set(None);
             }
-            event.prevent_default();
             if let Some(input) = input_ref_clone.cast::<HtmlInputElement>() {
-                query_state.set(input.value());
+                update_suggestions(input, suggestions_clone.clone());
             }
         })
     };
 
+    let input_classes = if error_state.is_some() {
+        "form-control me-2 is-invalid"
+    } else {
+        "form-control me-2"
+    };
+
     html! {
        <form class="d-flex" onsubmit={ search_onsubmit } role="search">
-           <div class="input-group">
+           <div class="input-group has-validation">
                <div class="input-group-text"><i class="bi bi-search"></i></div>
-               <input class="form-control me-2"
+               <input class={ input_classes }
                    type="search" placeholder={ "Search Library" }
+                   title={ "Like: author:\"name\" and tag:novel and not format:pdf and rating:>=4" }
                    ref={ input_ref }
+                   oninput={ input_oninput }
                    onkeydown={ input_onkeydown } />
+               if let Some(err) = &*error_state {
+                   <div class="invalid-tooltip">{ &err.message }</div>
+               }
+               <SearchSuggestionsComponent groups={ (*suggestions).clone() }
+                   onselect={ clear_suggestions } />
            </div>
 
            <button class="btn btn-primary" type="submit">
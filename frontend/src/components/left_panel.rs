@@ -82,6 +82,12 @@ pub fn left_panel() -> Html {
                         { "My Tags" }
                     </Link<Route>>
                 </li>
+                <li class="nav-item">
+                    <Link<Route> to={ Route::SavedSearch } classes={ link_cls(Route::SavedSearch) }>
+                        <i class="bi bi-bookmark-star pe-2"></i>
+                        { "My Searches" }
+                    </Link<Route>>
+                </li>
                 <li class="nav-item">
                     <Link<Route> to={ Route::BooksOfDownloadHistory }
                         classes={ link_cls(Route::BooksOfDownloadHistory) }>
@@ -13,4 +13,6 @@ pub mod left_panel;
 pub mod main_content;
 pub mod not_found;
 pub mod pagination;
+pub mod save_search;
+mod search_suggestions;
 pub mod user_context_provider;
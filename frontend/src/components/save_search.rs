@@ -0,0 +1,79 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::saved_searches::{NewSavedSearch, SavedQuery, SavedSearch};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::error::FetchError;
+use crate::router::Route;
+use crate::services::saved_searches::add_saved_search;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub query: SavedQuery,
+}
+
+/// Form to save current search with a name.
+#[function_component(SaveSearchComponent)]
+pub fn save_search(props: &Props) -> Html {
+    let name_ref = use_node_ref();
+    let saved = use_state(|| None::<Result<SavedSearch, FetchError>>);
+
+    let on_form_submit = {
+        let name_ref_clone = name_ref.clone();
+        let saved_clone = saved.clone();
+        let query = props.query.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            let Some(input) = name_ref_clone.cast::<HtmlInputElement>() else {
+                return;
+            };
+            let name = input.value().trim().to_owned();
+            if name.is_empty() {
+                return;
+            }
+            let new_search = NewSavedSearch {
+                name,
+                query: query.clone(),
+            };
+            let saved_clone = saved_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let ret = add_saved_search(&new_search).await;
+                if ret.is_ok() {
+                    input.set_value("");
+                }
+                saved_clone.set(Some(ret));
+            });
+        })
+    };
+
+    html! {
+        <form class="d-flex mb-2" onsubmit={ on_form_submit }>
+            <input class="form-control form-control-sm me-2 w-auto" type="text"
+                placeholder="Name of search" ref={ name_ref } />
+            <button class="btn btn-sm btn-outline-primary me-2" type="submit">
+                <i class="bi bi-bookmark-plus pe-1"></i>
+                { "Save search" }
+            </button>
+            {
+                match &*saved {
+                    Some(Ok(search)) => html! {
+                        <Link<Route> to={ Route::BooksOfSavedSearch { search_id: search.id } }
+                            classes="align-self-center">
+                            { format!("Saved as \"{}\"", search.name) }
+                        </Link<Route>>
+                    },
+                    Some(Err(_err)) => html! {
+                        <span class="text-danger align-self-center">
+                            { "Failed to save search, the name may be used already" }
+                        </span>
+                    },
+                    None => html! {},
+                }
+            }
+        </form>
+    }
+}
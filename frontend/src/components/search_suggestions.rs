@@ -0,0 +1,74 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::suggest::{SuggestionGroup, SuggestionKind, SuggestionList};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::router::Route;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub groups: SuggestionList,
+
+    /// Emitted when a suggestion is clicked.
+    pub onselect: Callback<()>,
+}
+
+const fn suggestion_route(kind: SuggestionKind, id: i32) -> Route {
+    match kind {
+        SuggestionKind::Book => Route::BookDetail { book_id: id },
+        SuggestionKind::Author => Route::BooksOfAuthor { author_id: id },
+        SuggestionKind::Publisher => Route::BooksOfPublisher { publisher_id: id },
+        SuggestionKind::Series => Route::BooksOfSeries { series_id: id },
+        SuggestionKind::Tag => Route::BooksOfTag { tag_id: id },
+        SuggestionKind::Category => Route::BooksOfCategory { category_id: id },
+    }
+}
+
+fn generate_group(group: &SuggestionGroup, onselect: &Callback<()>) -> Html {
+    let items = group
+        .list
+        .iter()
+        .map(|suggestion| {
+            let onclick = onselect.reform(|_event: MouseEvent| ());
+            html! {
+                <li key={ suggestion.id } onclick={ onclick }>
+                    <Link<Route> to={ suggestion_route(group.kind, suggestion.id) }
+                        classes="dropdown-item d-flex justify-content-between">
+                        <span class="text-truncate">{ &suggestion.name }</span>
+                        <span class="badge text-bg-light ms-2">{ suggestion.count }</span>
+                    </Link<Route>>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <>
+        <li><h6 class="dropdown-header">{ group.kind.title() }</h6></li>
+        { items }
+        </>
+    }
+}
+
+/// Dropdown of search-as-you-type suggestions, grouped by kind.
+#[function_component(SearchSuggestionsComponent)]
+pub fn search_suggestions(props: &Props) -> Html {
+    if props.groups.is_empty() {
+        return html! {};
+    }
+
+    let groups = props
+        .groups
+        .iter()
+        .map(|group| generate_group(group, &props.onselect))
+        .collect::<Html>();
+
+    html! {
+        <ul class="dropdown-menu show position-absolute top-100 start-0 w-100">
+            { groups }
+        </ul>
+    }
+}
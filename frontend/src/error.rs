@@ -22,6 +22,9 @@ pub enum ErrorKind {
     // For status == 404.
     NotFound,
 
+    // For status == 409.
+    Conflict,
+
     // For status == 500.
     InternalServerError,
 
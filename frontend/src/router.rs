@@ -17,16 +17,21 @@ use crate::views::books_of_discover::BooksOfDiscoverComponent;
 use crate::views::books_of_download_history::BooksOfDownloadHistoryComponent;
 use crate::views::books_of_file_format::BooksOfFileFormatComponent;
 use crate::views::books_of_publisher::BooksOfPublisherComponent;
+use crate::views::books_of_saved_search::BooksOfSavedSearchComponent;
 use crate::views::books_of_series::BooksOfSeriesComponent;
 use crate::views::books_of_simple_search::BooksOfSimpleSearchComponent;
 use crate::views::books_of_tag::BooksOfTagComponent;
 use crate::views::books_of_user_tag::BooksOfUserTagComponent;
 use crate::views::categories::CategoriesComponent;
 use crate::views::file_formats::FileFormatsComponent;
+use crate::views::import_jobs::ImportJobsComponent;
 use crate::views::logout::LogoutComponent;
 use crate::views::publishers::PublishersComponent;
+use crate::views::saved_searches::SavedSearchesComponent;
 use crate::views::series::SeriesComponent;
 use crate::views::tags::TagsComponent;
+use crate::views::trash::TrashComponent;
+use crate::views::upload_book::UploadBookComponent;
 use crate::views::user_info::UserInfoComponent;
 use crate::views::user_tags::UserTagsComponent;
 use crate::views::users::UsersComponent;
@@ -71,6 +76,11 @@ pub enum Route {
     #[at("/user-tag")]
     UserTag,
 
+    #[at("/saved-search/books/:search_id")]
+    BooksOfSavedSearch { search_id: i32 },
+    #[at("/saved-search")]
+    SavedSearch,
+
     #[at("/download/books")]
     BooksOfDownloadHistory,
 
@@ -85,6 +95,12 @@ pub enum Route {
     UserInfo,
     #[at("/user/all")]
     Users,
+    #[at("/import")]
+    Import,
+    #[at("/upload")]
+    UploadBook,
+    #[at("/trash")]
+    Trash,
     #[at("/logout")]
     Logout,
 
@@ -129,6 +145,11 @@ pub fn switch_route(routes: Route) -> Html {
         }
         Route::UserTag => html! { <UserTagsComponent /> },
 
+        Route::BooksOfSavedSearch { search_id } => {
+            html! { <BooksOfSavedSearchComponent search_id={ *search_id } /> }
+        }
+        Route::SavedSearch => html! { <SavedSearchesComponent /> },
+
         Route::BooksOfDownloadHistory => html! { <BooksOfDownloadHistoryComponent /> },
 
         Route::BooksOfAdvancedSearch => html! { <BooksOfAdvancedSearchComponent /> },
@@ -137,6 +158,9 @@ pub fn switch_route(routes: Route) -> Html {
 
         Route::UserInfo => html! { <UserInfoComponent /> },
         Route::Users => html! { <UsersComponent /> },
+        Route::Import => html! { <ImportJobsComponent /> },
+        Route::UploadBook => html! { <UploadBookComponent /> },
+        Route::Trash => html! { <TrashComponent /> },
         Route::Logout => html! { <LogoutComponent /> },
 
         Route::NotFound => html! { <NotFoundComponent /> },
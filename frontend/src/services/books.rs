@@ -2,11 +2,15 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use shared::books::{BookAndAuthorsList, BookUpdateReq};
+use shared::books::{BookAndAuthorsList, BookUpdateReq, BookWithCover, UploadBookResp};
 use shared::books_query::GetBooksQuery;
+use web_sys::FormData;
 
 use crate::error::FetchError;
-use crate::services::fetch::{request_get, request_put};
+use crate::services::fetch::{
+    request_delete, request_get, request_post, request_post_form_data, request_put,
+    request_put_form_data,
+};
 
 /// Get book list.
 ///
@@ -26,3 +30,45 @@ pub async fn update_book(query: &BookUpdateReq) -> Result<(), FetchError> {
     let url = format!("/api/book/{}", query.id);
     request_put(&url, query).await
 }
+
+/// Move book to trash.
+///
+/// # Errors
+/// Returns error if book not found.
+pub async fn delete_book(book_id: i32) -> Result<(), FetchError> {
+    let url = format!("/api/book/{book_id}");
+    request_delete(&url).await
+}
+
+/// Upload ebook files as a new book.
+///
+/// `form_data` contains `file` fields and an optional `metadata` field in json.
+///
+/// # Errors
+/// Returns error if files are invalid or too large.
+pub async fn upload_book(form_data: &FormData) -> Result<UploadBookResp, FetchError> {
+    request_post_form_data("/api/book/upload", form_data).await
+}
+
+/// Upload or replace cover of book.
+///
+/// `form_data` contains only one `cover` field.
+///
+/// # Errors
+/// Returns error if image is invalid.
+pub async fn upload_book_cover(
+    book_id: i32,
+    form_data: &FormData,
+) -> Result<BookWithCover, FetchError> {
+    let url = format!("/api/book/{book_id}/cover");
+    request_put_form_data(&url, form_data).await
+}
+
+/// Use cover embedded in ebook files of book.
+///
+/// # Errors
+/// Returns error if no cover is found.
+pub async fn extract_book_cover(book_id: i32) -> Result<BookWithCover, FetchError> {
+    let url = format!("/api/book/{book_id}/cover/extract");
+    request_post(&url, ()).await
+}
@@ -2,10 +2,10 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use shared::books_meta::BookMetadata;
+use shared::books_meta::{BookMetadata, BookMetadataUpdateReq};
 
 use crate::error::FetchError;
-use crate::services::fetch::request_get;
+use crate::services::fetch::{request_get, request_put};
 
 /// Get book metadata.
 ///
@@ -15,3 +15,15 @@ pub async fn fetch_book_metadata(book_id: i32) -> Result<BookMetadata, FetchErro
     let url = format!("/api/book/{book_id}");
     request_get(&url).await
 }
+
+/// Update metadata of book in one request, and returns new metadata.
+///
+/// # Errors
+/// Returns `Conflict` error if book was modified after `req.last_modified`.
+pub async fn update_book_metadata(
+    book_id: i32,
+    req: &BookMetadataUpdateReq,
+) -> Result<BookMetadata, FetchError> {
+    let url = format!("/api/book/{book_id}/metadata");
+    request_put(&url, req).await
+}
@@ -6,7 +6,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Headers, Request, RequestInit, RequestMode, Response};
+use web_sys::{FormData, Headers, Request, RequestInit, RequestMode, Response};
 
 use crate::error::{ErrorKind, FetchError};
 use crate::services::auth::get_token;
@@ -83,6 +83,56 @@ where
     request("DELETE", url, ()).await
 }
 
+/// Wrap `fetch()` api in browser, for POST requests with multipart form.
+///
+/// Content-type is set by browser, with boundary of multipart form.
+///
+/// # Errors
+///
+/// Returns error if failed to construct request or failed to read response body.
+pub async fn request_post_form_data<T>(url: &str, form_data: &FormData) -> Result<T, FetchError>
+where
+    T: DeserializeOwned + std::fmt::Debug,
+{
+    request_form_data("POST", url, form_data).await
+}
+
+/// Wrap `fetch()` api in browser, for PUT requests with multipart form.
+///
+/// Content-type is set by browser, with boundary of multipart form.
+///
+/// # Errors
+///
+/// Returns error if failed to construct request or failed to read response body.
+pub async fn request_put_form_data<T>(url: &str, form_data: &FormData) -> Result<T, FetchError>
+where
+    T: DeserializeOwned + std::fmt::Debug,
+{
+    request_form_data("PUT", url, form_data).await
+}
+
+async fn request_form_data<T>(
+    method: &str,
+    url: &str,
+    form_data: &FormData,
+) -> Result<T, FetchError>
+where
+    T: DeserializeOwned + std::fmt::Debug,
+{
+    let mut opts = RequestInit::new();
+    let headers = Headers::new()?;
+    if let Some(token) = get_token() {
+        headers.set("Authorization", &format!("Bearer {token}"))?;
+    }
+    opts.method(method)
+        .mode(RequestMode::Cors)
+        .headers(&headers)
+        .body(Some(form_data));
+
+    let request = Request::new_with_str_and_init(url, &opts)?;
+    send_request(url, &request).await
+}
+
 async fn request<T, B>(method: &str, url: &str, body: B) -> Result<T, FetchError>
 where
     T: DeserializeOwned + std::fmt::Debug,
@@ -103,8 +153,15 @@ where
     }
 
     let request = Request::new_with_str_and_init(url, &opts)?;
+    send_request(url, &request).await
+}
+
+async fn send_request<T>(url: &str, request: &Request) -> Result<T, FetchError>
+where
+    T: DeserializeOwned + std::fmt::Debug,
+{
     let window = gloo_utils::window();
-    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp_value = JsFuture::from(window.fetch_with_request(request)).await?;
     let resp: Response = resp_value.dyn_into()?;
     if resp.ok() {
         let text = JsFuture::from(resp.text()?).await?;
@@ -119,6 +176,7 @@ where
             401 => ErrorKind::Unauthorized,
             403 => ErrorKind::Forbidden,
             404 => ErrorKind::NotFound,
+            409 => ErrorKind::Conflict,
             500 => ErrorKind::InternalServerError,
             _ => ErrorKind::ResponseError,
         };
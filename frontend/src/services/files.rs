@@ -3,6 +3,10 @@
 // that can be found in the LICENSE file.
 
 use shared::files::{FileQuery, FileWithPath};
+use web_sys::FormData;
+
+use crate::error::FetchError;
+use crate::services::fetch::{request_delete, request_post_form_data, request_put_form_data};
 
 #[must_use]
 pub fn get_file_format_url(file: &FileWithPath) -> String {
@@ -15,3 +19,36 @@ pub fn get_file_format_url(file: &FileWithPath) -> String {
     let query_str = serde_urlencoded::to_string(query).unwrap_or_default();
     ["/api/file", &query_str].join("?")
 }
+
+/// Upload a new format of book.
+///
+/// `form_data` contains only one `file` field.
+///
+/// # Errors
+/// Returns error if book already has a file in the same format.
+pub async fn add_book_file(book_id: i32, form_data: &FormData) -> Result<FileWithPath, FetchError> {
+    let url = format!("/api/book/{book_id}/file");
+    request_post_form_data(&url, form_data).await
+}
+
+/// Replace content of book file with uploaded file in the same format.
+///
+/// # Errors
+/// Returns error if format of uploaded file does not match.
+pub async fn replace_book_file(
+    book_id: i32,
+    file_id: i32,
+    form_data: &FormData,
+) -> Result<FileWithPath, FetchError> {
+    let url = format!("/api/book/{book_id}/file/{file_id}");
+    request_put_form_data(&url, form_data).await
+}
+
+/// Delete book file.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn delete_book_file(book_id: i32, file_id: i32) -> Result<(), FetchError> {
+    let url = format!("/api/book/{book_id}/file/{file_id}");
+    request_delete(&url).await
+}
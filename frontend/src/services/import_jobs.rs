@@ -0,0 +1,45 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::import::{ImportJob, NewImportJobReq};
+
+use crate::error::FetchError;
+use crate::services::fetch::{request_get, request_post};
+
+/// Get import jobs started in web server, newest first.
+///
+/// # Errors
+/// Returns error if:
+/// - current user is not admin
+/// - server error
+pub async fn fetch_import_jobs() -> Result<Vec<ImportJob>, FetchError> {
+    request_get("/api/import").await
+}
+
+/// Start a new import job.
+///
+/// # Errors
+/// Returns error if:
+/// - path is not a folder or calibre library
+/// - server error
+pub async fn add_import_job(req: &NewImportJobReq) -> Result<ImportJob, FetchError> {
+    request_post("/api/import", req).await
+}
+
+/// Cancel a pending or running import job.
+///
+/// # Errors
+/// Returns error if:
+/// - job is already stopped
+/// - server error
+pub async fn cancel_import_job(job_id: i32) -> Result<ImportJob, FetchError> {
+    let url = format!("/api/import/{job_id}/cancel");
+    request_post(&url, ()).await
+}
+
+/// Url of server-sent events of job progress.
+#[must_use]
+pub fn import_job_events_url(job_id: i32) -> String {
+    format!("/api/import/{job_id}/events")
+}
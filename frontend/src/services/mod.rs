@@ -19,9 +19,13 @@ pub mod fetch;
 pub mod file_formats;
 pub mod files;
 pub mod images;
+pub mod import_jobs;
 pub mod publishers;
+pub mod saved_searches;
 pub mod series;
 pub mod simple_search;
+pub mod suggest;
 pub mod tags;
+pub mod trash;
 pub mod user_tags;
 pub mod users;
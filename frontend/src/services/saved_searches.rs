@@ -0,0 +1,56 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::books::BookAndAuthorsList;
+use shared::saved_searches::{GetSavedSearchBooksQuery, NewSavedSearch, SavedSearch};
+
+use crate::error::FetchError;
+use crate::services::fetch::{request_delete, request_get, request_post};
+
+/// Returns saved searches of current user.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn fetch_saved_searches() -> Result<Vec<SavedSearch>, FetchError> {
+    request_get("/api/saved-search").await
+}
+
+/// Returns saved search info.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn fetch_saved_search(search_id: i32) -> Result<SavedSearch, FetchError> {
+    let url = format!("/api/saved-search/{search_id}");
+    request_get(&url).await
+}
+
+/// Get book list matching saved search `search_id`.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn fetch_books_by_saved_search(
+    search_id: i32,
+    query: &GetSavedSearchBooksQuery,
+) -> Result<BookAndAuthorsList, FetchError> {
+    let query_str = serde_urlencoded::to_string(query)?;
+    let url = format!("/api/saved-search/{search_id}/book?{query_str}");
+    request_get(&url).await
+}
+
+/// Save a new search.
+///
+/// # Errors
+/// Returns error if server error or name is already used.
+pub async fn add_saved_search(new_search: &NewSavedSearch) -> Result<SavedSearch, FetchError> {
+    request_post("/api/saved-search", new_search).await
+}
+
+/// Delete specific saved search.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn delete_saved_search(search_id: i32) -> Result<(), FetchError> {
+    let url = format!("/api/saved-search/{search_id}");
+    request_delete(&url).await
+}
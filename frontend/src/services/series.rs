@@ -3,9 +3,8 @@
 // that can be found in the LICENSE file.
 
 use shared::books::BookAndAuthorsList;
-use shared::books_query::GetBooksQuery;
 use shared::general_query::GeneralQuery;
-use shared::series::{NewSeries, Series, SeriesAndBookList};
+use shared::series::{GetSeriesBooksQuery, NewSeries, Series, SeriesAndBookList};
 
 use crate::error::FetchError;
 use crate::services::fetch::{request_delete, request_get, request_post, request_put};
@@ -35,7 +34,7 @@ pub async fn fetch_series(series_id: i32) -> Result<Series, FetchError> {
 /// Returns error if server fails.
 pub async fn fetch_books_by_series(
     series_id: i32,
-    query: &GetBooksQuery,
+    query: &GetSeriesBooksQuery,
 ) -> Result<BookAndAuthorsList, FetchError> {
     let query_str = serde_urlencoded::to_string(query)?;
     let url = format!("/api/series/{series_id}/book?{query_str}");
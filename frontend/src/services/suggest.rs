@@ -0,0 +1,19 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::suggest::{SuggestQuery, SuggestionList};
+
+use crate::error::FetchError;
+use crate::services::fetch::request_get;
+
+/// Get suggestions of books, authors, publishers, series, tags and categories
+/// whose name starts with `query.q`.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn fetch_suggestions(query: &SuggestQuery) -> Result<SuggestionList, FetchError> {
+    let s: String = serde_urlencoded::to_string(query)?;
+    let url = format!("/api/suggest?{s}");
+    request_get(&url).await
+}
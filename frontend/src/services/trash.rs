@@ -0,0 +1,36 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::trash::TrashBook;
+
+use crate::error::FetchError;
+use crate::services::fetch::{request_delete, request_get, request_post};
+
+/// Get books in trash, latest deleted first.
+///
+/// # Errors
+/// Returns error if:
+/// - current user is not admin
+/// - server error
+pub async fn fetch_trash_books() -> Result<Vec<TrashBook>, FetchError> {
+    request_get("/api/trash").await
+}
+
+/// Restore book in trash.
+///
+/// # Errors
+/// Returns error if book directory in library is occupied.
+pub async fn restore_trash_book(book_id: i32) -> Result<(), FetchError> {
+    let url = format!("/api/trash/{book_id}/restore");
+    request_post(&url, ()).await
+}
+
+/// Remove book in trash permanently, with its files.
+///
+/// # Errors
+/// Returns error if server fails.
+pub async fn purge_trash_book(book_id: i32) -> Result<(), FetchError> {
+    let url = format!("/api/trash/{book_id}");
+    request_delete(&url).await
+}
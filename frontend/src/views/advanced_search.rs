@@ -2,58 +2,101 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use shared::advanced_search::AdvancedSearchQuery;
-use web_sys::HtmlInputElement;
+use shared::advanced_search::{AdvancedSearchQuery, MatchMode};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew_router::hooks::use_navigator;
 
 use crate::router::Route;
 use crate::views::util;
 
+/// Returns trimmed value of input element, or None if it is empty.
+fn input_value(node_ref: &NodeRef) -> Option<String> {
+    node_ref
+        .cast::<HtmlInputElement>()
+        .map(|input| input.value().trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+fn text_input(
+    id: &'static str,
+    label: &'static str,
+    node_ref: &NodeRef,
+    kind: &'static str,
+) -> Html {
+    html! {
+        <div class="mb-3">
+            <label for={ id } class="form-label">{ label }</label>
+            <input id={ id }
+                class="form-control"
+                ref={ node_ref.clone() }
+                name={ id }
+                type={ kind } />
+        </div>
+    }
+}
+
 #[function_component(AdvancedSearchComponent)]
 pub fn advanced_search() -> Html {
     util::set_document_title("Advanced Search");
 
     let navigator = use_navigator().unwrap();
+    let match_mode_ref = use_node_ref();
     let title_input_ref = use_node_ref();
     let author_input_ref = use_node_ref();
     let publisher_input_ref = use_node_ref();
     let isbn_input_ref = use_node_ref();
+    let tag_input_ref = use_node_ref();
+    let series_input_ref = use_node_ref();
+    let category_input_ref = use_node_ref();
+    let language_input_ref = use_node_ref();
+    let format_input_ref = use_node_ref();
+    let rating_min_input_ref = use_node_ref();
+    let rating_max_input_ref = use_node_ref();
+    let pubdate_from_input_ref = use_node_ref();
+    let pubdate_to_input_ref = use_node_ref();
+    let user_tag_input_ref = use_node_ref();
 
     let on_search_submit = {
-        let title_input_ref_clone = title_input_ref.clone();
-        let author_input_ref_clone = author_input_ref.clone();
-        let publisher_input_ref_clone = publisher_input_ref.clone();
-        let isbn_input_ref_clone = isbn_input_ref.clone();
+        let match_mode_ref = match_mode_ref.clone();
+        let title_input_ref = title_input_ref.clone();
+        let author_input_ref = author_input_ref.clone();
+        let publisher_input_ref = publisher_input_ref.clone();
+        let isbn_input_ref = isbn_input_ref.clone();
+        let tag_input_ref = tag_input_ref.clone();
+        let series_input_ref = series_input_ref.clone();
+        let category_input_ref = category_input_ref.clone();
+        let language_input_ref = language_input_ref.clone();
+        let format_input_ref = format_input_ref.clone();
+        let rating_min_input_ref = rating_min_input_ref.clone();
+        let rating_max_input_ref = rating_max_input_ref.clone();
+        let pubdate_from_input_ref = pubdate_from_input_ref.clone();
+        let pubdate_to_input_ref = pubdate_to_input_ref.clone();
+        let user_tag_input_ref = user_tag_input_ref.clone();
 
         Callback::from(move |event: SubmitEvent| {
             event.prevent_default();
 
             let mut query = AdvancedSearchQuery::default();
-            if let Some(input) = title_input_ref_clone.cast::<HtmlInputElement>() {
-                let value = input.value();
-                if !value.is_empty() {
-                    query.title = Some(value);
-                }
-            }
-            if let Some(input) = author_input_ref_clone.cast::<HtmlInputElement>() {
-                let value = input.value();
-                if !value.is_empty() {
-                    query.author = Some(value);
-                }
-            }
-            if let Some(input) = publisher_input_ref_clone.cast::<HtmlInputElement>() {
-                let value = input.value();
-                if !value.is_empty() {
-                    query.publisher = Some(value);
-                }
-            }
-            if let Some(input) = isbn_input_ref_clone.cast::<HtmlInputElement>() {
-                let value = input.value();
-                if !value.is_empty() {
-                    query.isbn = Some(value);
+            if let Some(select) = match_mode_ref.cast::<HtmlSelectElement>() {
+                if select.value() == "any" {
+                    query.match_mode = MatchMode::Any;
                 }
             }
+            query.title = input_value(&title_input_ref);
+            query.author = input_value(&author_input_ref);
+            query.publisher = input_value(&publisher_input_ref);
+            query.isbn = input_value(&isbn_input_ref);
+            query.tag = input_value(&tag_input_ref);
+            query.series = input_value(&series_input_ref);
+            query.category = input_value(&category_input_ref);
+            query.language = input_value(&language_input_ref);
+            query.format = input_value(&format_input_ref);
+            query.rating_min = input_value(&rating_min_input_ref).and_then(|v| v.parse().ok());
+            query.rating_max = input_value(&rating_max_input_ref).and_then(|v| v.parse().ok());
+            query.pubdate_from = input_value(&pubdate_from_input_ref).and_then(|v| v.parse().ok());
+            query.pubdate_to = input_value(&pubdate_to_input_ref).and_then(|v| v.parse().ok());
+            query.user_tag = input_value(&user_tag_input_ref);
 
             let ret = navigator.push_with_query(&Route::BooksOfAdvancedSearch, &query);
             debug_assert!(ret.is_ok());
@@ -67,40 +110,27 @@ pub fn advanced_search() -> Html {
         <div class="container-fluid">
         <form onsubmit={ on_search_submit } class="col-lg-6 col-md-9 col-sm-12">
         <div class="mb-3">
-            <label for="book-title" class="form-label">{ "Book Title" }</label>
-            <input id="book-title"
-                class="form-control"
-                ref={ title_input_ref }
-                name="book_title"
-                type="text" />
+            <label for="match-mode" class="form-label">{ "Match" }</label>
+            <select id="match-mode" class="form-select" ref={ match_mode_ref }>
+                <option value="all" selected={ true }>{ "All of conditions" }</option>
+                <option value="any">{ "Any of conditions" }</option>
+            </select>
         </div>
 
-        <div class="mb-3">
-            <label for="book-author" class="form-label">{ "Author" }</label>
-            <input id="book-author"
-                class="form-control"
-                ref={ author_input_ref }
-                name="book_author"
-                type="text" />
-        </div>
-
-        <div class="mb-3">
-            <label for="book-publisher" class="form-label">{ "Publisher" }</label>
-            <input id="book-publisher"
-                class="form-control"
-                ref={ publisher_input_ref }
-                name="book_publisher"
-                type="text" />
-        </div>
-
-        <div class="mb-3">
-            <label for="book-isbn" class="form-label">{ "ISBN" }</label>
-            <input id="book-isbn"
-                class="form-control"
-                ref={ isbn_input_ref }
-                name="book_isbn"
-                type="text" />
-        </div>
+        { text_input("book-title", "Book Title", &title_input_ref, "text") }
+        { text_input("book-author", "Author", &author_input_ref, "text") }
+        { text_input("book-publisher", "Publisher", &publisher_input_ref, "text") }
+        { text_input("book-isbn", "ISBN", &isbn_input_ref, "text") }
+        { text_input("book-tag", "Tag", &tag_input_ref, "text") }
+        { text_input("book-series", "Series", &series_input_ref, "text") }
+        { text_input("book-category", "Category Serial Number", &category_input_ref, "text") }
+        { text_input("book-language", "Language Code", &language_input_ref, "text") }
+        { text_input("book-format", "File Format", &format_input_ref, "text") }
+        { text_input("book-rating-min", "Minimum Rating", &rating_min_input_ref, "number") }
+        { text_input("book-rating-max", "Maximum Rating", &rating_max_input_ref, "number") }
+        { text_input("book-pubdate-from", "Published From", &pubdate_from_input_ref, "date") }
+        { text_input("book-pubdate-to", "Published To", &pubdate_to_input_ref, "date") }
+        { text_input("book-user-tag", "My Tag", &user_tag_input_ref, "text") }
 
         <button type="submit" class="btn btn-primary">{ "Search" }</button>
         </form>
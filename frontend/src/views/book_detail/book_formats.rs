@@ -2,38 +2,211 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use crate::services::files::get_file_format_url;
+use crate::error::FetchError;
+use crate::services::files::{
+    add_book_file, delete_book_file, get_file_format_url, replace_book_file,
+};
 use crate::views::util::to_readable_size;
 use shared::files::FileWithPath;
+use web_sys::{FormData, HtmlInputElement};
 use yew::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq, Properties)]
 pub struct Props {
+    pub book_id: i32,
     pub files: Vec<FileWithPath>,
+
+    /// Show buttons to add, replace and delete files.
+    #[prop_or_default]
+    pub editable: bool,
+}
+
+/// Build multipart form with the selected file, and reset file input.
+fn new_file_form_data(input_ref: &NodeRef) -> Option<FormData> {
+    let input = input_ref.cast::<HtmlInputElement>()?;
+    let file = input.files()?.get(0)?;
+    let form_data = FormData::new().ok()?;
+    form_data
+        .append_with_blob_and_filename("file", &file, &file.name())
+        .ok()?;
+    input.set_value("");
+    Some(form_data)
+}
+
+/// Replace file with the same id in list, or append it.
+fn update_file_list(files: &UseStateHandle<Vec<FileWithPath>>, file: FileWithPath) {
+    let mut new_files = (**files).clone();
+    if let Some(old_file) = new_files.iter_mut().find(|old_file| old_file.id == file.id) {
+        *old_file = file;
+    } else {
+        new_files.push(file);
+    }
+    files.set(new_files);
 }
 
 #[function_component(BookFormatsComponent)]
 pub fn book_formats(props: &Props) -> Html {
-    let files = &props.files;
+    let files = use_state(|| props.files.clone());
+    {
+        let files_clone = files.clone();
+        use_effect_with(props.files.clone(), move |new_files| {
+            files_clone.set(new_files.clone());
+            || ()
+        });
+    }
+    let file_error = use_state(|| None::<FetchError>);
+    let add_file_ref = use_node_ref();
+    let replace_file_ref = use_node_ref();
+    // Id of file to be replaced by the selected one.
+    let replace_target = use_mut_ref(|| None::<i32>);
+
+    let on_add_file_changed = {
+        let book_id = props.book_id;
+        let add_file_ref_clone = add_file_ref.clone();
+        let files_clone = files.clone();
+        let file_error_clone = file_error.clone();
+        Callback::from(move |_event: Event| {
+            let Some(form_data) = new_file_form_data(&add_file_ref_clone) else {
+                return;
+            };
+            let files_clone = files_clone.clone();
+            let file_error_clone = file_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match add_book_file(book_id, &form_data).await {
+                    Ok(file) => {
+                        file_error_clone.set(None);
+                        update_file_list(&files_clone, file);
+                    }
+                    Err(err) => file_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    let on_replace_file_changed = {
+        let book_id = props.book_id;
+        let replace_file_ref_clone = replace_file_ref.clone();
+        let replace_target_clone = replace_target.clone();
+        let files_clone = files.clone();
+        let file_error_clone = file_error.clone();
+        Callback::from(move |_event: Event| {
+            let Some(file_id) = replace_target_clone.borrow_mut().take() else {
+                return;
+            };
+            let Some(form_data) = new_file_form_data(&replace_file_ref_clone) else {
+                return;
+            };
+            let files_clone = files_clone.clone();
+            let file_error_clone = file_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match replace_book_file(book_id, file_id, &form_data).await {
+                    Ok(file) => {
+                        file_error_clone.set(None);
+                        update_file_list(&files_clone, file);
+                    }
+                    Err(err) => file_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
     let elements = files
         .iter()
         .map(|file| {
             let url = get_file_format_url(file);
             let readable_size = to_readable_size(file.size);
+
+            let buttons = if props.editable {
+                let on_replace_button_clicked = {
+                    let file_id = file.id;
+                    let replace_file_ref_clone = replace_file_ref.clone();
+                    let replace_target_clone = replace_target.clone();
+                    Callback::from(move |_event: MouseEvent| {
+                        *replace_target_clone.borrow_mut() = Some(file_id);
+                        if let Some(input) = replace_file_ref_clone.cast::<HtmlInputElement>() {
+                            input.click();
+                        }
+                    })
+                };
+                let on_delete_button_clicked = {
+                    let book_id = props.book_id;
+                    let file_id = file.id;
+                    let format_name = file.format_name.clone();
+                    let files_clone = files.clone();
+                    let file_error_clone = file_error.clone();
+                    Callback::from(move |_event: MouseEvent| {
+                        let message = format!("Delete {format_name} file of this book?");
+                        let confirmed = gloo_utils::window()
+                            .confirm_with_message(&message)
+                            .unwrap_or_default();
+                        if !confirmed {
+                            return;
+                        }
+                        let files_clone = files_clone.clone();
+                        let file_error_clone = file_error_clone.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            match delete_book_file(book_id, file_id).await {
+                                Ok(()) => {
+                                    file_error_clone.set(None);
+                                    let new_files = files_clone
+                                        .iter()
+                                        .filter(|file| file.id != file_id)
+                                        .cloned()
+                                        .collect();
+                                    files_clone.set(new_files);
+                                }
+                                Err(err) => file_error_clone.set(Some(err)),
+                            }
+                        });
+                    })
+                };
+
+                html! {
+                    <>
+                    <button type="button" class="btn btn-sm btn-link"
+                        onclick={ on_replace_button_clicked }>
+                        { "Replace" }
+                    </button>
+                    <button type="button" class="btn btn-sm btn-link text-danger"
+                        onclick={ on_delete_button_clicked }>
+                        { "Delete" }
+                    </button>
+                    </>
+                }
+            } else {
+                html! {}
+            };
+
             html! {
-                <li>
+                <li key={ file.id }>
                     <a class="book-format" target="_blank" href={ url }>
                         { format!("{} ({readable_size})", file.format_name) }
                         <i class="bi bi-download ms-1"></i>
                     </a>
+                    { buttons }
                 </li>
             }
         })
         .collect::<Html>();
 
     html! {
+        <>
         <ol class="book-formats ms-3">
             { elements }
         </ol>
+        if props.editable {
+            <input type="file" class="d-none" accept=".epub,.pdf,.mobi,.azw,.azw3"
+                ref={ replace_file_ref } onchange={ on_replace_file_changed } />
+            <div class="input-group input-group-sm mb-2">
+                <label class="input-group-text" for="add-book-file">{ "Add format" }</label>
+                <input id="add-book-file" type="file" class="form-control"
+                    accept=".epub,.pdf,.mobi,.azw,.azw3"
+                    ref={ add_file_ref } onchange={ on_add_file_changed } />
+            </div>
+            if let Some(err) = &*file_error {
+                <div class="text-danger">{ format!("Failed to update file: {err:?}") }</div>
+            }
+        }
+        </>
     }
 }
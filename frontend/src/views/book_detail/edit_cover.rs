@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::books::BookWithCover;
+use web_sys::{FormData, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::components::book_cover::BookCover;
+use crate::error::FetchError;
+use crate::services::books::{extract_book_cover, upload_book_cover};
+use crate::services::images::get_cover_url;
+
+#[derive(Debug, Clone, PartialEq, Eq, Properties)]
+pub struct Props {
+    pub book: BookWithCover,
+}
+
+/// Build multipart form with the selected image, and reset file input.
+fn new_cover_form_data(input_ref: &NodeRef) -> Option<FormData> {
+    let input = input_ref.cast::<HtmlInputElement>()?;
+    let file = input.files()?.get(0)?;
+    let form_data = FormData::new().ok()?;
+    form_data
+        .append_with_blob_and_filename("cover", &file, &file.name())
+        .ok()?;
+    input.set_value("");
+    Some(form_data)
+}
+
+#[function_component(EditCoverComponent)]
+pub fn edit_cover(props: &Props) -> Html {
+    let book = use_state(|| props.book.clone());
+    {
+        let book_clone = book.clone();
+        use_effect_with(props.book.clone(), move |new_book| {
+            book_clone.set(new_book.clone());
+            || ()
+        });
+    }
+    let cover_error = use_state(|| None::<FetchError>);
+    let cover_file_ref = use_node_ref();
+
+    let on_cover_file_changed = {
+        let book_id = props.book.id;
+        let cover_file_ref_clone = cover_file_ref.clone();
+        let book_clone = book.clone();
+        let cover_error_clone = cover_error.clone();
+        Callback::from(move |_event: Event| {
+            let Some(form_data) = new_cover_form_data(&cover_file_ref_clone) else {
+                return;
+            };
+            let book_clone = book_clone.clone();
+            let cover_error_clone = cover_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match upload_book_cover(book_id, &form_data).await {
+                    Ok(new_book) => {
+                        cover_error_clone.set(None);
+                        book_clone.set(new_book);
+                    }
+                    Err(err) => cover_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    let on_extract_button_clicked = {
+        let book_id = props.book.id;
+        let book_clone = book.clone();
+        let cover_error_clone = cover_error.clone();
+        Callback::from(move |_event: MouseEvent| {
+            let book_clone = book_clone.clone();
+            let cover_error_clone = cover_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match extract_book_cover(book_id).await {
+                    Ok(new_book) => {
+                        cover_error_clone.set(None);
+                        book_clone.set(new_book);
+                    }
+                    Err(err) => cover_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    // Cover path does not change after it is replaced, add timestamp to skip browser cache.
+    let cover = match book.small_cover.as_deref().map(get_cover_url) {
+        Some(Ok(url)) => {
+            let url = format!("{url}&t={}", book.last_modified.and_utc().timestamp());
+            html! {
+                <img class="book-cover-img" src={ url } alt={ book.title.clone() }
+                    width="135" height="200" />
+            }
+        }
+        _ => html! { <BookCover title={ book.title.clone() } /> },
+    };
+
+    html! {
+        <div class="mt-2 mb-2">
+            { cover }
+            <div class="input-group input-group-sm mt-2">
+                <label class="input-group-text" for="edit-cover-file">{ "Cover" }</label>
+                <input id="edit-cover-file" type="file" class="form-control"
+                    accept="image/jpeg,image/png,image/webp"
+                    ref={ cover_file_ref } onchange={ on_cover_file_changed } />
+                <button type="button" class="btn btn-outline-secondary"
+                    onclick={ on_extract_button_clicked }>
+                    { "Extract from files" }
+                </button>
+            </div>
+            if let Some(err) = &*cover_error {
+                <div class="text-danger">{ format!("Failed to update cover: {err:?}") }</div>
+            }
+        </div>
+    }
+}
@@ -4,9 +4,15 @@
 
 use shared::books_meta::BookMetadata;
 use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::error::FetchError;
+use crate::router::Route;
+use crate::services::books::delete_book;
 
 use super::book_formats::BookFormatsComponent;
 use super::edit_authors::EditAuthorsComponent;
+use super::edit_cover::EditCoverComponent;
 use super::edit_identifiers::EditIdentifiersComponent;
 use super::edit_publisher::EditPublisherComponent;
 use super::edit_series::EditSeriesComponent;
@@ -14,9 +20,8 @@ use super::edit_tags::EditTagsComponent;
 use super::edit_title::EditTitleComponent;
 use super::edit_user_tags::EditUserTagsComponent;
 use super::navigation::NavigationComponent;
-use crate::components::book_cover::BookCover;
 
-#[derive(Debug, Clone, PartialEq, Eq, Properties)]
+#[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
     pub metadata: BookMetadata,
 }
@@ -25,17 +30,39 @@ pub struct Props {
 pub fn edit_metadata_page(props: &Props) -> Html {
     let metadata = &props.metadata;
     let book = &metadata.book;
+    let navigator = use_navigator().expect("Navigator object is invalid");
+    let delete_error = use_state(|| None::<FetchError>);
+
+    let on_delete_button_clicked = {
+        let book_id = book.id;
+        let delete_error_clone = delete_error.clone();
+        Callback::from(move |_event: MouseEvent| {
+            let confirmed = gloo_utils::window()
+                .confirm_with_message("Move this book to trash?")
+                .unwrap_or_default();
+            if !confirmed {
+                return;
+            }
+            let navigator_clone = navigator.clone();
+            let delete_error_clone = delete_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match delete_book(book_id).await {
+                    Ok(()) => navigator_clone.push(&Route::Trash),
+                    Err(err) => delete_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
 
     html! {
         <div class="container">
             <h2>{ &metadata.book.title }</h2>
-            <div class="mt-2 mb-2">
-                <BookCover url={book.small_cover.clone().unwrap_or_default()} title={ book.title.clone() } />
-            </div>
+            <EditCoverComponent book={ book.clone() } />
 
             <div class="mb-2">
                 <h3>{ "Title "}</h3>
-                <EditTitleComponent book_id={ book.id } title={ book.title.clone() } />
+                <EditTitleComponent book_id={ book.id } title={ book.title.clone() }
+                    last_modified={ book.last_modified } />
             </div>
 
             <div class="mb-2">
@@ -70,7 +97,17 @@ pub fn edit_metadata_page(props: &Props) -> Html {
 
             <div class="mb-2">
                 <h3>{ "File Formats" }</h3>
-                <BookFormatsComponent files={ metadata.files.clone() } />
+                <BookFormatsComponent book_id={ book.id } files={ metadata.files.clone() } editable={ true } />
+            </div>
+
+            <div class="mb-2">
+                <button type="button" class="btn btn-outline-danger"
+                    onclick={ on_delete_button_clicked }>
+                    { "Delete book" }
+                </button>
+                if let Some(err) = &*delete_error {
+                    <div class="text-danger">{ format!("Failed to delete book: {err:?}") }</div>
+                }
             </div>
 
             <NavigationComponent previous_book={ metadata.previous_book } next_book={ metadata.next_book } />
@@ -2,53 +2,60 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use shared::books::BookUpdateReq;
+use chrono::NaiveDateTime;
+use shared::books_meta::BookMetadataUpdateReq;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
-use yew_hooks::use_async;
 
-use crate::services::books::update_book;
+use crate::error::{ErrorKind, FetchError};
+use crate::services::books_meta::update_book_metadata;
 
 #[derive(Debug, Clone, PartialEq, Eq, Properties)]
 pub struct Props {
     pub book_id: i32,
     pub title: String,
+    pub last_modified: NaiveDateTime,
 }
 
 #[function_component(EditTitleComponent)]
 pub fn edit_title(props: &Props) -> Html {
     let edit_title_ref = use_node_ref();
-
-    let query = use_state(|| BookUpdateReq {
-        id: props.book_id,
-        title: props.title.clone(),
-    });
-    let update_book_wrapper = {
-        let query_clone = query.clone();
-        // TODO(Shaohua): Show response status.
-        use_async(async move { update_book(&query_clone).await })
-    };
+    let title = use_state(|| props.title.clone());
+    // Updated after each request, so that title can be changed again.
+    let last_modified = use_state(|| props.last_modified);
+    let error = use_state(|| None::<FetchError>);
 
     let on_form_submit = {
+        let book_id = props.book_id;
         let edit_title_ref_clone = edit_title_ref.clone();
+        let error_clone = error.clone();
         Callback::from(move |event: SubmitEvent| {
             event.prevent_default();
-            let mut query_form = (*query).clone();
-
-            let mut metadata_changed = false;
-
-            if let Some(input) = edit_title_ref_clone.cast::<HtmlInputElement>() {
-                let value = input.value();
-                if !value.is_empty() && value != query_form.title {
-                    query_form.title = value;
-                    metadata_changed = true;
-                }
+            let Some(input) = edit_title_ref_clone.cast::<HtmlInputElement>() else {
+                return;
+            };
+            let value = input.value();
+            if value.trim().is_empty() || value == *title {
+                return;
             }
 
-            if metadata_changed {
-                query.set(query_form);
-                update_book_wrapper.run();
-            }
+            let req = BookMetadataUpdateReq {
+                title: Some(value),
+                ..BookMetadataUpdateReq::new(*last_modified)
+            };
+            let title_clone = title.clone();
+            let last_modified_clone = last_modified.clone();
+            let error_clone = error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match update_book_metadata(book_id, &req).await {
+                    Ok(metadata) => {
+                        title_clone.set(metadata.book.title);
+                        last_modified_clone.set(metadata.book.last_modified);
+                        error_clone.set(None);
+                    }
+                    Err(err) => error_clone.set(Some(err)),
+                }
+            });
         })
     };
 
@@ -70,6 +77,19 @@ pub fn edit_title(props: &Props) -> Html {
                     name="title" value={ props.title.clone() } />
                 <button type="submit" class="btn btn-primary">{ "Update" }</button>
             </div>
+            {
+                match &*error {
+                    Some(err) if err.kind() == ErrorKind::Conflict => html! {
+                        <div class="text-danger">
+                            { "Book was modified by others, reload this page and try again" }
+                        </div>
+                    },
+                    Some(err) => html! {
+                        <div class="text-danger">{ format!("Failed to update title: {err:?}") }</div>
+                    },
+                    None => html! {},
+                }
+            }
         </form>
     }
 }
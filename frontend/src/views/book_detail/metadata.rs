@@ -114,7 +114,7 @@ fn generate_categories_element(categories: &[Category]) -> Html {
         .collect::<Html>()
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Properties)]
+#[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
     pub metadata: BookMetadata,
 }
@@ -134,10 +134,13 @@ pub fn metadata_page(props: &Props) -> Html {
         pubdate.date().format("%Y-%m-%d").to_string()
     });
 
+    let series_index = metadata
+        .series_index
+        .map_or_else(String::new, |index| format!(" [{index}]"));
     let series_element = metadata.series.as_ref().map_or_else(|| html!{},
                                                               |series|
                                                                   html! {
-            <Link<Route> to={ Route::BooksOfSeries { series_id: series.id } }>{ &series.name }</Link<Route>>
+            <Link<Route> to={ Route::BooksOfSeries { series_id: series.id } }>{ &series.name }{ series_index }</Link<Route>>
         });
 
     html! {
@@ -190,7 +193,7 @@ pub fn metadata_page(props: &Props) -> Html {
 
             <div class="mt-2">
                 <span class="d-block me-2 fw-bold">{ "File Formats" }</span>
-                <BookFormatsComponent files={ metadata.files.clone() } />
+                <BookFormatsComponent book_id={ book.id } files={ metadata.files.clone() } />
             </div>
 
             <NavigationComponent previous_book={ metadata.previous_book } next_book={ metadata.next_book } />
@@ -5,6 +5,7 @@
 use shared::advanced_search::AdvancedSearchQuery;
 use shared::books_query::GetBooksOrder;
 use shared::page::PageId;
+use shared::saved_searches::SavedQuery;
 use yew::prelude::*;
 use yew_hooks::use_async;
 use yew_router::prelude::{use_location, use_navigator, Link};
@@ -12,6 +13,7 @@ use yew_router::prelude::{use_location, use_navigator, Link};
 use crate::components::book_filter::BookFilterComponent;
 use crate::components::book_list::BookListComponent;
 use crate::components::pagination::PaginationComponent;
+use crate::components::save_search::SaveSearchComponent;
 use crate::router::Route;
 use crate::services::advanced_search::fetch_books_by_advanced_search;
 use crate::views::util;
@@ -77,6 +79,7 @@ pub fn books_of_advanced_search() -> Html {
             html! {
                 <>
                 <h2>{ book_list.page.total }{ " results of search \"" }{ &query_desc }{"\""}</h2>
+                <SaveSearchComponent query={ SavedQuery::Advanced(Box::new(query.clone())) } />
                 <BookFilterComponent onchange={ book_filter_onchange } current_order={ query.order } />
                 <BookListComponent books={ book_list.list.clone() } />
                 <PaginationComponent current_page={ book_list.page.page_num }
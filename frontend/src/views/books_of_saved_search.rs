@@ -0,0 +1,116 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::books_query::GetBooksOrder;
+use shared::page::PageId;
+use shared::saved_searches::GetSavedSearchBooksQuery;
+use yew::prelude::*;
+use yew_hooks::{use_async, use_async_with_options, UseAsyncOptions};
+use yew_router::prelude::{use_location, use_navigator, Link};
+
+use crate::components::book_filter::BookFilterComponent;
+use crate::components::book_list::BookListComponent;
+use crate::components::pagination::PaginationComponent;
+use crate::router::Route;
+use crate::services::saved_searches::{fetch_books_by_saved_search, fetch_saved_search};
+use crate::views::util;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Properties)]
+pub struct Props {
+    pub search_id: i32,
+}
+
+#[function_component(BooksOfSavedSearchComponent)]
+pub fn books_of_saved_search(props: &Props) -> Html {
+    util::set_document_title(&format!("Saved Search: {}", props.search_id));
+
+    let navigator = use_navigator().unwrap();
+    let location = use_location().unwrap();
+    let query = location
+        .query::<GetSavedSearchBooksQuery>()
+        .unwrap_or_default();
+    let book_list = {
+        let search_id = props.search_id;
+        let query_clone = query.clone();
+        use_async(async move { fetch_books_by_saved_search(search_id, &query_clone).await })
+    };
+    {
+        let book_list_clone = book_list.clone();
+        use_effect_with((props.search_id, query.clone()), move |_deps| {
+            book_list_clone.run();
+            || ()
+        });
+    }
+
+    let search_info = {
+        let search_id = props.search_id;
+        use_async_with_options(
+            async move { fetch_saved_search(search_id).await },
+            UseAsyncOptions::enable_auto(),
+        )
+    };
+
+    let title_element = search_info.data.as_ref().map_or_else(
+        || html! {},
+        |search_info| {
+            util::set_document_title(&format!("Saved Search: {}", search_info.name));
+
+            html! {
+                <>
+                <h2>{ format!("Result of saved search \"{}\"", search_info.name) }</h2>
+                <p class="text-muted">{ search_info.query.desc() }</p>
+                </>
+            }
+        },
+    );
+
+    let on_book_filter_change = {
+        let search_id = props.search_id;
+        Callback::from(move |order: GetBooksOrder| {
+            util::scroll_to_top();
+
+            let new_query = GetSavedSearchBooksQuery {
+                order: Some(order),
+                ..query
+            };
+            let ret =
+                navigator.push_with_query(&Route::BooksOfSavedSearch { search_id }, &new_query);
+            debug_assert!(ret.is_ok());
+        })
+    };
+
+    let pagination_link = {
+        let search_id = props.search_id;
+        Callback::from(
+            move |(page_id, classes, title): (PageId, &'static str, String)| -> Html {
+                let new_query = GetSavedSearchBooksQuery {
+                    page: page_id,
+                    ..query
+                };
+                html! {
+                    <Link<Route, GetSavedSearchBooksQuery> to={ Route::BooksOfSavedSearch { search_id } }
+                        query={ Some(new_query) } classes={ classes }>
+                        { title }
+                    </Link<Route, GetSavedSearchBooksQuery>>
+                }
+            },
+        )
+    };
+
+    book_list.data.as_ref().map_or_else(
+        || html! { <h2>{ "Result of saved search" }</h2> },
+        |book_list| {
+            html! {
+                <>
+                { title_element }
+                <BookFilterComponent onchange={ on_book_filter_change } current_order={ query.order.unwrap_or_default() } />
+                <BookListComponent books={ book_list.list.clone() } />
+                <PaginationComponent current_page={ book_list.page.page_num }
+                    total_pages={ book_list.page.total_pages() }
+                    link={ pagination_link } />
+                </>
+            }
+        },
+    )
+}
@@ -2,8 +2,9 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
-use shared::books_query::{GetBooksOrder, GetBooksQuery};
+use shared::books_query::GetBooksOrder;
 use shared::page::PageId;
+use shared::series::GetSeriesBooksQuery;
 use yew::prelude::*;
 use yew_hooks::{use_async, use_async_with_options, UseAsyncOptions};
 use yew_router::prelude::{use_location, use_navigator, Link};
@@ -26,7 +27,7 @@ pub fn books_of_series(props: &Props) -> Html {
 
     let navigator = use_navigator().unwrap();
     let location = use_location().unwrap();
-    let query = location.query::<GetBooksQuery>().unwrap_or_default();
+    let query = location.query::<GetSeriesBooksQuery>().unwrap_or_default();
     let book_list = {
         let series_id = props.series_id;
         let query_clone = query.clone();
@@ -65,7 +66,10 @@ pub fn books_of_series(props: &Props) -> Html {
     let on_book_filter_change = {
         let series_id = props.series_id;
         Callback::from(move |order: GetBooksOrder| {
-            let new_query = GetBooksQuery { order, ..query };
+            let new_query = GetSeriesBooksQuery {
+                order: Some(order),
+                ..query
+            };
             let ret = navigator.push_with_query(&Route::BooksOfSeries { series_id }, &new_query);
             debug_assert!(ret.is_ok());
         })
@@ -75,15 +79,15 @@ pub fn books_of_series(props: &Props) -> Html {
         let series_id = props.series_id;
         Callback::from(
             move |(page_id, classes, title): (PageId, &'static str, String)| -> Html {
-                let new_query = GetBooksQuery {
+                let new_query = GetSeriesBooksQuery {
                     page: page_id,
                     ..query
                 };
                 html! {
-                    <Link<Route, GetBooksQuery> to={ Route::BooksOfSeries { series_id } }
+                    <Link<Route, GetSeriesBooksQuery> to={ Route::BooksOfSeries { series_id } }
                         query={ Some(new_query) } classes={ classes }>
                         { title }
-                    </Link<Route, GetBooksQuery>>
+                    </Link<Route, GetSeriesBooksQuery>>
                 }
             },
         )
@@ -95,7 +99,9 @@ pub fn books_of_series(props: &Props) -> Html {
             html! {
                 <>
                 { title_element }
-                <BookFilterComponent onchange={ on_book_filter_change } current_order={ query.order } />
+                <BookFilterComponent onchange={ on_book_filter_change }
+                    current_order={ query.order.unwrap_or_default() }
+                    relevance_active={ query.order.is_none() } />
                 <BookListComponent books={ book_list.list.clone() } />
                 <PaginationComponent current_page={ book_list.page.page_num }
                     total_pages={ book_list.page.total_pages() }
@@ -4,6 +4,8 @@
 
 use shared::books_query::GetBooksOrder;
 use shared::page::PageId;
+use shared::saved_searches::SavedQuery;
+use shared::search_query;
 use shared::simple_search::SimpleSearchQuery;
 use yew::prelude::*;
 use yew_hooks::use_async;
@@ -12,6 +14,7 @@ use yew_router::prelude::{use_location, use_navigator, Link};
 use crate::components::book_filter::BookFilterComponent;
 use crate::components::book_list::BookListComponent;
 use crate::components::pagination::PaginationComponent;
+use crate::components::save_search::SaveSearchComponent;
 use crate::router::Route;
 use crate::services::simple_search::fetch_books_by_simple_search;
 use crate::views::util;
@@ -39,9 +42,22 @@ pub fn books_of_simple_search() -> Html {
 
     let on_book_filter_change = {
         let query_clone = query.clone();
+        let navigator_clone = navigator.clone();
         Callback::from(move |order: GetBooksOrder| {
             let new_query = SimpleSearchQuery {
-                order,
+                order: Some(order),
+                ..query_clone.clone()
+            };
+            let ret = navigator_clone.push_with_query(&Route::BooksOfSimpleSearch, &new_query);
+            debug_assert!(ret.is_ok());
+        })
+    };
+
+    let on_relevance = {
+        let query_clone = query.clone();
+        Callback::from(move |()| {
+            let new_query = SimpleSearchQuery {
+                order: None,
                 ..query_clone.clone()
             };
             let ret = navigator.push_with_query(&Route::BooksOfSimpleSearch, &new_query);
@@ -71,14 +87,23 @@ pub fn books_of_simple_search() -> Html {
     book_list.data.as_ref().map_or_else(
         || {
             html! {
+                <>
                 <h2>{ "Result for search \"" }{ &keyword }{ "\""}</h2>
+                if let Err(err) = search_query::parse(keyword) {
+                    <div class="text-danger mb-2">{ format!("Invalid query: {err}") }</div>
+                }
+                </>
             }
         },
         |book_list| {
             html! {
                 <>
                 <h2>{ book_list.page.total }{ " results of search \""}{ &keyword }{"\""}</h2>
-                <BookFilterComponent onchange={ on_book_filter_change } current_order={ query.order } />
+                <SaveSearchComponent query={ SavedQuery::Simple(keyword.clone()) } />
+                <BookFilterComponent onchange={ on_book_filter_change }
+                    current_order={ query.order.unwrap_or_default() }
+                    on_relevance={ on_relevance }
+                    relevance_active={ query.order.is_none() } />
                 <BookListComponent books={ book_list.list.clone() } />
                 <PaginationComponent current_page={ book_list.page.page_num }
                     total_pages={ book_list.page.total_pages() }
@@ -0,0 +1,276 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::import::{ImportJob, ImportJobKind, NewImportJobReq};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{EventSource, HtmlInputElement, HtmlSelectElement, MessageEvent};
+use yew::prelude::*;
+use yew_hooks::{use_async_with_options, UseAsyncOptions};
+
+use crate::error::FetchError;
+use crate::services::import_jobs::{
+    add_import_job, cancel_import_job, fetch_import_jobs, import_job_events_url,
+};
+use crate::views::util;
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct JobItemProps {
+    pub job: ImportJob,
+}
+
+type EventHandler = Closure<dyn FnMut(MessageEvent)>;
+
+/// Subscribe progress events of job, until it is stopped.
+fn watch_job_events(
+    job_id: i32,
+    job: UseStateHandle<ImportJob>,
+) -> Option<(EventSource, EventHandler)> {
+    let source = EventSource::new(&import_job_events_url(job_id)).ok()?;
+    let source_clone = source.clone();
+    let on_message = EventHandler::new(move |event: MessageEvent| {
+        let Some(data) = event.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<ImportJob>(&data) {
+            Ok(new_job) => {
+                // Or else browser connects to server again.
+                if new_job.status.is_done() {
+                    source_clone.close();
+                }
+                job.set(new_job);
+            }
+            Err(err) => log::warn!("Invalid import job event: {err:?}"),
+        }
+    });
+    source.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    Some((source, on_message))
+}
+
+/// Show progress of import job, which is updated with server-sent events.
+#[function_component(ImportJobItemComponent)]
+pub fn import_job_item(props: &JobItemProps) -> Html {
+    let job = use_state(|| props.job.clone());
+    {
+        let job_clone = job.clone();
+        use_effect_with(props.job.id, move |job_id| {
+            let watcher = if job_clone.status.is_done() {
+                None
+            } else {
+                watch_job_events(*job_id, job_clone)
+            };
+
+            move || {
+                if let Some((source, _on_message)) = watcher {
+                    source.close();
+                }
+            }
+        });
+    }
+
+    let on_cancel_button_clicked = {
+        let job_id = props.job.id;
+        Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(err) = cancel_import_job(job_id).await {
+                    log::warn!("Failed to cancel import job {job_id}, err: {err:?}");
+                }
+            });
+        })
+    };
+
+    #[allow(clippy::cast_precision_loss)]
+    let percent = if job.total > 0 {
+        (job.done as f64 * 100.0 / job.total as f64).min(100.0)
+    } else {
+        0.0
+    };
+
+    html! {
+        <tr>
+            <td>{ job.id }</td>
+            <td>{ job.kind.to_string() }</td>
+            <td class="text-break">{ &job.path }</td>
+            <td>{ job.status.to_string() }</td>
+            <td>
+                <div class="progress" role="progressbar">
+                    <div class="progress-bar" style={ format!("width: {percent:.0}%") }></div>
+                </div>
+                <small>
+                    { format!("{} / {}, failed: {}", job.done, job.total, job.failed) }
+                </small>
+                if let Some(message) = &job.message {
+                    <div class="text-danger"><small>{ message }</small></div>
+                }
+            </td>
+            <td>
+                if !job.status.is_done() {
+                    <button type="button" class="btn btn-sm btn-outline-danger"
+                        onclick={ on_cancel_button_clicked }>
+                        { "Cancel" }
+                    </button>
+                }
+            </td>
+        </tr>
+    }
+}
+
+#[function_component(ImportJobsComponent)]
+pub fn import_jobs_page() -> Html {
+    util::set_document_title("Import");
+
+    let job_list = use_async_with_options(
+        async move { fetch_import_jobs().await },
+        UseAsyncOptions::enable_auto(),
+    );
+
+    let kind_ref = use_node_ref();
+    let path_ref = use_node_ref();
+    let move_files_ref = use_node_ref();
+    let allow_duplication_ref = use_node_ref();
+    let jobs_ref = use_node_ref();
+    let uid_ref = use_node_ref();
+    let gid_ref = use_node_ref();
+
+    let add_job_error = use_state(|| None::<FetchError>);
+
+    let on_form_submit = {
+        let kind_ref_clone = kind_ref.clone();
+        let path_ref_clone = path_ref.clone();
+        let move_files_ref_clone = move_files_ref.clone();
+        let allow_duplication_ref_clone = allow_duplication_ref.clone();
+        let jobs_ref_clone = jobs_ref.clone();
+        let uid_ref_clone = uid_ref.clone();
+        let gid_ref_clone = gid_ref.clone();
+        let job_list_clone = job_list.clone();
+        let add_job_error_clone = add_job_error.clone();
+
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let mut form = NewImportJobReq::default();
+            if let Some(select) = kind_ref_clone.cast::<HtmlSelectElement>() {
+                if select.value() == "folder" {
+                    form.kind = ImportJobKind::Folder;
+                }
+            }
+            if let Some(input) = path_ref_clone.cast::<HtmlInputElement>() {
+                form.path = input.value().trim().to_owned();
+            }
+            if let Some(input) = move_files_ref_clone.cast::<HtmlInputElement>() {
+                form.move_files = input.checked();
+            }
+            if let Some(input) = allow_duplication_ref_clone.cast::<HtmlInputElement>() {
+                form.allow_duplication = input.checked();
+            }
+            if let Some(input) = jobs_ref_clone.cast::<HtmlInputElement>() {
+                form.jobs = input.value().parse().unwrap_or(1);
+            }
+            if let Some(input) = uid_ref_clone.cast::<HtmlInputElement>() {
+                form.uid = input.value().trim().parse().ok();
+            }
+            if let Some(input) = gid_ref_clone.cast::<HtmlInputElement>() {
+                form.gid = input.value().trim().parse().ok();
+            }
+            if form.path.is_empty() {
+                return;
+            }
+
+            let job_list_clone = job_list_clone.clone();
+            let add_job_error_clone = add_job_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match add_import_job(&form).await {
+                    Ok(_job) => {
+                        add_job_error_clone.set(None);
+                        job_list_clone.run();
+                    }
+                    Err(err) => add_job_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    html! {
+        <>
+        <h2>{ "Import" }</h2>
+
+        <form class="mb-4" onsubmit={ on_form_submit }>
+            <div class="mb-2">
+                <label for="import-kind" class="form-label">{ "Source" }</label>
+                <select id="import-kind" class="form-select" ref={ kind_ref }>
+                    <option value="calibre" selected={ true }>{ "Calibre library" }</option>
+                    <option value="folder">{ "Folder of ebook files" }</option>
+                </select>
+            </div>
+
+            <div class="mb-2">
+                <label for="import-path" class="form-label">{ "Path on server" }</label>
+                <input id="import-path" class="form-control" type="text" ref={ path_ref } />
+            </div>
+
+            <div class="mb-2">
+                <label for="import-jobs" class="form-label">{ "Worker threads" }</label>
+                <input id="import-jobs" class="form-control" type="number"
+                    min="1" max="16" value="1" ref={ jobs_ref } />
+            </div>
+
+            <div class="row mb-2">
+                <div class="col">
+                    <label for="import-uid" class="form-label">{ "Owner uid of files" }</label>
+                    <input id="import-uid" class="form-control" type="number" min="0"
+                        ref={ uid_ref } />
+                </div>
+                <div class="col">
+                    <label for="import-gid" class="form-label">{ "Owner gid of files" }</label>
+                    <input id="import-gid" class="form-control" type="number" min="0"
+                        ref={ gid_ref } />
+                </div>
+            </div>
+
+            <div class="form-check">
+                <input id="import-move-files" class="form-check-input" type="checkbox"
+                    ref={ move_files_ref } />
+                <label for="import-move-files" class="form-check-label">
+                    { "Move files instead of copy" }
+                </label>
+            </div>
+
+            <div class="form-check mb-2">
+                <input id="import-allow-duplication" class="form-check-input" type="checkbox"
+                    ref={ allow_duplication_ref } />
+                <label for="import-allow-duplication" class="form-check-label">
+                    { "Import books which already exist" }
+                </label>
+            </div>
+
+            if let Some(err) = &*add_job_error {
+                <div class="text-danger mb-2">{ format!("Failed to start import: {err:?}") }</div>
+            }
+
+            <button class="btn btn-primary" type="submit">{ "Start" }</button>
+        </form>
+
+        if let Some(job_list) = &job_list.data {
+            <table class="table">
+                <thead>
+                    <tr>
+                        <th>{ "Id" }</th>
+                        <th>{ "Source" }</th>
+                        <th>{ "Path" }</th>
+                        <th>{ "Status" }</th>
+                        <th>{ "Progress" }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                {for job_list.iter().map(|job| html! {
+                    <ImportJobItemComponent key={ job.id } job={ job.clone() } />
+                })}
+                </tbody>
+            </table>
+        }
+        </>
+    }
+}
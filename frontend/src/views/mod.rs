@@ -13,17 +13,22 @@ pub mod books_of_discover;
 pub mod books_of_download_history;
 pub mod books_of_file_format;
 pub mod books_of_publisher;
+pub mod books_of_saved_search;
 pub mod books_of_series;
 pub mod books_of_simple_search;
 pub mod books_of_tag;
 pub mod books_of_user_tag;
 pub mod categories;
 pub mod file_formats;
+pub mod import_jobs;
 pub mod login;
 pub mod logout;
 pub mod publishers;
+pub mod saved_searches;
 pub mod series;
 pub mod tags;
+pub mod trash;
+pub mod upload_book;
 pub mod user_info;
 pub mod user_tags;
 pub mod users;
@@ -0,0 +1,101 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::saved_searches::SavedSearch;
+use yew::prelude::*;
+use yew_hooks::{use_async_with_options, UseAsyncHandle, UseAsyncOptions};
+use yew_router::prelude::Link;
+
+use crate::error::FetchError;
+use crate::router::Route;
+use crate::services::saved_searches::{delete_saved_search, fetch_saved_searches};
+use crate::views::util;
+
+/// Delete saved search, then reload search list.
+fn new_delete_callback(
+    search: &SavedSearch,
+    search_list: &UseAsyncHandle<Vec<SavedSearch>, FetchError>,
+) -> Callback<MouseEvent> {
+    let search_id = search.id;
+    let message = format!("Delete saved search {}?", search.name);
+    let search_list = search_list.clone();
+    Callback::from(move |_event: MouseEvent| {
+        let confirmed = gloo_utils::window()
+            .confirm_with_message(&message)
+            .unwrap_or_default();
+        if !confirmed {
+            return;
+        }
+        let search_list = search_list.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err) = delete_saved_search(search_id).await {
+                log::warn!("Failed to delete saved search {search_id}, err: {err:?}");
+            }
+            search_list.run();
+        });
+    })
+}
+
+#[function_component(SavedSearchesComponent)]
+pub fn saved_searches_page() -> Html {
+    util::set_document_title("Saved Searches");
+
+    let search_list = use_async_with_options(
+        async move { fetch_saved_searches().await },
+        UseAsyncOptions::enable_auto(),
+    );
+
+    let rows = search_list.data.as_ref().map_or_else(
+        || html! {},
+        |searches| {
+            searches
+                .iter()
+                .map(|search| {
+                    let on_delete_clicked = new_delete_callback(search, &search_list);
+                    html! {
+                        <tr key={ search.id }>
+                            <td>
+                                <Link<Route> to={ Route::BooksOfSavedSearch { search_id: search.id } }>
+                                    { &search.name }
+                                </Link<Route>>
+                            </td>
+                            <td class="text-muted">{ search.query.desc() }</td>
+                            <td>
+                                <button type="button" class="btn btn-sm btn-outline-danger"
+                                    onclick={ on_delete_clicked }>
+                                    { "Delete" }
+                                </button>
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>()
+        },
+    );
+
+    html! {
+        <>
+        <h2>{ "Saved Searches" }</h2>
+
+        if let Some(searches) = &search_list.data {
+            if searches.is_empty() {
+                <p>{ "No saved searches, save one from results of search or advanced search." }</p>
+            } else {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>{ "Name" }</th>
+                            <th>{ "Query" }</th>
+                            <th>{ "Actions" }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { rows }
+                    </tbody>
+                </table>
+            }
+        }
+        </>
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::trash::TrashBook;
+use yew::prelude::*;
+use yew_hooks::{use_async_with_options, UseAsyncHandle, UseAsyncOptions};
+use yew_router::prelude::Link;
+
+use crate::error::FetchError;
+use crate::router::Route;
+use crate::services::trash::{fetch_trash_books, purge_trash_book, restore_trash_book};
+use crate::views::util;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrashAction {
+    Restore,
+    Purge,
+}
+
+/// Restore or purge book, then reload book list.
+fn new_action_callback(
+    book: &TrashBook,
+    action: TrashAction,
+    book_list: &UseAsyncHandle<Vec<TrashBook>, FetchError>,
+    action_error: &UseStateHandle<Option<FetchError>>,
+) -> Callback<MouseEvent> {
+    let book_id = book.book;
+    let title = book.title.clone();
+    let book_list = book_list.clone();
+    let action_error = action_error.clone();
+    Callback::from(move |_event: MouseEvent| {
+        if action == TrashAction::Purge {
+            let message = format!("Delete {title} permanently? Its files can not be recovered.");
+            let confirmed = gloo_utils::window()
+                .confirm_with_message(&message)
+                .unwrap_or_default();
+            if !confirmed {
+                return;
+            }
+        }
+        let book_list = book_list.clone();
+        let action_error = action_error.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let ret = match action {
+                TrashAction::Restore => restore_trash_book(book_id).await,
+                TrashAction::Purge => purge_trash_book(book_id).await,
+            };
+            match ret {
+                Ok(()) => {
+                    action_error.set(None);
+                    book_list.run();
+                }
+                Err(err) => action_error.set(Some(err)),
+            }
+        });
+    })
+}
+
+#[function_component(TrashComponent)]
+pub fn trash_page() -> Html {
+    util::set_document_title("Trash");
+
+    let book_list = use_async_with_options(
+        async move { fetch_trash_books().await },
+        UseAsyncOptions::enable_auto(),
+    );
+    let action_error = use_state(|| None::<FetchError>);
+
+    let rows = book_list.data.as_ref().map_or_else(
+        || html! {},
+        |books| {
+            books
+                .iter()
+                .map(|book| {
+                    let on_restore_clicked =
+                        new_action_callback(book, TrashAction::Restore, &book_list, &action_error);
+                    let on_purge_clicked =
+                        new_action_callback(book, TrashAction::Purge, &book_list, &action_error);
+                    html! {
+                        <tr key={ book.id }>
+                            <td>{ book.book }</td>
+                            <td>{ &book.title }</td>
+                            <td>{ book.created.format("%Y-%m-%d %H:%M").to_string() }</td>
+                            <td>
+                                <button type="button" class="btn btn-sm btn-outline-primary me-2"
+                                    onclick={ on_restore_clicked }>
+                                    { "Restore" }
+                                </button>
+                                <button type="button" class="btn btn-sm btn-outline-danger"
+                                    onclick={ on_purge_clicked }>
+                                    { "Delete permanently" }
+                                </button>
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>()
+        },
+    );
+
+    html! {
+        <>
+        <h2>{ "Trash" }</h2>
+        <p class="text-muted">
+            { "Deleted books are kept here for a while, and then purged automatically." }
+        </p>
+
+        if let Some(err) = &*action_error {
+            <div class="text-danger mb-2">{ format!("Failed to update trash: {err:?}") }</div>
+        }
+
+        if let Some(books) = &book_list.data {
+            if books.is_empty() {
+                <p>{ "Trash is empty." }</p>
+            } else {
+                <table class="table">
+                    <thead>
+                        <tr>
+                            <th>{ "Id" }</th>
+                            <th>{ "Title" }</th>
+                            <th>{ "Deleted" }</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { rows }
+                    </tbody>
+                </table>
+            }
+        }
+        if let Some(err) = &book_list.error {
+            <div class="text-danger">{ format!("Failed to get trash: {err:?}") }</div>
+        }
+        <Link<Route> to={ Route::Book } classes="btn btn-link ps-0">{ "Back to books" }</Link<Route>>
+        </>
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use shared::books::UploadBookReq;
+use web_sys::{FormData, HtmlInputElement};
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::error::{ErrorKind, FetchError};
+use crate::router::Route;
+use crate::services::books::upload_book;
+use crate::views::util;
+
+/// Read value of text input, returns None if it is empty.
+fn get_input_value(node_ref: &NodeRef) -> Option<String> {
+    node_ref
+        .cast::<HtmlInputElement>()
+        .map(|input| input.value().trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// Build multipart form with selected files and metadata.
+fn new_form_data(files_ref: &NodeRef, req: &UploadBookReq) -> Result<Option<FormData>, FetchError> {
+    let Some(file_list) = files_ref
+        .cast::<HtmlInputElement>()
+        .and_then(|input| input.files())
+    else {
+        return Ok(None);
+    };
+    if file_list.length() == 0 {
+        return Ok(None);
+    }
+
+    let form_data = FormData::new()?;
+    for index in 0..file_list.length() {
+        if let Some(file) = file_list.get(index) {
+            form_data.append_with_blob_and_filename("file", &file, &file.name())?;
+        }
+    }
+    let metadata = serde_json::to_string(req)?;
+    form_data.append_with_str("metadata", &metadata)?;
+    Ok(Some(form_data))
+}
+
+#[function_component(UploadBookComponent)]
+pub fn upload_book_page() -> Html {
+    util::set_document_title("Upload");
+
+    let navigator = use_navigator().expect("Navigator object is invalid");
+    let files_ref = use_node_ref();
+    let title_ref = use_node_ref();
+    let authors_ref = use_node_ref();
+    let publisher_ref = use_node_ref();
+    let isbn_ref = use_node_ref();
+    let language_ref = use_node_ref();
+    let uploading = use_state(|| false);
+    let upload_error = use_state(|| None::<FetchError>);
+
+    let on_form_submit = {
+        let files_ref_clone = files_ref.clone();
+        let title_ref_clone = title_ref.clone();
+        let authors_ref_clone = authors_ref.clone();
+        let publisher_ref_clone = publisher_ref.clone();
+        let isbn_ref_clone = isbn_ref.clone();
+        let language_ref_clone = language_ref.clone();
+        let uploading_clone = uploading.clone();
+        let upload_error_clone = upload_error.clone();
+
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            if *uploading_clone {
+                return;
+            }
+
+            let req = UploadBookReq {
+                title: get_input_value(&title_ref_clone),
+                authors: get_input_value(&authors_ref_clone)
+                    .map(|authors| {
+                        authors
+                            .split([',', ';', '&'])
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(ToOwned::to_owned)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                publisher: get_input_value(&publisher_ref_clone),
+                isbn: get_input_value(&isbn_ref_clone),
+                language: get_input_value(&language_ref_clone),
+            };
+            let form_data = match new_form_data(&files_ref_clone, &req) {
+                Ok(Some(form_data)) => form_data,
+                Ok(None) => {
+                    upload_error_clone.set(Some(FetchError::from_string(
+                        ErrorKind::BadRequest,
+                        "No file is selected".to_owned(),
+                    )));
+                    return;
+                }
+                Err(err) => {
+                    upload_error_clone.set(Some(err));
+                    return;
+                }
+            };
+
+            uploading_clone.set(true);
+            let navigator_clone = navigator.clone();
+            let uploading_clone = uploading_clone.clone();
+            let upload_error_clone = upload_error_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let ret = upload_book(&form_data).await;
+                uploading_clone.set(false);
+                match ret {
+                    Ok(resp) => navigator_clone.push(&Route::BookDetail { book_id: resp.id }),
+                    Err(err) => upload_error_clone.set(Some(err)),
+                }
+            });
+        })
+    };
+
+    html! {
+        <>
+        <h2>{ "Upload book" }</h2>
+
+        <form onsubmit={ on_form_submit }>
+            <div class="mb-2">
+                <label for="upload-files" class="form-label">{ "Ebook files" }</label>
+                <input id="upload-files" class="form-control" type="file" multiple={ true }
+                    accept=".epub,.pdf,.mobi,.azw,.azw3" ref={ files_ref } />
+                <div class="form-text">
+                    { "Select one file of each format. Empty fields below are read from these files." }
+                </div>
+            </div>
+
+            <div class="mb-2">
+                <label for="upload-title" class="form-label">{ "Title" }</label>
+                <input id="upload-title" class="form-control" type="text" ref={ title_ref } />
+            </div>
+
+            <div class="mb-2">
+                <label for="upload-authors" class="form-label">{ "Authors" }</label>
+                <input id="upload-authors" class="form-control" type="text"
+                    placeholder="Separated by comma" ref={ authors_ref } />
+            </div>
+
+            <div class="mb-2">
+                <label for="upload-publisher" class="form-label">{ "Publisher" }</label>
+                <input id="upload-publisher" class="form-control" type="text"
+                    ref={ publisher_ref } />
+            </div>
+
+            <div class="mb-2">
+                <label for="upload-isbn" class="form-label">{ "ISBN" }</label>
+                <input id="upload-isbn" class="form-control" type="text" ref={ isbn_ref } />
+            </div>
+
+            <div class="mb-2">
+                <label for="upload-language" class="form-label">{ "Language" }</label>
+                <input id="upload-language" class="form-control" type="text"
+                    placeholder="Like en or zh-CN" ref={ language_ref } />
+            </div>
+
+            if let Some(err) = &*upload_error {
+                <div class="text-danger mb-2">{ format!("Failed to upload: {err:?}") }</div>
+            }
+
+            <button class="btn btn-primary" type="submit" disabled={ *uploading }>
+                if *uploading {
+                    { "Uploading..." }
+                } else {
+                    { "Upload" }
+                }
+            </button>
+        </form>
+        </>
+    }
+}
@@ -2,22 +2,58 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::books_query::GetBooksOrder;
 use crate::page::{default_page_id, PageId};
 
+/// How conditions of advanced search are combined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchMode {
+    /// Books shall match all of conditions.
+    #[default]
+    All,
+
+    /// Books shall match any of conditions.
+    Any,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AdvancedSearchQuery {
     #[serde(default = "default_page_id")]
     pub page: PageId,
     #[serde(default = "GetBooksOrder::default")]
     pub order: GetBooksOrder,
+    #[serde(default)]
+    pub match_mode: MatchMode,
 
     pub title: Option<String>,
     pub author: Option<String>,
     pub publisher: Option<String>,
     pub isbn: Option<String>,
+    pub tag: Option<String>,
+    pub series: Option<String>,
+
+    /// Serial number of CLC category, books in its sub categories are included.
+    pub category: Option<String>,
+
+    /// Language code, like `eng` or `zho`.
+    pub language: Option<String>,
+
+    /// File format name, like `EPUB`.
+    pub format: Option<String>,
+
+    /// Rating range, both ends are included.
+    pub rating_min: Option<i32>,
+    pub rating_max: Option<i32>,
+
+    /// Publishing date range, both ends are included.
+    pub pubdate_from: Option<NaiveDate>,
+    pub pubdate_to: Option<NaiveDate>,
+
+    /// Name of user tag of current user.
+    pub user_tag: Option<String>,
 }
 
 impl Default for AdvancedSearchQuery {
@@ -25,10 +61,21 @@ impl Default for AdvancedSearchQuery {
         Self {
             page: default_page_id(),
             order: GetBooksOrder::default(),
+            match_mode: MatchMode::default(),
             title: None,
             author: None,
             publisher: None,
             isbn: None,
+            tag: None,
+            series: None,
+            category: None,
+            language: None,
+            format: None,
+            rating_min: None,
+            rating_max: None,
+            pubdate_from: None,
+            pubdate_to: None,
+            user_tag: None,
         }
     }
 }
@@ -36,20 +83,30 @@ impl Default for AdvancedSearchQuery {
 impl AdvancedSearchQuery {
     #[must_use]
     pub fn desc(&self) -> String {
-        // TODO(Shaohua): Concat query fields.
-        if let Some(title) = &self.title {
-            return title.to_string();
-        }
-        if let Some(author) = &self.author {
-            return author.to_string();
-        }
-        if let Some(publisher) = &self.publisher {
-            return publisher.to_string();
-        }
-        if let Some(isbn) = &self.isbn {
-            return isbn.to_string();
-        }
-
-        String::new()
+        let fields = [
+            ("title:", self.title.clone()),
+            ("author:", self.author.clone()),
+            ("publisher:", self.publisher.clone()),
+            ("isbn:", self.isbn.clone()),
+            ("tag:", self.tag.clone()),
+            ("series:", self.series.clone()),
+            ("category:", self.category.clone()),
+            ("language:", self.language.clone()),
+            ("format:", self.format.clone()),
+            ("rating >=", self.rating_min.map(|v| v.to_string())),
+            ("rating <=", self.rating_max.map(|v| v.to_string())),
+            ("pubdate >=", self.pubdate_from.map(|v| v.to_string())),
+            ("pubdate <=", self.pubdate_to.map(|v| v.to_string())),
+            ("user tag:", self.user_tag.clone()),
+        ];
+        let sep = match self.match_mode {
+            MatchMode::All => ", ",
+            MatchMode::Any => " or ",
+        };
+        fields
+            .iter()
+            .filter_map(|(name, value)| value.as_ref().map(|value| format!("{name} {value}")))
+            .collect::<Vec<_>>()
+            .join(sep)
     }
 }
@@ -28,6 +28,8 @@ pub struct BookWithCover {
     pub large_cover: Option<String>,
     pub pubdate: Option<NaiveDateTime>,
     pub created: NaiveDateTime,
+    pub last_modified: NaiveDateTime,
+    pub title_sort: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,3 +60,22 @@ pub struct BookUpdateReq {
     pub id: i32,
     pub title: String,
 }
+
+/// Metadata of uploaded book.
+///
+/// Fields not set here are read from metadata embedded in uploaded files.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadBookReq {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    pub isbn: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadBookResp {
+    pub id: i32,
+    pub title: String,
+}
@@ -2,13 +2,15 @@
 // Use of this source is governed by GNU General Public License
 // that can be found in the LICENSE file.
 
+use chrono::NaiveDateTime;
 #[cfg(feature = "diesel")]
 use diesel::Queryable;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::authors::Author;
 use crate::books::BookWithCover;
 use crate::categories::Category;
+use crate::custom_fields::BookCustomField;
 use crate::files::FileWithPath;
 use crate::identifiers::IdentifierWithType;
 use crate::languages::Language;
@@ -18,7 +20,7 @@ use crate::series::Series;
 use crate::tags::Tag;
 use crate::user_tags::UserTag;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "diesel", derive(Queryable))]
 pub struct BookMetadata {
     pub book: BookWithCover,
@@ -28,10 +30,147 @@ pub struct BookMetadata {
     pub files: Vec<FileWithPath>,
     pub publisher: Option<Publisher>,
     pub series: Option<Series>,
+
+    /// Position of this book in `series`.
+    pub series_index: Option<f32>,
     pub categories: Vec<Category>,
     pub identifiers: Vec<IdentifierWithType>,
     pub lang: Option<Language>,
     pub rating: Option<Rating>,
+    pub custom_fields: Vec<BookCustomField>,
     pub previous_book: Option<i32>,
     pub next_book: Option<i32>,
 }
+
+/// Identifier of book in metadata update request.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookIdentifierReq {
+    /// Id of identifier type.
+    pub scheme: i32,
+    pub value: String,
+    pub url: Option<String>,
+}
+
+/// Series of book in metadata update request.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookSeriesReq {
+    /// Name of series.
+    pub name: String,
+
+    /// Position of book in series.
+    pub index: f32,
+}
+
+/// Update metadata of book in one request.
+///
+/// Fields not set are left unchanged. Nullable fields set to `null` are
+/// removed from book.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookMetadataUpdateReq {
+    /// `last_modified` of book when it was loaded.
+    ///
+    /// Update is rejected if book was modified after that.
+    pub last_modified: NaiveDateTime,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Sort key of title, generated from `title` if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_sort: Option<String>,
+
+    /// Names of authors, in order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub pubdate: Option<Option<NaiveDateTime>>,
+
+    /// Name of publisher.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub publisher: Option<Option<String>>,
+
+    /// Language code, like `eng`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub lang: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub rating: Option<Option<i32>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub comment: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identifiers: Option<Vec<BookIdentifierReq>>,
+
+    /// Ids of tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<i32>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_some"
+    )]
+    pub series: Option<Option<BookSeriesReq>>,
+
+    /// Ids of categories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<i32>>,
+
+    /// Ids of tags of current user, tags of other users are not touched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_tags: Option<Vec<i32>>,
+}
+
+impl BookMetadataUpdateReq {
+    /// Request which changes nothing but checks `last_modified`.
+    #[must_use]
+    pub const fn new(last_modified: NaiveDateTime) -> Self {
+        Self {
+            last_modified,
+            title: None,
+            title_sort: None,
+            authors: None,
+            pubdate: None,
+            publisher: None,
+            lang: None,
+            rating: None,
+            comment: None,
+            identifiers: None,
+            tags: None,
+            series: None,
+            categories: None,
+            user_tags: None,
+        }
+    }
+}
+
+/// Deserialize present field as `Some`, so that `null` and missing field can be told apart.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+#[cfg(feature = "diesel")]
+use diesel::Queryable;
+use serde::{Deserialize, Serialize};
+
+/// Data types of custom field.
+///
+/// Names are the same as calibre custom column datatypes, with `json` added
+/// for calibre plugin data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Comments,
+    Series,
+    Enumeration,
+    Int,
+    Float,
+    Bool,
+    Datetime,
+    Rating,
+    Json,
+}
+
+impl CustomFieldType {
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Self::Text),
+            "comments" => Some(Self::Comments),
+            "series" => Some(Self::Series),
+            "enumeration" => Some(Self::Enumeration),
+            "int" => Some(Self::Int),
+            "float" => Some(Self::Float),
+            "bool" => Some(Self::Bool),
+            "datetime" => Some(Self::Datetime),
+            "rating" => Some(Self::Rating),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Comments => "comments",
+            Self::Series => "series",
+            Self::Enumeration => "enumeration",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Datetime => "datetime",
+            Self::Rating => "rating",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "diesel", derive(Queryable))]
+pub struct CustomField {
+    pub id: i32,
+    pub label: String,
+    pub name: String,
+    pub datatype: String,
+    pub is_multiple: bool,
+    pub display: String,
+    pub created: NaiveDateTime,
+    pub last_modified: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CustomValue {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Datetime(NaiveDateTime),
+}
+
+/// Values of a custom field of book.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookCustomField {
+    pub field: i32,
+    pub label: String,
+    pub name: String,
+    pub datatype: String,
+    pub values: Vec<CustomValue>,
+}
@@ -17,6 +17,9 @@ pub struct File {
     pub name: String,
     pub created: NaiveDateTime,
     pub last_modified: NaiveDateTime,
+
+    /// Sha256 checksum of file content, in lowercase hex.
+    pub sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -29,6 +32,7 @@ pub struct FileWithPath {
     pub format_name: String,
     pub name: String,
     pub path: String,
+    pub sha256: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -38,3 +42,10 @@ pub struct FileQuery {
     pub format: i32,
     pub path: String,
 }
+
+/// Files with the same content, attached to different books.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateFiles {
+    pub sha256: String,
+    pub files: Vec<FileWithPath>,
+}
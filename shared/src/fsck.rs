@@ -0,0 +1,66 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsckIssueKind {
+    /// File record points to a file which does not exist in library.
+    MissingFile,
+
+    /// Size of file in library differs from its file record.
+    SizeMismatch,
+
+    /// Directory in library which does not belong to any book.
+    OrphanDir,
+
+    /// Book has `has_cover` set but `cover.webp` is missing.
+    MissingCover,
+
+    /// `cover.webp` exists but `has_cover` of book is not set.
+    UnknownCover,
+
+    /// Row in link table refers to a book which does not exist.
+    DanglingLink,
+}
+
+/// A problem found in library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsckIssue {
+    pub kind: FsckIssueKind,
+
+    /// Id of book, `None` for orphan directories.
+    pub book: Option<i32>,
+
+    /// Path relative to library root, or name of link table.
+    pub path: String,
+    pub message: String,
+
+    /// Whether this issue is repaired in fix mode.
+    pub fixed: bool,
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}, {}", self.kind, self.path, self.message)?;
+        if let Some(book_id) = self.book {
+            write!(f, ", book: {book_id}")?;
+        }
+        if self.fixed {
+            write!(f, " (fixed)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsckReport {
+    /// Number of books checked.
+    pub books: usize,
+
+    /// Number of files checked.
+    pub files: usize,
+    pub issues: Vec<FsckIssue>,
+}
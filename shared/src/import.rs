@@ -0,0 +1,141 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportJobKind {
+    /// Import calibre library.
+    #[default]
+    Calibre,
+
+    /// Import ebook files in folder, with their embedded metadata.
+    Folder,
+}
+
+impl From<i32> for ImportJobKind {
+    fn from(kind: i32) -> Self {
+        match kind {
+            1 => Self::Folder,
+            _ => Self::Calibre,
+        }
+    }
+}
+
+impl From<ImportJobKind> for i32 {
+    fn from(kind: ImportJobKind) -> Self {
+        match kind {
+            ImportJobKind::Calibre => 0,
+            ImportJobKind::Folder => 1,
+        }
+    }
+}
+
+impl fmt::Display for ImportJobKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Calibre => "Calibre Library",
+            Self::Folder => "Folder",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportJobStatus {
+    #[default]
+    Pending,
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl ImportJobStatus {
+    /// Check whether job is stopped.
+    #[must_use]
+    pub const fn is_done(self) -> bool {
+        matches!(self, Self::Finished | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl From<i32> for ImportJobStatus {
+    fn from(status: i32) -> Self {
+        match status {
+            1 => Self::Running,
+            2 => Self::Finished,
+            3 => Self::Failed,
+            4 => Self::Cancelled,
+            _ => Self::Pending,
+        }
+    }
+}
+
+impl From<ImportJobStatus> for i32 {
+    fn from(status: ImportJobStatus) -> Self {
+        match status {
+            ImportJobStatus::Pending => 0,
+            ImportJobStatus::Running => 1,
+            ImportJobStatus::Finished => 2,
+            ImportJobStatus::Failed => 3,
+            ImportJobStatus::Cancelled => 4,
+        }
+    }
+}
+
+impl fmt::Display for ImportJobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Pending => "Pending",
+            Self::Running => "Running",
+            Self::Finished => "Finished",
+            Self::Failed => "Failed",
+            Self::Cancelled => "Cancelled",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewImportJobReq {
+    pub kind: ImportJobKind,
+
+    /// Path to calibre library or folder on server.
+    pub path: String,
+
+    pub move_files: bool,
+    pub allow_duplication: bool,
+
+    /// Number of worker threads, only used for calibre library.
+    pub jobs: usize,
+
+    /// Owner of imported files, keep unchanged if not set.
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub gid: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportJob {
+    pub id: i32,
+    pub kind: ImportJobKind,
+    pub path: String,
+
+    /// Id of import library, only set for calibre library.
+    pub library: Option<i32>,
+
+    pub status: ImportJobStatus,
+    pub total: u64,
+
+    /// Number of finished books, including failed ones.
+    pub done: u64,
+    pub failed: u64,
+
+    /// Error message if job failed.
+    pub message: Option<String>,
+    pub created: NaiveDateTime,
+}
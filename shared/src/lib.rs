@@ -8,19 +8,26 @@ pub mod books;
 pub mod books_meta;
 pub mod books_query;
 pub mod categories;
+pub mod custom_fields;
 pub mod file_formats;
 pub mod files;
+pub mod fsck;
 pub mod general_query;
 pub mod identifier_type;
 pub mod identifiers;
 pub mod images;
+pub mod import;
 pub mod languages;
 pub mod page;
 pub mod publishers;
 pub mod ratings;
 pub mod recursive_query;
+pub mod saved_searches;
+pub mod search_query;
 pub mod series;
 pub mod simple_search;
+pub mod suggest;
 pub mod tags;
+pub mod trash;
 pub mod user_tags;
 pub mod users;
@@ -0,0 +1,70 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::advanced_search::AdvancedSearchQuery;
+use crate::books_query::GetBooksOrder;
+use crate::page::{default_page_id, PageId};
+
+/// Query of saved search.
+///
+/// Page in advanced search conditions is ignored, books of saved search are
+/// paginated by request. They are sorted by request too, if order is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum SavedQuery {
+    /// Conditions of advanced search.
+    Advanced(Box<AdvancedSearchQuery>),
+
+    /// Query of search box, see `search_query` module.
+    Simple(String),
+}
+
+impl SavedQuery {
+    #[must_use]
+    pub fn desc(&self) -> String {
+        match self {
+            Self::Advanced(query) => query.desc(),
+            Self::Simple(query) => query.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub query: SavedQuery,
+    pub created: NaiveDateTime,
+    pub last_modified: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSavedSearch {
+    pub name: String,
+    pub query: SavedQuery,
+}
+
+/// Query of books in saved search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetSavedSearchBooksQuery {
+    #[serde(default = "default_page_id")]
+    pub page: PageId,
+
+    /// Use order of saved query if not set.
+    #[serde(default)]
+    pub order: Option<GetBooksOrder>,
+}
+
+impl Default for GetSavedSearchBooksQuery {
+    fn default() -> Self {
+        Self {
+            page: default_page_id(),
+            order: None,
+        }
+    }
+}
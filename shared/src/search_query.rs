@@ -0,0 +1,682 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+//! Query language of search box, similar to that of calibre.
+//!
+//! ```text
+//! author:"鲁迅" and tag:小说 and not format:pdf and rating:>=4
+//! ```
+//!
+//! - Bare words and quoted strings match title, authors, tags and other text of books.
+//! - `field:value` matches books whose field contains value, and `field:=value`
+//!   matches the whole value.
+//! - `rating` and `pubdate` fields accept `=`, `<`, `<=`, `>` and `>=` operators,
+//!   publishing date is written like `2000-12-31`.
+//! - Terms are combined with `and`, `or` and `not`. `and` may be omitted and binds
+//!   tighter than `or`, use parentheses to group terms.
+//! - At most [`MAX_TERMS`] terms and [`MAX_DEPTH`] levels of nested parentheses
+//!   and `not` are accepted.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Max number of terms in a query.
+pub const MAX_TERMS: usize = 64;
+
+/// Max nesting level of parentheses and `not`.
+pub const MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextField {
+    Title,
+    Author,
+    Publisher,
+    Tag,
+    Series,
+    Isbn,
+
+    /// Serial number of CLC category.
+    Category,
+
+    /// Language code.
+    Language,
+
+    /// File format name.
+    Format,
+
+    /// User tag of current user.
+    UserTag,
+}
+
+impl TextField {
+    /// Get field by name, plural names of calibre are accepted too.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "title" => Some(Self::Title),
+            "author" | "authors" => Some(Self::Author),
+            "publisher" => Some(Self::Publisher),
+            "tag" | "tags" => Some(Self::Tag),
+            "series" => Some(Self::Series),
+            "isbn" => Some(Self::Isbn),
+            "category" => Some(Self::Category),
+            "language" | "languages" => Some(Self::Language),
+            "format" | "formats" => Some(Self::Format),
+            "usertag" => Some(Self::UserTag),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Equal,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl CompareOp {
+    fn from_str(op: &str) -> Option<Self> {
+        match op {
+            "=" => Some(Self::Equal),
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessEqual),
+            ">" => Some(Self::Greater),
+            ">=" => Some(Self::GreaterEqual),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Term {
+    /// Keywords matched against search documents of books.
+    Keywords(String),
+
+    Text {
+        field: TextField,
+        value: String,
+        /// Match the whole value instead of substring.
+        exact: bool,
+    },
+
+    Rating {
+        op: CompareOp,
+        value: i32,
+    },
+
+    Pubdate {
+        op: CompareOp,
+        value: NaiveDate,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// Error in query, `start` and `end` are char offsets of the invalid part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(start: usize, end: usize, message: &str) -> Self {
+        Self {
+            start,
+            end,
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.message, self.start, self.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Colon,
+    LeftParen,
+    RightParen,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | ':' | '"')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let start = pos;
+        let kind = match chars[pos] {
+            c if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            '(' => {
+                pos += 1;
+                TokenKind::LeftParen
+            }
+            ')' => {
+                pos += 1;
+                TokenKind::RightParen
+            }
+            ':' => {
+                pos += 1;
+                TokenKind::Colon
+            }
+            '"' => {
+                pos += 1;
+                let mut value = String::new();
+                loop {
+                    match chars.get(pos) {
+                        None => {
+                            return Err(ParseError::new(start, pos, "Unterminated quoted string"))
+                        }
+                        Some('"') => {
+                            pos += 1;
+                            break;
+                        }
+                        Some('\\') if pos + 1 < chars.len() => {
+                            value.push(chars[pos + 1]);
+                            pos += 2;
+                        }
+                        Some(&c) => {
+                            value.push(c);
+                            pos += 1;
+                        }
+                    }
+                }
+                TokenKind::Quoted(value)
+            }
+            _ => {
+                while pos < chars.len() && !is_delimiter(chars[pos]) {
+                    pos += 1;
+                }
+                TokenKind::Word(chars[start..pos].iter().collect())
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: pos,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Value of `field:value` term, with optional compare operator.
+struct FieldValue {
+    op: Option<CompareOp>,
+    op_start: usize,
+    op_end: usize,
+    value: String,
+    start: usize,
+    end: usize,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    input_len: usize,
+    depth: usize,
+    terms: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .map_or(false, |token| is_keyword(token, keyword))
+    }
+
+    fn end_error(&self, message: &str) -> ParseError {
+        ParseError::new(self.input_len, self.input_len, message)
+    }
+
+    /// Enter a nested expression started by `token`.
+    fn enter(&mut self, token: &Token) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            let message = format!("Nested deeper than {MAX_DEPTH} levels");
+            return Err(ParseError::new(token.start, token.end, &message));
+        }
+        Ok(())
+    }
+
+    fn add_term(&mut self, start: usize, end: usize) -> Result<(), ParseError> {
+        self.terms += 1;
+        if self.terms > MAX_TERMS {
+            let message = format!("More than {MAX_TERMS} terms");
+            return Err(ParseError::new(start, end, &message));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            expr = Expr::Or(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_not()?;
+        loop {
+            match self.peek() {
+                None => break,
+                Some(token) if token.kind == TokenKind::RightParen || is_keyword(token, "or") => {
+                    break
+                }
+                Some(token) if is_keyword(token, "and") => self.pos += 1,
+                Some(_) => (),
+            }
+            let rhs = self.parse_not()?;
+            expr = Expr::And(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, ParseError> {
+        if self.peek_keyword("not") {
+            if let Some(token) = self.next_token() {
+                self.enter(&token)?;
+            }
+            let expr = self.parse_not()?;
+            self.depth -= 1;
+            return Ok(Expr::Not(Box::new(expr)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        let Some(token) = self.next_token() else {
+            return Err(self.end_error("Expected search term"));
+        };
+        match token.kind {
+            TokenKind::LeftParen => {
+                self.enter(&token)?;
+                let expr = self.parse_or()?;
+                match self.next_token() {
+                    Some(Token {
+                        kind: TokenKind::RightParen,
+                        ..
+                    }) => {
+                        self.depth -= 1;
+                        Ok(expr)
+                    }
+                    Some(next) => Err(ParseError::new(next.start, next.end, "Expected `)`")),
+                    None => Err(ParseError::new(token.start, token.end, "Unclosed `(`")),
+                }
+            }
+            TokenKind::RightParen => Err(ParseError::new(token.start, token.end, "Unexpected `)`")),
+            TokenKind::Colon => Err(ParseError::new(
+                token.start,
+                token.end,
+                "Expected field name before `:`",
+            )),
+            TokenKind::Quoted(value) => {
+                self.add_term(token.start, token.end)?;
+                Ok(Expr::Term(Term::Keywords(value)))
+            }
+            TokenKind::Word(word) => {
+                if ["and", "or"].iter().any(|k| word.eq_ignore_ascii_case(k)) {
+                    let message = format!("Expected search term before `{word}`");
+                    return Err(ParseError::new(token.start, token.end, &message));
+                }
+                self.add_term(token.start, token.end)?;
+                if matches!(self.peek(), Some(next) if next.kind == TokenKind::Colon) {
+                    self.pos += 1;
+                    let term = self.parse_field(&word, token.start, token.end)?;
+                    return Ok(Expr::Term(term));
+                }
+                Ok(Expr::Term(Term::Keywords(word)))
+            }
+        }
+    }
+
+    fn parse_field_value(&mut self, name: &str) -> Result<FieldValue, ParseError> {
+        let missing_value = format!("Expected value after `{name}:`");
+        let Some(token) = self.next_token() else {
+            return Err(self.end_error(&missing_value));
+        };
+        let word = match token.kind {
+            TokenKind::Quoted(value) => {
+                return Ok(FieldValue {
+                    op: None,
+                    op_start: token.start,
+                    op_end: token.start,
+                    value,
+                    start: token.start,
+                    end: token.end,
+                })
+            }
+            TokenKind::Word(word) => word,
+            _ => return Err(ParseError::new(token.start, token.end, &missing_value)),
+        };
+
+        let op_len = word
+            .chars()
+            .take_while(|c| matches!(c, '<' | '>' | '='))
+            .count();
+        let op_text: String = word.chars().take(op_len).collect();
+        let op_end = token.start + op_len;
+        let op = if op_len == 0 {
+            None
+        } else {
+            let op = CompareOp::from_str(&op_text).ok_or_else(|| {
+                let message = format!("Unknown operator `{op_text}`");
+                ParseError::new(token.start, op_end, &message)
+            })?;
+            Some(op)
+        };
+
+        let mut value = FieldValue {
+            op,
+            op_start: token.start,
+            op_end,
+            value: word.chars().skip(op_len).collect(),
+            start: op_end,
+            end: token.end,
+        };
+        if value.value.is_empty() {
+            // Quoted value after operator, like `tag:="science fiction"`.
+            match self.peek() {
+                Some(Token {
+                    kind: TokenKind::Quoted(quoted),
+                    start,
+                    end,
+                }) if *start == token.end => {
+                    value.value = quoted.clone();
+                    value.start = *start;
+                    value.end = *end;
+                    self.pos += 1;
+                }
+                _ => return Err(ParseError::new(token.start, token.end, &missing_value)),
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_field(&mut self, name: &str, start: usize, end: usize) -> Result<Term, ParseError> {
+        let field_name = name.to_lowercase();
+        let value = self.parse_field_value(name)?;
+        match field_name.as_str() {
+            "rating" => {
+                let rating = value.value.parse().map_err(|_err| {
+                    ParseError::new(value.start, value.end, "Rating shall be an integer")
+                })?;
+                Ok(Term::Rating {
+                    op: value.op.unwrap_or(CompareOp::Equal),
+                    value: rating,
+                })
+            }
+            "pubdate" => {
+                let date = NaiveDate::parse_from_str(&value.value, "%Y-%m-%d").map_err(|_err| {
+                    ParseError::new(
+                        value.start,
+                        value.end,
+                        "Publishing date shall be like 2000-12-31",
+                    )
+                })?;
+                Ok(Term::Pubdate {
+                    op: value.op.unwrap_or(CompareOp::Equal),
+                    value: date,
+                })
+            }
+            _ => {
+                let Some(field) = TextField::from_name(&field_name) else {
+                    let message = format!("Unknown field `{name}`");
+                    return Err(ParseError::new(start, end, &message));
+                };
+                let exact = match value.op {
+                    None => false,
+                    Some(CompareOp::Equal) => true,
+                    Some(_) => {
+                        let message = format!("Only `=` is supported by field `{name}`");
+                        return Err(ParseError::new(value.op_start, value.op_end, &message));
+                    }
+                };
+                Ok(Term::Text {
+                    field,
+                    value: value.value,
+                    exact,
+                })
+            }
+        }
+    }
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(&token.kind, TokenKind::Word(word) if word.eq_ignore_ascii_case(keyword))
+}
+
+/// Parse search query.
+///
+/// Returns None if query is empty.
+///
+/// # Errors
+/// Returns error with position if query is invalid.
+pub fn parse(input: &str) -> Result<Option<Expr>, ParseError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        input_len: input.chars().count(),
+        depth: 0,
+        terms: 0,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(ParseError::new(token.start, token.end, "Unexpected `)`"));
+    }
+    Ok(Some(expr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(value: &str) -> Expr {
+        Expr::Term(Term::Keywords(value.to_owned()))
+    }
+
+    fn text(field: TextField, value: &str, exact: bool) -> Expr {
+        Expr::Term(Term::Text {
+            field,
+            value: value.to_owned(),
+            exact,
+        })
+    }
+
+    fn and(lhs: Expr, rhs: Expr) -> Expr {
+        Expr::And(Box::new(lhs), Box::new(rhs))
+    }
+
+    fn or(lhs: Expr, rhs: Expr) -> Expr {
+        Expr::Or(Box::new(lhs), Box::new(rhs))
+    }
+
+    fn not(expr: Expr) -> Expr {
+        Expr::Not(Box::new(expr))
+    }
+
+    fn parse_ok(input: &str) -> Expr {
+        parse(input).unwrap().unwrap()
+    }
+
+    fn parse_err(input: &str) -> (usize, usize) {
+        let err = parse(input).unwrap_err();
+        (err.start, err.end)
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(parse(""), Ok(None));
+        assert_eq!(parse("  "), Ok(None));
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            parse_ok("a or b and c"),
+            or(keywords("a"), and(keywords("b"), keywords("c")))
+        );
+        assert_eq!(
+            parse_ok("a b or c"),
+            or(and(keywords("a"), keywords("b")), keywords("c"))
+        );
+        assert_eq!(
+            parse_ok("not a and b"),
+            and(not(keywords("a")), keywords("b"))
+        );
+        assert_eq!(
+            parse_ok("not a or not b"),
+            or(not(keywords("a")), not(keywords("b")))
+        );
+        assert_eq!(
+            parse_ok("(a OR b) AND NOT c"),
+            and(or(keywords("a"), keywords("b")), not(keywords("c")))
+        );
+        assert_eq!(parse_ok("not not a"), not(not(keywords("a"))));
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(parse_ok(r#""a b""#), keywords("a b"));
+        assert_eq!(parse_ok(r#""say \"hi\"""#), keywords(r#"say "hi""#));
+        assert_eq!(parse_ok(r#""back\\slash""#), keywords(r"back\slash"));
+        assert_eq!(parse_ok(r#""and""#), keywords("and"));
+        assert_eq!(
+            parse_ok(r#"author:"鲁迅 全集""#),
+            text(TextField::Author, "鲁迅 全集", false)
+        );
+        assert_eq!(
+            parse_ok(r#"tag:="science fiction""#),
+            text(TextField::Tag, "science fiction", true)
+        );
+        assert_eq!(parse_err(r#"a "bc"#), (2, 5));
+    }
+
+    #[test]
+    fn test_compare_operators() {
+        let cases = [
+            ("rating:4", CompareOp::Equal),
+            ("rating:=4", CompareOp::Equal),
+            ("rating:<4", CompareOp::Less),
+            ("rating:<=4", CompareOp::LessEqual),
+            ("rating:>4", CompareOp::Greater),
+            ("rating:>=4", CompareOp::GreaterEqual),
+        ];
+        for (input, op) in cases {
+            assert_eq!(parse_ok(input), Expr::Term(Term::Rating { op, value: 4 }));
+        }
+        assert_eq!(
+            parse_ok("pubdate:<2000-12-31"),
+            Expr::Term(Term::Pubdate {
+                op: CompareOp::Less,
+                value: NaiveDate::from_ymd_opt(2000, 12, 31).unwrap(),
+            })
+        );
+        assert_eq!(parse_ok("title:=abc"), text(TextField::Title, "abc", true));
+    }
+
+    #[test]
+    fn test_field_aliases() {
+        let cases = [
+            ("author", TextField::Author),
+            ("authors", TextField::Author),
+            ("Authors", TextField::Author),
+            ("tag", TextField::Tag),
+            ("tags", TextField::Tag),
+            ("language", TextField::Language),
+            ("languages", TextField::Language),
+            ("format", TextField::Format),
+            ("formats", TextField::Format),
+            ("usertag", TextField::UserTag),
+        ];
+        for (name, field) in cases {
+            assert_eq!(parse_ok(&format!("{name}:x")), text(field, "x", false));
+        }
+    }
+
+    #[test]
+    fn test_error_positions() {
+        // Positions are char offsets.
+        assert_eq!(parse_err("鲁迅 foo:x"), (3, 6));
+        assert_eq!(parse_err("rating:abc"), (7, 10));
+        assert_eq!(parse_err("rating:=>4"), (7, 9));
+        assert_eq!(parse_err("title:<x"), (6, 7));
+        assert_eq!(parse_err("pubdate:2000"), (8, 12));
+        assert_eq!(parse_err("(a or b"), (0, 1));
+        assert_eq!(parse_err("a)"), (1, 2));
+        assert_eq!(parse_err("a and"), (5, 5));
+        assert_eq!(parse_err("or a"), (0, 2));
+        assert_eq!(parse_err(":a"), (0, 1));
+        assert_eq!(parse_err("tag:"), (4, 4));
+    }
+
+    #[test]
+    fn test_limits() {
+        let nested = format!("{}a{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert!(parse(&nested).is_ok());
+        let nested = format!(
+            "{}a{}",
+            "(".repeat(MAX_DEPTH + 1),
+            ")".repeat(MAX_DEPTH + 1)
+        );
+        assert_eq!(parse_err(&nested), (MAX_DEPTH, MAX_DEPTH + 1));
+        let nested = "not ".repeat(MAX_DEPTH + 1) + "a";
+        assert_eq!(parse_err(&nested), (MAX_DEPTH * 4, MAX_DEPTH * 4 + 3));
+
+        let terms = vec!["a"; MAX_TERMS].join(" ");
+        assert!(parse(&terms).is_ok());
+        let terms = vec!["a"; MAX_TERMS + 1].join(" or ");
+        let start = MAX_TERMS * 5;
+        assert_eq!(parse_err(&terms), (start, start + 1));
+    }
+}
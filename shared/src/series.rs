@@ -7,7 +7,8 @@ use chrono::NaiveDateTime;
 use diesel::Queryable;
 use serde::{Deserialize, Serialize};
 
-use crate::page::Page;
+use crate::books_query::GetBooksOrder;
+use crate::page::{default_page_id, Page, PageId};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "diesel", derive(Queryable))]
@@ -36,3 +37,23 @@ pub struct SeriesAndBookList {
 pub struct NewSeries {
     pub name: String,
 }
+
+/// Query of books in series.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetSeriesBooksQuery {
+    #[serde(default = "default_page_id")]
+    pub page: PageId,
+
+    /// Sort by series index if not set.
+    #[serde(default)]
+    pub order: Option<GetBooksOrder>,
+}
+
+impl Default for GetSeriesBooksQuery {
+    fn default() -> Self {
+        Self {
+            page: default_page_id(),
+            order: None,
+        }
+    }
+}
@@ -11,8 +11,10 @@ use crate::page::{default_page_id, PageId};
 pub struct SimpleSearchQuery {
     #[serde(default = "default_page_id")]
     pub page: PageId,
-    #[serde(default = "GetBooksOrder::default")]
-    pub order: GetBooksOrder,
+
+    /// Sort by relevance if not set.
+    #[serde(default)]
+    pub order: Option<GetBooksOrder>,
 
     /// Search keyword.
     pub query: String,
@@ -23,7 +25,7 @@ impl Default for SimpleSearchQuery {
         Self {
             query: String::new(),
             page: default_page_id(),
-            order: GetBooksOrder::default(),
+            order: None,
         }
     }
 }
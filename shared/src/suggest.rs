@@ -0,0 +1,65 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuggestQuery {
+    /// Prefix of name to match.
+    #[serde(default)]
+    pub q: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SuggestionKind {
+    Book,
+    Author,
+    Publisher,
+    Series,
+    Tag,
+    Category,
+}
+
+impl SuggestionKind {
+    pub const ALL: [Self; 6] = [
+        Self::Book,
+        Self::Author,
+        Self::Publisher,
+        Self::Series,
+        Self::Tag,
+        Self::Category,
+    ];
+
+    #[must_use]
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Book => "Books",
+            Self::Author => "Authors",
+            Self::Publisher => "Publishers",
+            Self::Series => "Series",
+            Self::Tag => "Tags",
+            Self::Category => "Categories",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub id: i32,
+    pub name: String,
+
+    /// Number of books for authors, publishers, series, tags and categories,
+    /// and number of downloads for books.
+    pub count: i64,
+}
+
+/// Suggestions of the same kind, most frequent ones first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuggestionGroup {
+    pub kind: SuggestionKind,
+    pub list: Vec<Suggestion>,
+}
+
+/// Groups are sorted in order of `SuggestionKind::ALL`, and empty groups are skipped.
+pub type SuggestionList = Vec<SuggestionGroup>;
@@ -0,0 +1,28 @@
+// Copyright (c) 2023 Xu Shaohua <shaohua@biofan.org>. All rights reserved.
+// Use of this source is governed by GNU General Public License
+// that can be found in the LICENSE file.
+
+use chrono::NaiveDateTime;
+#[cfg(feature = "diesel")]
+use diesel::Queryable;
+use serde::{Deserialize, Serialize};
+
+/// Book moved to trash.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "diesel", derive(Queryable))]
+pub struct TrashBook {
+    pub id: i32,
+
+    /// Id of book before it was deleted.
+    pub book: i32,
+    pub title: String,
+
+    /// Directory of book files, relative to library root.
+    pub path: String,
+
+    /// Id of user who deleted this book.
+    pub user_id: i32,
+
+    /// Time when this book was deleted.
+    pub created: NaiveDateTime,
+}